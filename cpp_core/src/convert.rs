@@ -27,6 +27,41 @@ pub trait CastInto<T>: Sized {
     unsafe fn cast_into(self) -> T;
 }
 
+/// Creates a new C++ object from a Rust value.
+///
+/// Rust's coherence rules don't allow crates with C++ bindings to implement
+/// `From<V>` for `CppBox<T>` directly. Instead, they can implement `CppFrom<V>` for `T`,
+/// which enables `From<V>` for `CppBox<T>` if `V` is one of `bool`, `i32`, `u32`, `i64`,
+/// `u64`, `f32`, `f64`, `&str` or `String`.
+pub trait CppFrom<V>: CppDeletable {
+    /// Creates a new object containing `value`.
+    ///
+    /// ### Safety
+    ///
+    /// The implementation may invoke arbitrary foreign code, so no safety guarantees can be made.
+    unsafe fn cpp_from(value: V) -> CppBox<Self>;
+}
+
+macro_rules! impl_from_for_cpp_box {
+    ($($value:ty),*) => {
+        $(
+            impl<T: CppFrom<$value>> From<$value> for CppBox<T> {
+                fn from(value: $value) -> Self {
+                    unsafe { T::cpp_from(value) }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_for_cpp_box!(bool, i32, u32, i64, u64, f32, f64, String);
+
+impl<'a, T: CppFrom<&'a str>> From<&'a str> for CppBox<T> {
+    fn from(value: &'a str) -> Self {
+        unsafe { T::cpp_from(value) }
+    }
+}
+
 impl<T, U: CastFrom<T>> CastInto<U> for T {
     unsafe fn cast_into(self) -> U {
         U::cast_from(self)
@@ -105,3 +140,37 @@ where
         Self::cast_from(Ptr::from_raw(value))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::CppFrom;
+    use crate::{CppBox, CppDeletable};
+
+    struct Variant(String);
+
+    impl CppDeletable for Variant {
+        unsafe fn delete(&self) {
+            drop(Box::from_raw(self as *const Self as *mut Self));
+        }
+    }
+
+    impl CppFrom<i32> for Variant {
+        unsafe fn cpp_from(value: i32) -> CppBox<Variant> {
+            CppBox::from_raw(Box::into_raw(Box::new(Variant(value.to_string())))).unwrap()
+        }
+    }
+
+    impl<'a> CppFrom<&'a str> for Variant {
+        unsafe fn cpp_from(value: &'a str) -> CppBox<Variant> {
+            CppBox::from_raw(Box::into_raw(Box::new(Variant(value.into())))).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_from() {
+        let a: CppBox<Variant> = 42.into();
+        assert_eq!(a.0, "42");
+        let b = CppBox::<Variant>::from("text");
+        assert_eq!(b.0, "text");
+    }
+}
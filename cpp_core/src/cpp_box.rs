@@ -1,8 +1,8 @@
 use crate::ops::{Begin, BeginMut, End, EndMut, Increment, Indirection};
-use crate::vector_ops::{Data, DataMut, Size};
+use crate::vector_ops::{self, Data, DataMut, Size};
 use crate::{cpp_iter, CppIterator, DynamicCast, Ptr, Ref, StaticDowncast, StaticUpcast};
 use std::ops::Deref;
-use std::{fmt, mem, ptr, slice};
+use std::{fmt, mem, ptr};
 
 /// Objects that can be deleted using C++'s `delete` operator.
 ///
@@ -18,6 +18,33 @@ pub trait CppDeletable: Sized {
     unsafe fn delete(&self);
 }
 
+/// Objects that can be copied using their C++ copy constructor.
+///
+/// This trait is automatically implemented by `ritual` for class types
+/// that have a public copy constructor. It allows `CppBox<T>` to implement `Clone`.
+pub trait CppClone: CppDeletable {
+    /// Creates a copy of `self` using C++'s copy constructor.
+    ///
+    /// # Safety
+    ///
+    /// The caller must make sure `self` contains a valid pointer. This function
+    /// may invoke arbitrary foreign code, so no safety guarantees can be made.
+    unsafe fn cpp_clone(&self) -> CppBox<Self>;
+}
+
+/// Objects that can be created using their C++ default constructor.
+///
+/// This trait is automatically implemented by `ritual` for class types
+/// that have a public default constructor. It allows `CppBox<T>` to implement `Default`.
+pub trait CppDefault: CppDeletable {
+    /// Creates a new object using C++'s default constructor.
+    ///
+    /// # Safety
+    ///
+    /// This function may invoke arbitrary foreign code, so no safety guarantees can be made.
+    unsafe fn cpp_default() -> CppBox<Self>;
+}
+
 /// An owning pointer to a C++ object.
 ///
 /// `CppBox` is automatically used in places where C++ class objects are passed by value
@@ -236,7 +263,7 @@ where
     pub unsafe fn as_slice<'a>(&self) -> &'a [T] {
         let ptr = self.data();
         let size = self.size();
-        slice::from_raw_parts(ptr, size)
+        vector_ops::slice_from_data(ptr, size)
     }
 }
 
@@ -256,7 +283,7 @@ where
     pub unsafe fn as_mut_slice<'a>(&self) -> &'a mut [T] {
         let ptr = self.data_mut();
         let size = self.size();
-        slice::from_raw_parts_mut(ptr, size)
+        vector_ops::slice_from_data_mut(ptr, size)
     }
 }
 
@@ -317,16 +344,45 @@ impl<T: CppDeletable> Drop for CppBox<T> {
     }
 }
 
+/// Creates a copy of the stored object using its C++ copy constructor.
+impl<T: CppClone> Clone for CppBox<T> {
+    fn clone(&self) -> Self {
+        unsafe { (**self).cpp_clone() }
+    }
+}
+
+/// Creates an object using its C++ default constructor.
+impl<T: CppDefault> Default for CppBox<T> {
+    fn default() -> Self {
+        unsafe { T::cpp_default() }
+    }
+}
+
+/// Formats the address of the stored object.
+///
+/// Generated wrappers of C++ classes implement `Debug` using the C++ output stream operator
+/// if it's available. Dereference the box to format the object itself: `format!("{:?}", *value)`.
 impl<T: CppDeletable> fmt::Debug for CppBox<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "CppBox({:?})", self.0)
     }
 }
 
+/// Formats the stored object.
+impl<T: CppDeletable + fmt::Display> fmt::Display for CppBox<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{CppBox, CppDeletable, Ptr};
+    use crate::{CppBox, CppClone, CppDefault, CppDeletable, Ptr};
     use std::cell::RefCell;
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::HashSet;
+    use std::fmt;
+    use std::hash::{Hash, Hasher};
     use std::rc::Rc;
 
     struct Struct1 {
@@ -355,4 +411,129 @@ mod tests {
         }
         assert!(*value1.borrow() == 42);
     }
+
+    #[derive(Hash)]
+    struct Struct2(i32);
+
+    impl CppDeletable for Struct2 {
+        unsafe fn delete(&self) {}
+    }
+
+    impl fmt::Display for Struct2 {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "Struct2({})", self.0)
+        }
+    }
+
+    impl fmt::Debug for Struct2 {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            fmt::Display::fmt(self, f)
+        }
+    }
+
+    fn hash_of<T: Hash>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_display_debug_and_hash_use_object() {
+        let object = Struct2(5);
+        let object_box = unsafe { CppBox::new(Ptr::from_raw(&object)).unwrap() };
+        let object_ref = unsafe { object_box.as_ref() };
+        assert_eq!(object_box.to_string(), "Struct2(5)");
+        assert_eq!(object_ref.to_string(), "Struct2(5)");
+        assert_eq!(format!("{:?}", *object_box), "Struct2(5)");
+        assert_eq!(format!("{:?}", *object_ref), "Struct2(5)");
+        assert!(format!("{:?}", object_box).starts_with("CppBox("));
+        assert!(format!("{:?}", object_ref).starts_with("Ref("));
+        assert_eq!(hash_of(&object_box), hash_of(&object));
+        assert_eq!(hash_of(&object_ref), hash_of(&object));
+    }
+
+    impl PartialEq<CppBox<Struct2>> for Struct2 {
+        fn eq(&self, other: &CppBox<Struct2>) -> bool {
+            self.0 == (**other).0
+        }
+    }
+
+    #[test]
+    fn test_hashable_box_is_map_key() {
+        let object1 = Struct2(1);
+        let object2 = Struct2(2);
+        let mut set = HashSet::new();
+        unsafe {
+            set.insert(CppBox::new(Ptr::from_raw(&object1)).unwrap());
+            set.insert(CppBox::new(Ptr::from_raw(&object2)).unwrap());
+            set.insert(CppBox::new(Ptr::from_raw(&object1)).unwrap());
+        }
+        assert_eq!(set.len(), 2);
+    }
+
+    /// Returns true if the type implements `Eq`.
+    macro_rules! is_eq {
+        ($type:ty) => {{
+            #[allow(dead_code)]
+            trait NotEq {
+                const IS_EQ: bool = false;
+            }
+            impl<T> NotEq for T {}
+
+            struct Check<T>(std::marker::PhantomData<T>);
+            #[allow(dead_code)]
+            impl<T: Eq> Check<T> {
+                const IS_EQ: bool = true;
+            }
+            <Check<$type>>::IS_EQ
+        }};
+    }
+
+    /// A type with inexact equality and without a C++ hash function, like `QPointF`.
+    struct PointF(f64);
+
+    impl CppDeletable for PointF {
+        unsafe fn delete(&self) {}
+    }
+
+    impl PartialEq<CppBox<PointF>> for PointF {
+        fn eq(&self, other: &CppBox<PointF>) -> bool {
+            (self.0 - (**other).0).abs() < 1e-12
+        }
+    }
+
+    #[test]
+    fn test_eq_requires_hash() {
+        assert!(is_eq!(CppBox<Struct2>));
+        assert!(!is_eq!(CppBox<PointF>));
+    }
+
+    struct Struct3(i32);
+
+    impl CppDeletable for Struct3 {
+        unsafe fn delete(&self) {
+            drop(Box::from_raw(self as *const Self as *mut Self));
+        }
+    }
+
+    impl CppClone for Struct3 {
+        unsafe fn cpp_clone(&self) -> CppBox<Self> {
+            CppBox::from_raw(Box::into_raw(Box::new(Struct3(self.0)))).unwrap()
+        }
+    }
+
+    impl CppDefault for Struct3 {
+        unsafe fn cpp_default() -> CppBox<Self> {
+            CppBox::from_raw(Box::into_raw(Box::new(Struct3(7)))).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_clone_and_default_use_constructors() {
+        let object1: CppBox<Struct3> = Default::default();
+        assert_eq!((*object1).0, 7);
+        let object2 = object1.clone();
+        assert_eq!((*object2).0, 7);
+        assert_ne!(object1.as_raw_ptr(), object2.as_raw_ptr());
+    }
 }
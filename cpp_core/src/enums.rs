@@ -0,0 +1,31 @@
+use std::error::Error;
+use std::fmt;
+
+/// Error returned by `FromStr` implementations of native Rust enums
+/// generated for closed C++ enums.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseEnumError {
+    name: String,
+}
+
+impl ParseEnumError {
+    /// Creates an error indicating that `name` is not a valid variant name.
+    pub fn new(name: &str) -> Self {
+        ParseEnumError {
+            name: name.to_string(),
+        }
+    }
+
+    /// Returns the string that failed to parse.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl fmt::Display for ParseEnumError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown enum variant: {:?}", self.name)
+    }
+}
+
+impl Error for ParseEnumError {}
@@ -1,5 +1,8 @@
-use crate::ops::{Decrement, Increment, Indirection};
+use crate::ops::{Decrement, Increment, Indirection, Key, Value};
+use crate::vector_ops::{At, Size};
 use crate::{CppBox, CppDeletable, Ref};
+use std::convert::TryFrom;
+use std::marker::PhantomData;
 use std::os::raw::c_char;
 
 /// `Iterator` and `DoubleEndedIterator` backed by C++ iterators.
@@ -73,6 +76,162 @@ where
     }
 }
 
+impl<T1, T2> CppIterator<T1, T2>
+where
+    T1: CppDeletable,
+    T2: CppDeletable,
+{
+    /// Converts this iterator into an iterator over `(key, value)` pairs
+    /// produced by `key()` and `value()` functions of the C++ iterator,
+    /// as provided by associative containers (e.g. Qt's `QMap` and `QHash`).
+    pub fn key_value(self) -> CppKeyValueIterator<T1, T2> {
+        CppKeyValueIterator(self)
+    }
+}
+
+/// `Iterator` and `DoubleEndedIterator` over `(key, value)` pairs
+/// backed by C++ iterators.
+///
+/// This object is produced by `CppIterator::key_value`.
+pub struct CppKeyValueIterator<T1, T2>(CppIterator<T1, T2>)
+where
+    T1: CppDeletable,
+    T2: CppDeletable;
+
+impl<T1, T2> Iterator for CppKeyValueIterator<T1, T2>
+where
+    T1: CppDeletable + PartialEq<Ref<T2>> + Key + Value + Increment,
+    T2: CppDeletable,
+{
+    type Item = (<T1 as Key>::Output, <T1 as Value>::Output);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        unsafe {
+            let iter = &mut self.0;
+            if iter.begin == iter.end.as_ref() {
+                None
+            } else {
+                let item = (iter.begin.key(), iter.begin.value());
+                let inner = &mut *iter.begin.as_mut_raw_ptr();
+                inner.inc();
+                Some(item)
+            }
+        }
+    }
+}
+
+impl<T1, T2> DoubleEndedIterator for CppKeyValueIterator<T1, T2>
+where
+    T1: CppDeletable + PartialEq<Ref<T2>> + Key + Value + Increment,
+    T2: CppDeletable
+        + Decrement
+        + Key<Output = <T1 as Key>::Output>
+        + Value<Output = <T1 as Value>::Output>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        unsafe {
+            let iter = &mut self.0;
+            if iter.begin == iter.end.as_ref() {
+                None
+            } else {
+                let inner = &mut *iter.end.as_mut_raw_ptr();
+                inner.dec();
+                Some((iter.end.key(), iter.end.value()))
+            }
+        }
+    }
+}
+
+/// `Iterator`, `DoubleEndedIterator` and `ExactSizeIterator` over elements
+/// of a C++ collection that provides `size()` and `at()` functions.
+///
+/// Use `cpp_index_iter` function to construct it.
+pub struct CppIndexIterator<T, I> {
+    collection: Ref<T>,
+    begin: usize,
+    end: usize,
+    _marker: PhantomData<fn(I)>,
+}
+
+/// Constructs a Rust-style iterator over all elements of a C++ collection
+/// that provides `size()` and `at()` functions.
+///
+/// The size of the collection is retrieved once, so the collection
+/// must not be resized while the iterator is in use.
+///
+/// ### Safety
+///
+/// `collection` must be valid. It's not possible to make any guarantees about safety, since
+/// `CppIndexIterator` will call arbitrary C++ library code when used.
+pub unsafe fn cpp_index_iter<T, I>(collection: Ref<T>) -> CppIndexIterator<T, I>
+where
+    T: At<I> + Size,
+{
+    CppIndexIterator {
+        collection,
+        begin: 0,
+        end: collection.size(),
+        _marker: PhantomData,
+    }
+}
+
+impl<T, I> CppIndexIterator<T, I>
+where
+    T: At<I>,
+    I: TryFrom<usize>,
+{
+    fn get(&self, index: usize) -> <T as At<I>>::Output {
+        let index = I::try_from(index)
+            .unwrap_or_else(|_| panic!("index {} is out of range of the index type", index));
+        unsafe { self.collection.at(index) }
+    }
+}
+
+impl<T, I> Iterator for CppIndexIterator<T, I>
+where
+    T: At<I>,
+    I: TryFrom<usize>,
+{
+    type Item = <T as At<I>>::Output;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.begin == self.end {
+            None
+        } else {
+            let value = self.get(self.begin);
+            self.begin += 1;
+            Some(value)
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.begin;
+        (len, Some(len))
+    }
+}
+
+impl<T, I> DoubleEndedIterator for CppIndexIterator<T, I>
+where
+    T: At<I>,
+    I: TryFrom<usize>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.begin == self.end {
+            None
+        } else {
+            self.end -= 1;
+            Some(self.get(self.end))
+        }
+    }
+}
+
+impl<T, I> ExactSizeIterator for CppIndexIterator<T, I>
+where
+    T: At<I>,
+    I: TryFrom<usize>,
+{
+}
+
 /// A convenience trait that provides `end_ptr()` method for slices.
 pub trait EndPtr {
     /// Type of item.
@@ -94,3 +253,120 @@ impl<'a> EndPtr for &'a str {
         unsafe { self.as_ptr().add(self.len()) as *const c_char }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::ops::{Decrement, Increment, Key, Value};
+    use crate::vector_ops::{At, Size};
+    use crate::{cpp_index_iter, cpp_iter, CppBox, CppDeletable, Ptr, Ref};
+    use std::cell::Cell;
+
+    struct Vector(Vec<i32>);
+
+    impl CppDeletable for Vector {
+        unsafe fn delete(&self) {}
+    }
+
+    impl Size for Vector {
+        unsafe fn size(&self) -> usize {
+            self.0.len()
+        }
+    }
+
+    impl At<i32> for Vector {
+        type Output = Ref<i32>;
+
+        unsafe fn at(&self, index: i32) -> Ref<i32> {
+            Ref::from_raw(&self.0[index as usize]).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_index_iterator() {
+        let vector = Vector(vec![1, 2, 3]);
+        let vector_ref = unsafe { Ref::from_raw(&vector).unwrap() };
+        let iter = unsafe { cpp_index_iter::<_, i32>(vector_ref) };
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.map(|x| *x).collect::<Vec<_>>(), vec![1, 2, 3]);
+        let iter = unsafe { cpp_index_iter::<_, i32>(vector_ref) };
+        assert_eq!(iter.rev().map(|x| *x).collect::<Vec<_>>(), vec![3, 2, 1]);
+
+        assert_eq!(vector_ref[1], 2);
+        let vector_box = unsafe { CppBox::new(Ptr::from_raw(&vector)).unwrap() };
+        assert_eq!(vector_box[2], 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn test_index_out_of_bounds() {
+        let vector = Vector(vec![1, 2, 3]);
+        let vector_ref = unsafe { Ref::from_raw(&vector).unwrap() };
+        let _ = vector_ref[3];
+    }
+
+    struct MapIterator {
+        items: Vec<(i32, char)>,
+        index: Cell<usize>,
+    }
+
+    impl CppDeletable for MapIterator {
+        unsafe fn delete(&self) {
+            drop(Box::from_raw(self as *const Self as *mut Self));
+        }
+    }
+
+    impl PartialEq<Ref<MapIterator>> for MapIterator {
+        fn eq(&self, other: &Ref<MapIterator>) -> bool {
+            self.index == other.index
+        }
+    }
+
+    impl Increment for MapIterator {
+        type Output = ();
+        unsafe fn inc(&self) {
+            self.index.set(self.index.get() + 1);
+        }
+    }
+
+    impl Decrement for MapIterator {
+        type Output = ();
+        unsafe fn dec(&self) {
+            self.index.set(self.index.get() - 1);
+        }
+    }
+
+    impl Key for MapIterator {
+        type Output = i32;
+        unsafe fn key(&self) -> i32 {
+            self.items[self.index.get()].0
+        }
+    }
+
+    impl Value for MapIterator {
+        type Output = char;
+        unsafe fn value(&self) -> char {
+            self.items[self.index.get()].1
+        }
+    }
+
+    fn map_iterator(items: &[(i32, char)], index: usize) -> CppBox<MapIterator> {
+        let iterator = Box::new(MapIterator {
+            items: items.to_vec(),
+            index: Cell::new(index),
+        });
+        unsafe { CppBox::from_raw(Box::into_raw(iterator)).unwrap() }
+    }
+
+    #[test]
+    fn test_key_value_iterator() {
+        let items = vec![(1, 'a'), (2, 'b'), (3, 'c')];
+        let iter = unsafe { cpp_iter(map_iterator(&items, 0), map_iterator(&items, 3)) };
+        assert_eq!(iter.key_value().collect::<Vec<_>>(), items);
+
+        let iter = unsafe { cpp_iter(map_iterator(&items, 0), map_iterator(&items, 3)) };
+        assert_eq!(
+            iter.key_value().rev().collect::<Vec<_>>(),
+            vec![(3, 'c'), (2, 'b'), (1, 'a')]
+        );
+    }
+}
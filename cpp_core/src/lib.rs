@@ -12,6 +12,11 @@
 //! - `Ptr`: possibly owned, possibly null (correspond to C++ pointers)
 //! - `Ref`: not owned, non-null (correspond to C++ references)
 //!
+//! In addition, `SharedPtr` holds a C++ shared pointer (`std::shared_ptr` or `QSharedPointer`)
+//! returned by value. It dereferences to the managed object and is cloned
+//! by incrementing the reference count. Functions returning `std::unique_ptr<T>`
+//! release the object and return `CppBox<T>` instead.
+//!
 //! Accessing objects through these pointers is inherently unsafe,
 //! as the compiler cannot make any guarantee about the validity of pointers to objects
 //! managed by C++ libraries.
@@ -29,6 +34,16 @@
 //!
 //! If the object provides an iterator interface through `begin()` and `end()` functions,
 //! pointer types will implement `IntoIterator`, so you can iterate on them directly.
+//! Collections providing `size()` and `at()` functions can be indexed with `[]`
+//! through `CppBox` and `Ref`, and `cpp_index_iter` creates an `ExactSizeIterator`
+//! over their elements. Iterators of associative containers providing `key()` and `value()`
+//! functions can be converted to iterators over `(key, value)` pairs
+//! using `CppIterator::key_value`.
+//!
+//! Contiguous collections of primitive types providing `data()` and `size()` functions
+//! (e.g. `QVector<int>` or `std::vector<float>`) can be viewed as Rust slices using
+//! `as_slice` and `as_mut_slice` methods of pointer types. Collections providing
+//! `append()` or `push_back()` implement `FromIterator` (through `CppBox`) and `Extend`.
 //!
 //! # Casts
 //!
@@ -50,26 +65,45 @@
 //! or even `Ptr<DerivedClass>` (where `DerivedClass` inherits `SomeClass`). You can also
 //! pass a null pointer object (`NullPtr`) if you don't have a value
 //! (`Ptr::null()` is also an option but it can cause type inference issues).
+//!
+//! # Serialization
+//!
+//! When the `serde` feature is enabled, `CppBox<T>` implements `serde`'s `Serialize`
+//! if `T` implements it, and `Deserialize` if `T` implements `CppDeserialize`.
+//! The `serde` crate is re-exported as `cpp_core::serde`, so that crates with C++ bindings
+//! can provide implementations without depending on it directly.
 
 #![deny(missing_docs)]
 
 pub use crate::casts::{DynamicCast, StaticDowncast, StaticUpcast};
-pub use crate::convert::{CastFrom, CastInto};
-pub use crate::cpp_box::{CppBox, CppDeletable};
-pub use crate::iterator::{cpp_iter, CppIterator, EndPtr};
+pub use crate::convert::{CastFrom, CastInto, CppFrom};
+pub use crate::cpp_box::{CppBox, CppClone, CppDefault, CppDeletable};
+pub use crate::enums::ParseEnumError;
+pub use crate::iterator::{
+    cpp_index_iter, cpp_iter, CppIndexIterator, CppIterator, CppKeyValueIterator, EndPtr,
+};
 pub use crate::ptr::{NullPtr, Ptr};
 pub use crate::ref_::Ref;
+#[cfg(feature = "serde")]
+pub use crate::serde_impls::CppDeserialize;
+pub use crate::shared_ptr::SharedPtr;
 pub use libc::wchar_t;
+#[cfg(feature = "serde")]
+pub use serde;
 
 mod casts;
 pub mod cmp;
 mod convert;
 mod cpp_box;
+mod enums;
 mod iterator;
 pub mod ops;
 mod ops_impls;
 mod ptr;
 mod ref_;
+#[cfg(feature = "serde")]
+mod serde_impls;
+mod shared_ptr;
 pub mod vector_ops;
 
 // C++ doesn't guarantee these types to be exactly u16 and u32,
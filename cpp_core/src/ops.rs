@@ -101,3 +101,46 @@ pub trait EndMut {
     /// may invoke arbitrary foreign code, so no safety guarantees can be made.
     unsafe fn end_mut(&self) -> Self::Output;
 }
+
+/// Represents `key() const` function of an associative container's iterator.
+pub trait Key {
+    /// Output type.
+    type Output;
+
+    /// Returns the key of the current item.
+    ///
+    /// # Safety
+    ///
+    /// The caller must make sure `self` contains a valid pointer. This function
+    /// may invoke arbitrary foreign code, so no safety guarantees can be made.
+    unsafe fn key(&self) -> Self::Output;
+}
+
+/// Represents `value() const` function of an associative container's iterator.
+pub trait Value {
+    /// Output type.
+    type Output;
+
+    /// Returns the value of the current item.
+    ///
+    /// # Safety
+    ///
+    /// The caller must make sure `self` contains a valid pointer. This function
+    /// may invoke arbitrary foreign code, so no safety guarantees can be made.
+    unsafe fn value(&self) -> Self::Output;
+}
+
+/// Represents `get() const` function of a C++ smart pointer
+/// (`data() const` in case of `QSharedPointer`).
+pub trait Get {
+    /// Output type.
+    type Output;
+
+    /// Returns a pointer to the managed object.
+    ///
+    /// # Safety
+    ///
+    /// The caller must make sure `self` contains a valid pointer. This function
+    /// may invoke arbitrary foreign code, so no safety guarantees can be made.
+    unsafe fn get(&self) -> Self::Output;
+}
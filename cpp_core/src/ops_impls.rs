@@ -1,9 +1,13 @@
 use crate::{
     cmp::{Ge, Gt, Le, Lt},
-    CppBox, CppDeletable, Ptr, Ref,
+    vector_ops::{At, Push, Size},
+    CppBox, CppDefault, CppDeletable, Ptr, Ref,
 };
 use std::cmp::{Ordering, PartialEq, PartialOrd};
-use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Rem, Shl, Shr, Sub};
+use std::convert::TryFrom;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Index, Mul, Rem, Shl, Shr, Sub};
 
 macro_rules! define_op {
     ($trait1:ident, $func:ident) => {
@@ -147,3 +151,105 @@ where
         unsafe { (**self).ge(other) }
     }
 }
+
+/// Allows to use `CppBox<T>` as a `HashMap` key if `T` implements
+/// the C++ equality operator and `T` is comparable to `CppBox<T>`.
+///
+/// `Hash` is only implemented for types that have a C++ hash function,
+/// so the `Hash` bound excludes types with inexact equality, e.g. `QPointF`.
+impl<T: CppDeletable + Hash + PartialEq<CppBox<T>>> Eq for CppBox<T> {}
+
+impl<T: Hash + PartialEq<Ref<T>>> Eq for Ref<T> {}
+
+impl<T: CppDeletable + Hash> Hash for CppBox<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl<T: Hash> Hash for Ref<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+/// Returns the element of `collection` at `index`.
+/// Panics if `index` is out of bounds.
+unsafe fn checked_at<T, I, U>(collection: &T, index: I) -> &U
+where
+    T: At<I, Output = Ref<U>> + Size,
+    I: Copy,
+    usize: TryFrom<I>,
+{
+    let size = collection.size();
+    match usize::try_from(index) {
+        Ok(index) if index < size => {}
+        _ => panic!("index out of bounds: the size is {}", size),
+    }
+    &*collection.at(index).as_raw_ptr()
+}
+
+/// Provides indexing of C++ collections with `size()` and `at()` functions.
+impl<T, I, U> Index<I> for CppBox<T>
+where
+    T: CppDeletable + At<I, Output = Ref<U>> + Size,
+    I: Copy,
+    usize: TryFrom<I>,
+{
+    type Output = U;
+
+    fn index(&self, index: I) -> &U {
+        unsafe { checked_at(&**self, index) }
+    }
+}
+
+/// Provides indexing of C++ collections with `size()` and `at()` functions.
+impl<T, I, U> Index<I> for Ref<T>
+where
+    T: At<I, Output = Ref<U>> + Size,
+    I: Copy,
+    usize: TryFrom<I>,
+{
+    type Output = U;
+
+    fn index(&self, index: I) -> &U {
+        unsafe { checked_at(&**self, index) }
+    }
+}
+
+/// Creates a C++ collection using its default constructor and
+/// appends all elements of the iterator.
+impl<T, U> FromIterator<U> for CppBox<T>
+where
+    T: CppDefault + Push<U>,
+{
+    fn from_iter<I: IntoIterator<Item = U>>(iter: I) -> Self {
+        let collection = unsafe { T::cpp_default() };
+        for item in iter {
+            unsafe { Push::push(&*collection, item) };
+        }
+        collection
+    }
+}
+
+impl<T, U> Extend<U> for CppBox<T>
+where
+    T: CppDeletable + Push<U>,
+{
+    fn extend<I: IntoIterator<Item = U>>(&mut self, iter: I) {
+        for item in iter {
+            unsafe { Push::push(&**self, item) };
+        }
+    }
+}
+
+impl<T, U> Extend<U> for Ref<T>
+where
+    T: Push<U>,
+{
+    fn extend<I: IntoIterator<Item = U>>(&mut self, iter: I) {
+        for item in iter {
+            unsafe { Push::push(&**self, item) };
+        }
+    }
+}
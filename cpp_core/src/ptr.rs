@@ -1,10 +1,10 @@
 use crate::ops::{Begin, BeginMut, End, EndMut, Increment, Indirection};
-use crate::vector_ops::{Data, DataMut, Size};
+use crate::vector_ops::{self, Data, DataMut, Size};
 use crate::{
     cpp_iter, CppBox, CppDeletable, CppIterator, DynamicCast, Ref, StaticDowncast, StaticUpcast,
 };
+use std::fmt;
 use std::ops::Deref;
-use std::{fmt, slice};
 
 /// A pointer to a C++ object (similar to a C++ pointer).
 ///
@@ -184,7 +184,7 @@ where
     pub unsafe fn as_slice<'a>(self) -> &'a [T] {
         let ptr = self.data();
         let size = self.size();
-        slice::from_raw_parts(ptr, size)
+        vector_ops::slice_from_data(ptr, size)
     }
 }
 
@@ -204,7 +204,7 @@ where
     pub unsafe fn as_mut_slice<'a>(self) -> &'a mut [T] {
         let ptr = self.data_mut();
         let size = self.size();
-        slice::from_raw_parts_mut(ptr, size)
+        vector_ops::slice_from_data_mut(ptr, size)
     }
 }
 
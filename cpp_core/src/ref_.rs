@@ -1,10 +1,10 @@
 use crate::ops::{Begin, BeginMut, End, EndMut, Increment, Indirection};
-use crate::vector_ops::{Data, DataMut, Size};
+use crate::vector_ops::{self, Data, DataMut, Size};
 use crate::{
     cpp_iter, CppBox, CppDeletable, CppIterator, DynamicCast, Ptr, StaticDowncast, StaticUpcast,
 };
 use std::ops::Deref;
-use std::{fmt, ptr, slice};
+use std::{fmt, ptr};
 
 /// A non-null, mutable pointer to a C++ object (similar to a C++ reference).
 ///
@@ -44,12 +44,22 @@ impl<T> Clone for Ref<T> {
 /// Creates another pointer to the same object.
 impl<T> Copy for Ref<T> {}
 
+/// Formats the address of the pointed object.
+///
+/// Dereference the pointer to format the object itself: `format!("{:?}", *value)`.
 impl<T> fmt::Debug for Ref<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Ref({:?})", self.0)
     }
 }
 
+/// Formats the pointed object.
+impl<T: fmt::Display> fmt::Display for Ref<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
 impl<T> Ref<T> {
     /// Creates a `Ref` from a `Ptr`. Returns `None` if `ptr` is null.
     ///
@@ -178,7 +188,7 @@ where
     pub unsafe fn as_slice<'a>(self) -> &'a [T] {
         let ptr = self.data();
         let size = self.size();
-        slice::from_raw_parts(ptr, size)
+        vector_ops::slice_from_data(ptr, size)
     }
 }
 
@@ -198,7 +208,7 @@ where
     pub unsafe fn as_mut_slice<'a>(self) -> &'a mut [T] {
         let ptr = self.data_mut();
         let size = self.size();
-        slice::from_raw_parts_mut(ptr, size)
+        vector_ops::slice_from_data_mut(ptr, size)
     }
 }
 
@@ -0,0 +1,67 @@
+use crate::{CppBox, CppDeletable};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Creates a new C++ object from the output of a `serde` deserializer.
+///
+/// Rust's coherence rules don't allow crates with C++ bindings to implement
+/// `Deserialize` for `CppBox<T>` directly. Instead, they can implement `CppDeserialize`
+/// for `T`, which enables `Deserialize` for `CppBox<T>`. `Serialize` for `CppBox<T>`
+/// is available if `T` implements `Serialize`.
+pub trait CppDeserialize<'de>: CppDeletable {
+    /// Deserializes a new object.
+    fn cpp_deserialize<D>(deserializer: D) -> Result<CppBox<Self>, D::Error>
+    where
+        D: Deserializer<'de>;
+}
+
+impl<T: CppDeletable + Serialize> Serialize for CppBox<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        (**self).serialize(serializer)
+    }
+}
+
+impl<'de, T: CppDeserialize<'de>> Deserialize<'de> for CppBox<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::cpp_deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CppDeserialize;
+    use crate::{CppBox, CppDeletable};
+    use serde::de::value::{Error, StrDeserializer};
+    use serde::de::IntoDeserializer;
+    use serde::{Deserialize, Deserializer};
+
+    struct Text(String);
+
+    impl CppDeletable for Text {
+        unsafe fn delete(&self) {
+            drop(Box::from_raw(self as *const Self as *mut Self));
+        }
+    }
+
+    impl<'de> CppDeserialize<'de> for Text {
+        fn cpp_deserialize<D>(deserializer: D) -> Result<CppBox<Text>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let value = String::deserialize(deserializer)?;
+            unsafe { Ok(CppBox::from_raw(Box::into_raw(Box::new(Text(value)))).unwrap()) }
+        }
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let deserializer: StrDeserializer<'_, Error> = "text".into_deserializer();
+        let value = CppBox::<Text>::deserialize(deserializer).unwrap();
+        assert_eq!(value.0, "text");
+    }
+}
@@ -0,0 +1,187 @@
+use crate::ops::Get;
+use crate::{CppBox, CppClone, CppDeletable, Ptr, Ref};
+use std::fmt;
+use std::ops::Deref;
+
+/// An owning pointer to a C++ shared pointer object
+/// (e.g. `std::shared_ptr<T>` or `QSharedPointer<T>`).
+///
+/// `SharedPtr` is automatically used in return values of functions that return
+/// a shared pointer by value. `P` is the shared pointer type, and `SharedPtr<P>`
+/// dereferences to the managed object.
+///
+/// Cloning a `SharedPtr` uses the C++ copy constructor of the shared pointer,
+/// so it increments the reference count instead of copying the managed object.
+/// Dropping a `SharedPtr` deletes the C++ shared pointer object,
+/// which decrements the reference count.
+pub struct SharedPtr<P: CppDeletable>(CppBox<P>);
+
+impl<P: CppDeletable> SharedPtr<P> {
+    /// Encapsulates the shared pointer object into a `SharedPtr`.
+    ///
+    /// ### Safety
+    ///
+    /// The pointer must point to a shared pointer object that can be
+    /// safely deleted using C++'s `delete` operator.
+    /// The object must not be deleted by other means while `SharedPtr` exists.
+    pub unsafe fn new(shared_ptr: CppBox<P>) -> Self {
+        SharedPtr(shared_ptr)
+    }
+
+    /// Encapsulates the shared pointer object into a `SharedPtr`.
+    /// Returns `None` if the pointer is null.
+    ///
+    /// See `SharedPtr::new` for more information.
+    ///
+    /// ### Safety
+    ///
+    /// The pointer must point to a shared pointer object that can be
+    /// safely deleted using C++'s `delete` operator.
+    /// The object must not be deleted by other means while `SharedPtr` exists.
+    pub unsafe fn from_raw(ptr: *const P) -> Option<Self> {
+        CppBox::from_raw(ptr).map(SharedPtr)
+    }
+
+    /// Returns a reference to the shared pointer object.
+    ///
+    /// ### Safety
+    ///
+    /// This operation is safe as long as `self` is valid.
+    pub unsafe fn as_shared_ptr(&self) -> Ref<P> {
+        self.0.as_ref()
+    }
+
+    /// Destroys the `SharedPtr` without deleting the shared pointer object
+    /// and returns an owning pointer to it.
+    pub fn into_box(self) -> CppBox<P> {
+        self.0
+    }
+
+    /// Destroys the `SharedPtr` without deleting the shared pointer object
+    /// and returns a raw pointer to it. The caller of the function becomes the owner of
+    /// the object and should ensure that the object will be deleted at some point.
+    pub fn into_raw_ptr(self) -> *mut P {
+        self.0.into_raw_ptr()
+    }
+}
+
+impl<P, T> SharedPtr<P>
+where
+    P: CppDeletable + Get<Output = Ptr<T>>,
+{
+    /// Returns a pointer to the managed object.
+    ///
+    /// ### Safety
+    ///
+    /// This operation is safe as long as `self` is valid.
+    pub unsafe fn get(&self) -> Ptr<T> {
+        self.0.get()
+    }
+
+    /// Returns true if the shared pointer doesn't manage an object.
+    ///
+    /// ### Safety
+    ///
+    /// This operation is safe as long as `self` is valid.
+    pub unsafe fn is_null(&self) -> bool {
+        self.get().is_null()
+    }
+}
+
+/// Creates another shared pointer to the same object
+/// using the shared pointer's C++ copy constructor.
+impl<P: CppClone> Clone for SharedPtr<P> {
+    fn clone(&self) -> Self {
+        SharedPtr(unsafe { self.0.cpp_clone() })
+    }
+}
+
+/// Allows to call member functions of the managed object.
+///
+/// Panics if the shared pointer is null.
+impl<P, T> Deref for SharedPtr<P>
+where
+    P: CppDeletable + Get<Output = Ptr<T>>,
+{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        let ptr = unsafe { self.get() };
+        if ptr.is_null() {
+            panic!("attempted to deref a null SharedPtr<T>");
+        }
+        unsafe { &*ptr.as_raw_ptr() }
+    }
+}
+
+impl<P: CppDeletable> fmt::Debug for SharedPtr<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SharedPtr({:?})", self.0.as_raw_ptr())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::ops::Get;
+    use crate::{CppBox, CppClone, CppDeletable, Ptr, SharedPtr};
+    use std::rc::Rc;
+
+    struct SharedPointer(Option<Rc<i32>>);
+
+    impl CppDeletable for SharedPointer {
+        unsafe fn delete(&self) {
+            drop(Box::from_raw(self as *const Self as *mut Self));
+        }
+    }
+
+    impl CppClone for SharedPointer {
+        unsafe fn cpp_clone(&self) -> CppBox<Self> {
+            let object = Box::new(SharedPointer(self.0.clone()));
+            CppBox::from_raw(Box::into_raw(object)).unwrap()
+        }
+    }
+
+    impl Get for SharedPointer {
+        type Output = Ptr<i32>;
+
+        unsafe fn get(&self) -> Ptr<i32> {
+            match &self.0 {
+                Some(value) => Ptr::from_raw(&**value),
+                None => Ptr::null(),
+            }
+        }
+    }
+
+    fn shared_ptr(value: Option<Rc<i32>>) -> SharedPtr<SharedPointer> {
+        let object = Box::new(SharedPointer(value));
+        unsafe { SharedPtr::from_raw(Box::into_raw(object)).unwrap() }
+    }
+
+    #[test]
+    fn test_clone_shares_object() {
+        let value = Rc::new(5);
+        let ptr1 = shared_ptr(Some(value.clone()));
+        assert_eq!(*ptr1, 5);
+        assert_eq!(Rc::strong_count(&value), 2);
+
+        let ptr2 = ptr1.clone();
+        assert_eq!(Rc::strong_count(&value), 3);
+        assert_eq!(
+            unsafe { ptr1.get() }.as_raw_ptr(),
+            unsafe { ptr2.get() }.as_raw_ptr()
+        );
+        assert_eq!(*ptr2, 5);
+
+        drop(ptr1);
+        drop(ptr2);
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "null SharedPtr")]
+    fn test_null_deref() {
+        let ptr = shared_ptr(None);
+        assert!(unsafe { ptr.is_null() });
+        let _ = *ptr;
+    }
+}
@@ -1,5 +1,7 @@
 //! Traits for common operations on C++ vectors.
 
+use std::slice;
+
 /// Provides access to the underlying memory buffer.
 pub trait Data {
     /// Return type of `data()` function.
@@ -36,3 +38,134 @@ pub trait Size {
     /// may invoke arbitrary foreign code, so no safety guarantees can be made.
     unsafe fn size(&self) -> usize;
 }
+
+/// Provides access to the elements of the collection by index.
+pub trait At<I> {
+    /// Return type of `at()` function.
+    type Output;
+    /// Returns the element at position `index`.
+    ///
+    /// # Safety
+    ///
+    /// The caller must make sure `self` contains a valid pointer and `index`
+    /// is less than the size of the collection. This function
+    /// may invoke arbitrary foreign code, so no safety guarantees can be made.
+    unsafe fn at(&self, index: I) -> Self::Output;
+}
+
+/// Appends an element to the end of the collection.
+pub trait Push<T> {
+    /// Appends `value` to the end of the collection.
+    ///
+    /// # Safety
+    ///
+    /// The caller must make sure `self` contains a valid pointer. This function
+    /// may invoke arbitrary foreign code, so no safety guarantees can be made.
+    unsafe fn push(&self, value: T);
+}
+
+/// Creates a slice from the result of `data()` and `size()`. Unlike `slice::from_raw_parts`,
+/// accepts a null pointer, which C++ containers may return when they are empty.
+pub(crate) unsafe fn slice_from_data<'a, T>(data: *const T, size: usize) -> &'a [T] {
+    if size == 0 || data.is_null() {
+        &[]
+    } else {
+        slice::from_raw_parts(data, size)
+    }
+}
+
+/// Creates a mutable slice from the result of `data_mut()` and `size()`.
+/// Accepts a null pointer if the container is empty.
+pub(crate) unsafe fn slice_from_data_mut<'a, T>(data: *mut T, size: usize) -> &'a mut [T] {
+    if size == 0 || data.is_null() {
+        &mut []
+    } else {
+        slice::from_raw_parts_mut(data, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Data, DataMut, Push, Size};
+    use crate::{CppBox, CppDefault, CppDeletable, Ref};
+    use std::cell::UnsafeCell;
+    use std::ptr;
+
+    struct Vector(UnsafeCell<Vec<i32>>);
+
+    impl CppDeletable for Vector {
+        unsafe fn delete(&self) {
+            drop(Box::from_raw(self as *const Self as *mut Self));
+        }
+    }
+
+    impl CppDefault for Vector {
+        unsafe fn cpp_default() -> CppBox<Vector> {
+            let vector = Box::new(Vector(UnsafeCell::new(Vec::new())));
+            CppBox::from_raw(Box::into_raw(vector)).unwrap()
+        }
+    }
+
+    impl Data for Vector {
+        type Output = *const i32;
+        unsafe fn data(&self) -> *const i32 {
+            // like `std::vector`, may return null if empty
+            if (*self.0.get()).is_empty() {
+                ptr::null()
+            } else {
+                (*self.0.get()).as_ptr()
+            }
+        }
+    }
+
+    impl DataMut for Vector {
+        type Output = *mut i32;
+        unsafe fn data_mut(&self) -> *mut i32 {
+            if (*self.0.get()).is_empty() {
+                ptr::null_mut()
+            } else {
+                (*self.0.get()).as_mut_ptr()
+            }
+        }
+    }
+
+    impl Size for Vector {
+        unsafe fn size(&self) -> usize {
+            (*self.0.get()).len()
+        }
+    }
+
+    impl Push<i32> for Vector {
+        unsafe fn push(&self, value: i32) {
+            (*self.0.get()).push(value);
+        }
+    }
+
+    #[test]
+    fn test_slices() {
+        let vector: CppBox<Vector> = (1..4).collect();
+        unsafe {
+            assert_eq!(vector.as_slice(), &[1, 2, 3]);
+            vector.as_mut_slice()[1] = 5;
+            assert_eq!(vector.as_slice(), &[1, 5, 3]);
+        }
+
+        let empty = CppBox::<Vector>::default();
+        let empty_ref = unsafe { Ref::from_raw(&*empty).unwrap() };
+        unsafe {
+            assert!(empty.as_slice().is_empty());
+            assert!(empty_ref.as_mut_slice().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_from_iter_and_extend() {
+        let mut vector: CppBox<Vector> = vec![1, 2].into_iter().collect();
+        vector.extend(vec![3]);
+        let mut vector_ref = unsafe { Ref::from_raw(&*vector).unwrap() };
+        vector_ref.extend(4..6);
+        unsafe {
+            assert_eq!(vector.as_slice(), &[1, 2, 3, 4, 5]);
+        }
+    }
+}
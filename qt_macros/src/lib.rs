@@ -14,6 +14,8 @@ use proc_macro::TokenStream;
 use proc_macro_hack::proc_macro_hack;
 
 mod q_init_resource;
+mod q_object;
+mod signals;
 mod slot;
 mod ui_form;
 
@@ -58,13 +60,72 @@ pub fn slot(attrs: TokenStream, input: TokenStream) -> TokenStream {
     crate::slot::slot(attrs, input)
 }
 
+/// Declares a set of custom signals backed by signal wrapper objects.
+///
+/// # Usage
+///
+/// This attribute should be used on structs:
+/// ```ignore
+/// #[signals]
+/// pub struct CounterSignals {
+///     pub value_changed: SignalOfInt,
+///     pub finished: SignalNoArgs,
+/// }
+/// ```
+///
+/// The type of each field must be a signal wrapper type (e.g. `SignalOfInt`) generated by
+/// `ritual`. This type must be in scope. The macro replaces it with `QBox<T>`, so the struct
+/// owns the signal objects.
+///
+/// Alternatively, a field can declare argument types of the signal as a tuple:
+/// ```ignore
+/// #[signals]
+/// pub struct CounterSignals {
+///     pub value_changed: Signal<(i32,)>,
+///     pub finished: Signal<()>,
+/// }
+/// ```
+/// The macro replaces such types with the corresponding wrapper types (`SignalOfInt` and
+/// `SignalNoArgs` in this example), so these wrapper types must also be in scope.
+/// The wrapper name is derived from the argument types in the same way `ritual` names
+/// the wrappers, and `i32`, `u32`, `f32` and `f64` are treated as the `int`, `uint`,
+/// `float` and `double` C++ types. If the arguments of the wrapper are different from
+/// the declared argument types, a compilation error is reported at the field.
+///
+/// The macro generates the function `unsafe fn new() -> Self` that creates all signal objects,
+/// and a method with the same name and visibility as each field that returns
+/// the corresponding `Signal`. It can be used for making connections:
+/// ```ignore
+/// signals.value_changed().connect(&slot);
+/// ```
+///
+/// Use the `emit` method of the field to emit the signal:
+/// ```ignore
+/// signals.value_changed.emit(42);
+/// ```
+///
+/// Since each signal object is a `QObject`, the signal can also be connected to
+/// from C++ code using the object pointer and the `SIGNAL(emit_(...))` signature.
+///
+/// Signals of a Rust struct exposed as a `QObject` subclass can be declared with
+/// the `#[qt(signal)]` attribute instead (see `QObject` derive).
+///
+/// # Requirements
+///
+/// - The struct must contain named fields.
+/// - All fields must have signal wrapper types or `Signal<(...)>` types.
+#[proc_macro_attribute]
+pub fn signals(attrs: TokenStream, input: TokenStream) -> TokenStream {
+    crate::signals::signals(attrs, input)
+}
+
 /// Generates code for loading an UI file.
 ///
 /// # Usage
 ///
 /// This attribute should be used on structs:
 /// ```ignore
-/// #[ui_form("../ui/form.ui")]
+/// #[ui_form("ui/form.ui")]
 /// #[derive(Debug)]
 /// struct Form {
 ///     widget: QBox<QWidget>,
@@ -74,25 +135,139 @@ pub fn slot(attrs: TokenStream, input: TokenStream) -> TokenStream {
 /// ```
 ///
 /// Specify path to the UI file as an argument of the attribute. The path must be relative to
-/// the current file. Content of the UI file will be embedded into the executable.
+/// the crate root (the directory containing `Cargo.toml`). Content of the UI file will be
+/// embedded into the executable.
 ///
 /// The macro will generate the function `fn load() -> Self`.
 ///
 /// # Requirements
 ///
 /// - The struct must contain named fields.
-/// - The first argument must have `QBox<QWidget>` type. This argument will contain the main widget.
-/// - Each of the following arguments must have a name corresponding to `objectName` of an object
-/// in the UI file. The type of the field must be `QPtr<T>`, where `T` must correspond to the type
-/// of the object.
+/// - One of the fields must have `QBox<T>` type, where `T` is `QWidget`, the class of
+/// the top level widget or one of its base classes. This field will contain the main widget.
+/// - Each of the other fields must have a name corresponding to `objectName` of a widget,
+/// a layout or an action in the UI file (including nested layouts). The type of the field
+/// must be `QPtr<T>`, where `T` is the class of the object or one of its base classes.
+///
+/// # Checks
 ///
-/// The `load()` function will panic if the UI file is invalid or if a name or type of any field
-/// doesn't match the objects in the UI file.
+/// The UI file is parsed at compile time, and a compilation error is reported if a name or
+/// type of any field doesn't match the objects in the UI file.
+///
+/// Types of custom widgets unknown to the macro are checked when `load()` is called.
+/// The `load()` function will panic if the UI file can't be loaded by `QUiLoader` or if
+/// a field doesn't match the objects in the UI file.
+///
+/// `qt_ritual_build::compile_ui` provides an alternative that compiles the UI file at build time
+/// and doesn't require `qt_ui_tools`.
 #[proc_macro_attribute]
 pub fn ui_form(attrs: TokenStream, input: TokenStream) -> TokenStream {
     crate::ui_form::ui_form(attrs, input)
 }
 
+/// Exposes a Rust struct as a `QObject` subclass with properties and signals.
+///
+/// # Usage
+///
+/// This derive should be used on structs:
+/// ```ignore
+/// #[derive(Default, QObject)]
+/// struct Counter {
+///     #[qt(property)]
+///     value: i32,
+///     #[qt(property)]
+///     label: String,
+///     #[qt(signal)]
+///     overflowed: DeclaredSignal<(i32, String)>,
+///     history: Vec<i32>,
+/// }
+/// ```
+///
+/// Each field marked with `#[qt(property)]` becomes a Qt property. The property name is the
+/// field name converted to lower camel case (e.g. `item_count` becomes `itemCount`). It has
+/// a getter, a setter (`setItemCount`) and a notify signal (`itemCountChanged`).
+///
+/// Each field marked with `#[qt(signal)]` must have the `qt_core::DeclaredSignal<A>` type
+/// and becomes a Qt signal with argument types specified by the `A` tuple.
+/// The signal name is the field name converted to lower camel case.
+///
+/// The C++ class is generated and compiled by `qt_ritual_build::add_qobjects`, which must be
+/// called from the build script with the path to the file containing the struct.
+///
+/// The macro generates the following functions:
+///
+/// - `unsafe fn create(parent) -> QBox<QObject>` creates a new C++ object containing
+/// the default value of the struct.
+/// - `unsafe fn from_object(object) -> Option<&RefCell<Self>>` returns the value stored
+/// in an object created by `create` (or by QML).
+/// - `unsafe fn update(object, f)` calls `f` with a mutable reference to the value and emits
+/// notify signals of the properties changed by `f`. Changing the value through `from_object`
+/// doesn't emit any signals.
+/// - `unsafe fn register_qml_type(uri, version_major, version_minor, qml_name) -> i32`
+/// registers the class with `qmlRegisterType`.
+/// - `unsafe fn emit_<signal>(object, args...)` emits a signal of `object`. `String` arguments
+/// are passed as `&str`.
+/// - `unsafe fn <signal>(object) -> Signal<A>` returns a signal of `object` for making
+/// connections. `String` arguments are received as `Ref<QString>`.
+///
+/// The signal functions have the same visibility as the fields and panic if `object` is not
+/// an object of this class. Signals should not be emitted from `update` or invokable methods
+/// because slots connected to them can't access the value while it's borrowed.
+///
+/// While the value is borrowed (e.g. in `update`), reading a property through the C++ object
+/// returns the default value of its type, writing a property has no effect, and invokable
+/// methods are not called. Panics in Rust code called from C++ are caught and
+/// the default value is returned instead.
+///
+/// # Requirements
+///
+/// - The struct must contain named fields and must not be generic.
+/// - The struct must implement `Default`.
+/// - Properties and signal arguments must have one of the following types: `bool`, `i32`,
+/// `u32`, `i64`, `u64`, `f32`, `f64`, `String`.
+/// - The name of the struct must be unique among all structs passed to `add_qobjects`.
+#[proc_macro_derive(QObject, attributes(qt))]
+pub fn derive_q_object(input: TokenStream) -> TokenStream {
+    crate::q_object::derive_q_object(input)
+}
+
+/// Exposes methods of a struct with `#[derive(QObject)]` as invokable methods of
+/// the C++ class.
+///
+/// # Usage
+///
+/// This attribute should be used on impl blocks:
+/// ```ignore
+/// #[invokables]
+/// impl Counter {
+///     fn add(&mut self, value: i32) {
+///         self.value += value;
+///     }
+///
+///     fn describe(&self, prefix: String) -> String {
+///         format!("{}{}", prefix, self.value)
+///     }
+/// }
+/// ```
+///
+/// Each method with a `&self` or `&mut self` receiver becomes a `Q_INVOKABLE` method with
+/// the name converted to lower camel case, so it can be called from QML or through
+/// `QMetaObject::invokeMethod`. Associated functions without a receiver are ignored.
+/// Notify signals of the properties changed by the method are emitted after the call.
+///
+/// # Requirements
+///
+/// - The impl block must be in a file passed to `qt_ritual_build::add_qobjects`.
+/// - Arguments and return values must have the types supported for properties.
+/// - Methods must not be generic or async.
+/// - Methods that can't be invokable (e.g. helper methods taking references) must be declared
+/// in a separate impl block without the `invokables` attribute.
+/// - Methods must not access the same object through `from_object` or `update`.
+#[proc_macro_attribute]
+pub fn invokables(attrs: TokenStream, input: TokenStream) -> TokenStream {
+    crate::q_object::invokables(attrs, input)
+}
+
 // This is an implementation detail of the `qt_core::q_init_resource` macro.
 #[doc(hidden)]
 #[proc_macro_hack]
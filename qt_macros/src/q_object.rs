@@ -0,0 +1,662 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use qt_macros_common::q_object::{
+    self, check_invokable_signature, field_kind, signal_arguments, signal_signature, FieldKind,
+    ValueType,
+};
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{
+    parse_macro_input, Data, DeriveInput, Error, Fields, FnArg, Ident, ImplItem, ItemImpl,
+    LitByteStr, Pat, ReturnType, Type,
+};
+
+fn ffi_function_name(class_name: &Ident, suffix: &str) -> Ident {
+    Ident::new(
+        &q_object::ffi_function_name(&class_name.unraw().to_string(), suffix),
+        class_name.span(),
+    )
+}
+
+/// Generates helper functions used by the functions called from C++.
+///
+/// The value may already be borrowed when C++ calls back into Rust (e.g. if a property
+/// is read from `update`), so the functions use `cell` with `try_borrow` and return
+/// default values instead of panicking. Panics must not unwind into C++ code,
+/// so every function body is wrapped in `catch_panic`.
+fn ffi_helpers(class_name: &Ident) -> TokenStream2 {
+    quote! {
+        /// Calls `f` and returns its result, or `default` if `f` panics.
+        fn catch_panic<R>(default: R, f: impl ::std::ops::FnOnce() -> R) -> R {
+            ::std::panic::catch_unwind(::std::panic::AssertUnwindSafe(f)).unwrap_or(default)
+        }
+
+        /// Returns the value created by the `new` function, or `None` if `data` is null.
+        unsafe fn cell<'a>(
+            data: *const ::std::ffi::c_void,
+        ) -> ::std::option::Option<&'a ::std::cell::RefCell<#class_name>> {
+            (data as *const ::std::cell::RefCell<#class_name>).as_ref()
+        }
+    }
+}
+
+/// Returns the Rust type of a value passed to the signal emitting function.
+fn emit_argument_type(value_type: ValueType) -> TokenStream2 {
+    match value_type {
+        ValueType::Bool => quote! { bool },
+        ValueType::I32 => quote! { i32 },
+        ValueType::U32 => quote! { u32 },
+        ValueType::I64 => quote! { i64 },
+        ValueType::U64 => quote! { u64 },
+        ValueType::F32 => quote! { f32 },
+        ValueType::F64 => quote! { f64 },
+        ValueType::String => quote! { &str },
+    }
+}
+
+/// Returns the Rust type of a signal argument received by slots.
+fn signal_argument_type(value_type: ValueType) -> TokenStream2 {
+    match value_type {
+        ValueType::String => quote! { ::cpp_core::Ref<::qt_core::QString> },
+        _ => emit_argument_type(value_type),
+    }
+}
+
+/// Generates the functions emitting and returning the signal declared by `field`.
+fn signal_functions(
+    class_name: &Ident,
+    field: &syn::Field,
+    extern_functions: &mut Vec<TokenStream2>,
+    methods: &mut Vec<TokenStream2>,
+) -> Result<(), Error> {
+    let name = field.ident.as_ref().expect("named field expected");
+    let vis = &field.vis;
+    let arguments = signal_arguments(&field.ty)?;
+    let ffi_name = ffi_function_name(class_name, &format!("emit_signal_{}", name.unraw()));
+    let emit_name = format_ident!("emit_{}", name.unraw());
+    let class_name_str = class_name.to_string();
+
+    let mut arg_names = Vec::new();
+    let mut emit_args = Vec::new();
+    let mut ffi_args = Vec::new();
+    let mut conversions = Vec::new();
+    let mut call_args = Vec::new();
+    let mut signal_types = Vec::new();
+    for (index, &value_type) in arguments.iter().enumerate() {
+        let arg_name = format_ident!("arg{}", index);
+        let emit_type = emit_argument_type(value_type);
+        emit_args.push(quote! { #arg_name: #emit_type });
+        if value_type == ValueType::String {
+            ffi_args.push(quote! { #arg_name: *const ::qt_core::QString });
+            conversions.push(quote! {
+                let #arg_name = ::qt_core::QString::from_std_str(#arg_name);
+            });
+            call_args.push(quote! { #arg_name.as_raw_ptr() });
+        } else {
+            ffi_args.push(quote! { #arg_name: #emit_type });
+            call_args.push(quote! { #arg_name });
+        }
+        signal_types.push(signal_argument_type(value_type));
+        arg_names.push(arg_name);
+    }
+    let receiver_id = LitByteStr::new(
+        format!(
+            "2{}\0",
+            signal_signature(&name.unraw().to_string(), &arguments)
+        )
+        .as_bytes(),
+        name.span(),
+    );
+
+    extern_functions.push(quote! {
+        fn #ffi_name(object: *const ::qt_core::QObject, #(#ffi_args),*);
+    });
+    methods.push(quote! {
+        /// Emits the signal declared by the field with the same name.
+        ///
+        /// Panics if `object` is not an object of this class.
+        #vis unsafe fn #emit_name(
+            object: impl ::cpp_core::CastInto<::cpp_core::Ptr<::qt_core::QObject>>,
+            #(#emit_args),*
+        ) {
+            let object = ::cpp_core::CastInto::cast_into(object);
+            assert!(
+                Self::from_object(object).is_some(),
+                concat!("object is not a ", #class_name_str)
+            );
+            #(#conversions)*
+            #ffi_name(object.as_raw_ptr(), #(#call_args),*);
+        }
+
+        /// Returns the signal declared by the field with the same name.
+        ///
+        /// Panics if `object` is not an object of this class.
+        #vis unsafe fn #name(
+            object: impl ::cpp_core::CastInto<::cpp_core::Ptr<::qt_core::QObject>>,
+        ) -> ::qt_core::Signal<(#(#signal_types,)*)> {
+            let object = ::cpp_core::CastInto::cast_into(object);
+            let object = match object.as_ref() {
+                Some(object) if Self::from_object(object).is_some() => object,
+                _ => panic!(concat!("object is not a ", #class_name_str)),
+            };
+            ::qt_core::Signal::new(
+                object,
+                ::std::ffi::CStr::from_bytes_with_nul_unchecked(#receiver_id),
+            )
+        }
+    });
+    Ok(())
+}
+
+fn derive_impl(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new(
+            input.generics.span(),
+            "generic types are not supported",
+        ));
+    }
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new(
+                    input.ident.span(),
+                    "only structs with named fields are supported",
+                ))
+            }
+        },
+        _ => return Err(Error::new(input.ident.span(), "only structs are supported")),
+    };
+
+    let class_name = &input.ident;
+    let class_name_str = class_name.to_string();
+    let new_name = ffi_function_name(class_name, "new");
+    let drop_name = ffi_function_name(class_name, "drop");
+    let create_name = ffi_function_name(class_name, "create");
+    let data_name = ffi_function_name(class_name, "data");
+    let register_name = ffi_function_name(class_name, "register");
+
+    let mut ffi_functions = Vec::new();
+    let mut property_names = Vec::new();
+    let mut emit_names = Vec::new();
+    let mut signal_extern_functions = Vec::new();
+    let mut signal_methods = Vec::new();
+    for field in fields {
+        match field_kind(&field.attrs)? {
+            Some(FieldKind::Property) => {}
+            Some(FieldKind::Signal) => {
+                signal_functions(
+                    class_name,
+                    field,
+                    &mut signal_extern_functions,
+                    &mut signal_methods,
+                )?;
+                continue;
+            }
+            None => continue,
+        }
+        let name = field.ident.as_ref().expect("named field expected");
+        let value_type = ValueType::from_type(&field.ty)?;
+        let getter_name = ffi_function_name(class_name, &format!("get_{}", name.unraw()));
+        let setter_name = ffi_function_name(class_name, &format!("set_{}", name.unraw()));
+        if value_type == ValueType::String {
+            ffi_functions.push(quote! {
+                #[no_mangle]
+                unsafe extern "C" fn #getter_name(
+                    data: *const ::std::ffi::c_void,
+                    output: *mut ::qt_core::QString,
+                ) {
+                    catch_panic((), || {
+                        if let Some(data) = cell(data).and_then(|data| data.try_borrow().ok()) {
+                            let value = ::qt_core::QString::from_std_str(&data.#name);
+                            (*output).copy_from(&value);
+                        }
+                    })
+                }
+
+                #[no_mangle]
+                unsafe extern "C" fn #setter_name(
+                    data: *const ::std::ffi::c_void,
+                    value: *const ::qt_core::QString,
+                ) -> bool {
+                    catch_panic(false, || {
+                        let data = cell(data).and_then(|data| data.try_borrow_mut().ok());
+                        let mut data = match data {
+                            Some(data) => data,
+                            None => return false,
+                        };
+                        let value = (*value).to_std_string();
+                        if data.#name == value {
+                            false
+                        } else {
+                            data.#name = value;
+                            true
+                        }
+                    })
+                }
+            });
+        } else {
+            let ty = &field.ty;
+            ffi_functions.push(quote! {
+                #[no_mangle]
+                unsafe extern "C" fn #getter_name(data: *const ::std::ffi::c_void) -> #ty {
+                    catch_panic(::std::default::Default::default(), || {
+                        match cell(data).and_then(|data| data.try_borrow().ok()) {
+                            Some(data) => data.#name,
+                            None => ::std::default::Default::default(),
+                        }
+                    })
+                }
+
+                #[no_mangle]
+                unsafe extern "C" fn #setter_name(
+                    data: *const ::std::ffi::c_void,
+                    value: #ty,
+                ) -> bool {
+                    catch_panic(false, || {
+                        let data = cell(data).and_then(|data| data.try_borrow_mut().ok());
+                        let mut data = match data {
+                            Some(data) => data,
+                            None => return false,
+                        };
+                        #[allow(clippy::float_cmp)]
+                        let is_same = data.#name == value;
+                        if is_same {
+                            false
+                        } else {
+                            data.#name = value;
+                            true
+                        }
+                    })
+                }
+            });
+        }
+        property_names.push(name);
+        emit_names.push(ffi_function_name(
+            class_name,
+            &format!("emit_{}_changed", name.unraw()),
+        ));
+    }
+    let old_value_names = property_names
+        .iter()
+        .map(|name| format_ident!("old_{}", name.unraw()))
+        .collect::<Vec<_>>();
+    let helpers = ffi_helpers(class_name);
+
+    Ok(quote! {
+        const _: () = {
+            #helpers
+
+            #[no_mangle]
+            unsafe extern "C" fn #new_name() -> *mut ::std::ffi::c_void {
+                catch_panic(::std::ptr::null_mut(), || {
+                    let value = <#class_name as ::std::default::Default>::default();
+                    let data = ::std::boxed::Box::new(::std::cell::RefCell::new(value));
+                    ::std::boxed::Box::into_raw(data) as *mut ::std::ffi::c_void
+                })
+            }
+
+            #[no_mangle]
+            unsafe extern "C" fn #drop_name(data: *mut ::std::ffi::c_void) {
+                catch_panic((), || {
+                    if !data.is_null() {
+                        let data = data as *mut ::std::cell::RefCell<#class_name>;
+                        let _ = ::std::boxed::Box::from_raw(data);
+                    }
+                })
+            }
+
+            #(#ffi_functions)*
+        };
+
+        extern "C" {
+            fn #create_name(parent: *const ::qt_core::QObject) -> *const ::qt_core::QObject;
+            fn #data_name(object: *const ::qt_core::QObject) -> *const ::std::ffi::c_void;
+            fn #register_name(
+                uri: *const ::std::os::raw::c_char,
+                version_major: ::std::os::raw::c_int,
+                version_minor: ::std::os::raw::c_int,
+                qml_name: *const ::std::os::raw::c_char,
+            ) -> ::std::os::raw::c_int;
+            #(
+                fn #emit_names(object: *const ::qt_core::QObject);
+            )*
+            #(#signal_extern_functions)*
+        }
+
+        impl #class_name {
+            /// Creates a new C++ object containing the default value of this type.
+            pub unsafe fn create(
+                parent: impl ::cpp_core::CastInto<::cpp_core::Ptr<::qt_core::QObject>>,
+            ) -> ::qt_core::QBox<::qt_core::QObject> {
+                let parent = ::cpp_core::CastInto::cast_into(parent);
+                ::qt_core::QBox::from_raw(#create_name(parent.as_raw_ptr()))
+            }
+
+            /// Returns the value stored in `object` if it's an object of this class.
+            pub unsafe fn from_object<'a>(
+                object: impl ::cpp_core::CastInto<::cpp_core::Ptr<::qt_core::QObject>>,
+            ) -> ::std::option::Option<&'a ::std::cell::RefCell<Self>> {
+                let object = ::cpp_core::CastInto::cast_into(object);
+                let data = #data_name(object.as_raw_ptr());
+                if data.is_null() {
+                    None
+                } else {
+                    Some(&*(data as *const ::std::cell::RefCell<Self>))
+                }
+            }
+
+            /// Calls `f` with the value stored in `object` and emits notification signals
+            /// of the properties changed by `f`.
+            ///
+            /// Panics if `object` is not an object of this class.
+            pub unsafe fn update<R>(
+                object: impl ::cpp_core::CastInto<::cpp_core::Ptr<::qt_core::QObject>>,
+                f: impl ::std::ops::FnOnce(&mut Self) -> R,
+            ) -> R {
+                let object = ::cpp_core::CastInto::cast_into(object);
+                let cell = Self::from_object(object)
+                    .expect(concat!("object is not a ", #class_name_str));
+                let mut value = cell.borrow_mut();
+                #(
+                    let #old_value_names = ::std::clone::Clone::clone(&value.#property_names);
+                )*
+                let result = f(&mut *value);
+                #(
+                    #[allow(clippy::float_cmp)]
+                    let #old_value_names = value.#property_names != #old_value_names;
+                )*
+                drop(value);
+                #(
+                    if #old_value_names {
+                        #emit_names(object.as_raw_ptr());
+                    }
+                )*
+                result
+            }
+
+            /// Registers the class in the QML system with the name `qml_name`
+            /// in the library imported from `uri` having the version number composed
+            /// from `version_major` and `version_minor`.
+            ///
+            /// Returns the QML type id, or -1 if the Qt QML module was not available
+            /// at build time.
+            pub unsafe fn register_qml_type(
+                uri: &str,
+                version_major: i32,
+                version_minor: i32,
+                qml_name: &str,
+            ) -> i32 {
+                let uri = ::std::ffi::CString::new(uri).expect("uri contains a null byte");
+                let qml_name =
+                    ::std::ffi::CString::new(qml_name).expect("qml_name contains a null byte");
+                #register_name(uri.as_ptr(), version_major, version_minor, qml_name.as_ptr())
+            }
+
+            #(#signal_methods)*
+        }
+    })
+}
+
+pub fn derive_q_object(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let expanded = derive_impl(&input).unwrap_or_else(|err| err.to_compile_error());
+    TokenStream::from(expanded)
+}
+
+fn invokables_impl(input: &ItemImpl) -> Result<TokenStream2, Error> {
+    if !input.generics.params.is_empty() || input.trait_.is_some() {
+        return Err(Error::new(
+            input.span(),
+            "only inherent impls of non-generic types are supported",
+        ));
+    }
+    let class_name = match &*input.self_ty {
+        Type::Path(path) if path.qself.is_none() => path.path.get_ident(),
+        _ => None,
+    }
+    .ok_or_else(|| Error::new(input.self_ty.span(), "expected a type name"))?;
+
+    let mut ffi_functions = Vec::new();
+    for item in &input.items {
+        let method = match item {
+            ImplItem::Method(method) => method,
+            _ => continue,
+        };
+        let receiver = match method.sig.receiver() {
+            Some(FnArg::Receiver(receiver)) if receiver.reference.is_some() => receiver,
+            Some(_) => {
+                return Err(Error::new(
+                    method.sig.span(),
+                    "invokable methods must take `&self` or `&mut self`",
+                ))
+            }
+            // Associated functions are not exposed.
+            None => continue,
+        };
+        check_invokable_signature(&method.sig)?;
+        let name = &method.sig.ident;
+        let (borrow, binding) = if receiver.mutability.is_some() {
+            (quote! { try_borrow_mut }, quote! { mut data })
+        } else {
+            (quote! { try_borrow }, quote! { data })
+        };
+
+        let mut ffi_args = Vec::new();
+        let mut call_args = Vec::new();
+        for arg in method.sig.inputs.iter().skip(1) {
+            let arg = match arg {
+                FnArg::Typed(arg) => arg,
+                FnArg::Receiver(_) => unreachable!(),
+            };
+            let arg_name = match &*arg.pat {
+                Pat::Ident(pat) => &pat.ident,
+                _ => return Err(Error::new(arg.pat.span(), "expected an argument name")),
+            };
+            let ty = &arg.ty;
+            if ValueType::from_type(ty)? == ValueType::String {
+                ffi_args.push(quote! { #arg_name: *const ::qt_core::QString });
+                call_args.push(quote! { (*#arg_name).to_std_string() });
+            } else {
+                ffi_args.push(quote! { #arg_name: #ty });
+                call_args.push(quote! { #arg_name });
+            }
+        }
+
+        let ffi_name = ffi_function_name(class_name, &format!("invoke_{}", name.unraw()));
+        let borrowed = quote! {
+            cell(data).and_then(|data| data.#borrow().ok())
+        };
+        let function = match &method.sig.output {
+            ReturnType::Default => quote! {
+                #[no_mangle]
+                unsafe extern "C" fn #ffi_name(
+                    data: *const ::std::ffi::c_void,
+                    #(#ffi_args),*
+                ) {
+                    catch_panic((), || {
+                        if let Some(#binding) = #borrowed {
+                            data.#name(#(#call_args),*);
+                        }
+                    })
+                }
+            },
+            ReturnType::Type(_, ty) => {
+                if ValueType::from_type(ty)? == ValueType::String {
+                    quote! {
+                        #[no_mangle]
+                        unsafe extern "C" fn #ffi_name(
+                            data: *const ::std::ffi::c_void,
+                            #(#ffi_args,)*
+                            output: *mut ::qt_core::QString,
+                        ) {
+                            catch_panic((), || {
+                                if let Some(#binding) = #borrowed {
+                                    let result = data.#name(#(#call_args),*);
+                                    (*output).copy_from(&::qt_core::QString::from_std_str(result));
+                                }
+                            })
+                        }
+                    }
+                } else {
+                    quote! {
+                        #[no_mangle]
+                        unsafe extern "C" fn #ffi_name(
+                            data: *const ::std::ffi::c_void,
+                            #(#ffi_args),*
+                        ) -> #ty {
+                            catch_panic(::std::default::Default::default(), || {
+                                match #borrowed {
+                                    Some(#binding) => data.#name(#(#call_args),*),
+                                    None => ::std::default::Default::default(),
+                                }
+                            })
+                        }
+                    }
+                }
+            }
+        };
+        ffi_functions.push(function);
+    }
+
+    let helpers = ffi_helpers(class_name);
+    Ok(quote! {
+        const _: () = {
+            #helpers
+
+            #(#ffi_functions)*
+        };
+    })
+}
+
+pub fn invokables(attrs: TokenStream, input: TokenStream) -> TokenStream {
+    if !attrs.is_empty() {
+        return Error::new(
+            Span::call_site(),
+            "invokables attribute doesn't accept arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let input = parse_macro_input!(input as ItemImpl);
+    let ffi_functions = invokables_impl(&input).unwrap_or_else(|err| err.to_compile_error());
+    let expanded = quote! {
+        #input
+        #ffi_functions
+    };
+    TokenStream::from(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{derive_impl, invokables_impl};
+    use syn::parse_quote;
+
+    #[test]
+    fn derive() {
+        let input = parse_quote! {
+            #[derive(Default, QObject)]
+            struct Counter {
+                #[qt(property)]
+                value: i32,
+                #[qt(property)]
+                label: String,
+                #[qt(signal)]
+                overflowed: DeclaredSignal<(i32, String)>,
+                history: Vec<i32>,
+            }
+        };
+        let output = derive_impl(&input).unwrap().to_string();
+        assert!(output.contains("ritual_qobject_Counter_get_value"));
+        assert!(output.contains("ritual_qobject_Counter_set_label"));
+        assert!(output.contains("ritual_qobject_Counter_emit_label_changed"));
+        assert!(!output.contains("history_changed"));
+        assert!(output.contains("ritual_qobject_Counter_emit_signal_overflowed"));
+        assert!(output.contains("fn emit_overflowed"));
+        assert!(output.contains(r#"b"2overflowed(int,QString)\0""#));
+        // Functions called from C++ must not panic if the value is already borrowed.
+        assert!(output.contains("catch_unwind"));
+        assert!(output.contains("data . try_borrow ()"));
+        assert!(output.contains("data . try_borrow_mut ()"));
+        assert!(!output.contains("data . borrow ()"));
+
+        let input = parse_quote! {
+            struct Counter {
+                #[qt(signal)]
+                overflowed: DeclaredSignal<i32>,
+            }
+        };
+        assert!(derive_impl(&input).is_err());
+
+        let input = parse_quote! {
+            struct Counter {
+                #[qt(property)]
+                values: Vec<i32>,
+            }
+        };
+        assert!(derive_impl(&input).is_err());
+
+        let input = parse_quote! {
+            struct Counter(i32);
+        };
+        assert!(derive_impl(&input).is_err());
+    }
+
+    #[test]
+    fn invokables() {
+        let input = parse_quote! {
+            impl Counter {
+                fn new() -> Self {
+                    Counter::default()
+                }
+                fn add(&mut self, value: i32) {
+                    self.value += value;
+                }
+                fn describe(&self, prefix: String) -> String {
+                    format!("{}{}", prefix, self.value)
+                }
+            }
+        };
+        let output = invokables_impl(&input).unwrap().to_string();
+        assert!(output.contains("ritual_qobject_Counter_invoke_add"));
+        assert!(output.contains("ritual_qobject_Counter_invoke_describe"));
+        assert!(!output.contains("invoke_new"));
+        assert!(output.contains("catch_unwind"));
+        assert!(output.contains("data . try_borrow_mut ()"));
+        assert!(output.contains("data . try_borrow ()"));
+
+        let input = parse_quote! {
+            impl Counter {
+                fn consume(self) {}
+            }
+        };
+        assert!(invokables_impl(&input).is_err());
+
+        let input = parse_quote! {
+            impl Counter {
+                fn history(&self) -> &[i32] {
+                    &self.history
+                }
+            }
+        };
+        let err = invokables_impl(&input).unwrap_err().to_string();
+        assert!(err.contains("invalid return type of invokable method `history`"));
+        assert!(err.contains("without `#[invokables]`"));
+
+        let input = parse_quote! {
+            impl Counter {
+                fn add_all(&mut self, values: impl IntoIterator<Item = i32>) {}
+            }
+        };
+        assert!(invokables_impl(&input).is_err());
+
+        let input = parse_quote! {
+            impl Counter {
+                fn add_value<T: Into<i32>>(&mut self, value: i32) {}
+            }
+        };
+        let err = invokables_impl(&input).unwrap_err().to_string();
+        assert!(err.contains("invokable method `add_value` must not be generic"));
+    }
+}
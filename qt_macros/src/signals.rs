@@ -0,0 +1,145 @@
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use qt_macros_common::signals::{signal_arguments, signal_wrapper_name};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, parse_quote, Error, Fields, Ident, ItemStruct};
+
+fn signals_impl(input: &mut ItemStruct) -> Result<TokenStream2, Error> {
+    let fields = match &mut input.fields {
+        Fields::Named(fields) => &mut fields.named,
+        _ => {
+            return Err(Error::new(
+                input.ident.span(),
+                "only structs with named fields are supported",
+            ))
+        }
+    };
+
+    let mut field_names = Vec::new();
+    let mut field_visibilities = Vec::new();
+    let mut signal_types = Vec::new();
+    let mut checks = Vec::new();
+    for field in fields.iter_mut() {
+        let field_name = field
+            .ident
+            .clone()
+            .ok_or_else(|| Error::new(field.span(), "named field expected"))?;
+        let signal_type = match signal_arguments(&field.ty)? {
+            Some(arguments) => {
+                let span = field.ty.span();
+                let wrapper = Ident::new(&signal_wrapper_name(&arguments)?, span);
+                // Reports a mismatch between the declared argument types and
+                // the arguments of the wrapper at the field.
+                checks.push(quote_spanned! {span=>
+                    const _: () = {
+                        fn check(
+                            signal: ::qt_core::Signal<
+                                <#wrapper as ::qt_core::AsReceiver>::Arguments
+                            >,
+                        ) -> ::qt_core::Signal<(#(#arguments,)*)> {
+                            signal
+                        }
+                    };
+                });
+                parse_quote!(#wrapper)
+            }
+            None => field.ty.clone(),
+        };
+        field.ty = parse_quote!(::qt_core::QBox<#signal_type>);
+        field_names.push(field_name);
+        field_visibilities.push(field.vis.clone());
+        signal_types.push(signal_type);
+    }
+
+    let struct_name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        #(#checks)*
+
+        impl #impl_generics #struct_name #ty_generics #where_clause {
+            /// Creates new signal objects.
+            pub unsafe fn new() -> Self {
+                Self {
+                    #(
+                        #field_names: <#signal_types>::new(),
+                    )*
+                }
+            }
+
+            #(
+                #field_visibilities unsafe fn #field_names(&self) -> ::qt_core::Signal<
+                    <#signal_types as ::qt_core::AsReceiver>::Arguments
+                > {
+                    self.#field_names.signal()
+                }
+            )*
+        }
+    })
+}
+
+pub fn signals(attrs: TokenStream, input: TokenStream) -> TokenStream {
+    if !attrs.is_empty() {
+        return Error::new(
+            Span::call_site(),
+            "signals attribute doesn't accept arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+    let mut input = parse_macro_input!(input as ItemStruct);
+    let output = signals_impl(&mut input).unwrap_or_else(|err| err.to_compile_error());
+    let expanded = quote! {
+        #input
+        #output
+    };
+    TokenStream::from(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::signals_impl;
+    use quote::quote;
+    use syn::{parse_quote, ItemStruct};
+
+    #[test]
+    fn declared_arguments() {
+        let mut input: ItemStruct = parse_quote! {
+            struct CounterSignals {
+                value_changed: SignalOfInt,
+                limit_changed: Signal<(i32,)>,
+                reset: Signal<()>,
+            }
+        };
+        let output = signals_impl(&mut input).unwrap().to_string();
+        let fields = quote!(#input).to_string();
+        assert!(fields.contains("value_changed : :: qt_core :: QBox < SignalOfInt >"));
+        assert!(fields.contains("limit_changed : :: qt_core :: QBox < SignalOfInt >"));
+        assert!(fields.contains("reset : :: qt_core :: QBox < SignalNoArgs >"));
+        assert!(output.contains("-> :: qt_core :: Signal < (i32 ,) >"));
+        assert!(output.contains("fn limit_changed"));
+    }
+
+    #[test]
+    fn errors() {
+        let mut input: ItemStruct = parse_quote! {
+            struct CounterSignals(SignalOfInt);
+        };
+        assert!(signals_impl(&mut input).is_err());
+
+        let mut input: ItemStruct = parse_quote! {
+            struct CounterSignals {
+                value_changed: Signal<i32>,
+            }
+        };
+        assert!(signals_impl(&mut input).is_err());
+
+        let mut input: ItemStruct = parse_quote! {
+            struct CounterSignals {
+                value_changed: Signal<(&'static [i32],)>,
+            }
+        };
+        assert!(signals_impl(&mut input).is_err());
+    }
+}
@@ -1,45 +1,505 @@
 use proc_macro::TokenStream;
-use proc_macro2::Literal;
-use quote::{quote, ToTokens};
-use syn::{parse_macro_input, ItemStruct};
+use proc_macro2::Span;
+use quote::quote;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Error, GenericArgument, ItemStruct, LitStr, PathArguments, Type};
 
-pub fn ui_form(attrs: TokenStream, input: TokenStream) -> TokenStream {
-    let ui_file_path = parse_macro_input!(attrs as Literal);
-    let input = parse_macro_input!(input as ItemStruct);
+/// Kind of an object declared in a UI file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ObjectKind {
+    Widget,
+    Layout,
+    Action,
+}
 
-    let struct_name = &input.ident;
+/// A named object declared in a UI file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct UiObject {
+    kind: ObjectKind,
+    class_name: String,
+}
 
-    let mut fields = input
-        .fields
-        .iter()
-        .map(|f| f.ident.clone().expect("unnamed fields are not supported"))
-        .collect::<Vec<_>>();
-    let first_field = fields.remove(0);
-    let field_names = fields
-        .iter()
-        .map(|ident| ident.to_token_stream().to_string())
-        .collect::<Vec<_>>();
+/// Objects declared in a UI file.
+#[derive(Debug)]
+struct UiFile {
+    root_class_name: String,
+    objects: HashMap<String, UiObject>,
+}
 
-    let expanded = quote! {
-        #input
+fn parse_ui(xml: &str) -> Result<UiFile, String> {
+    let document =
+        roxmltree::Document::parse(xml).map_err(|err| format!("invalid UI file: {}", err))?;
+    let root_widget = document
+        .root_element()
+        .children()
+        .find(|node| node.has_tag_name("widget"))
+        .ok_or("invalid UI file: top level widget is missing")?;
+    let root_class_name = root_widget
+        .attribute("class")
+        .ok_or("invalid UI file: top level widget doesn't have a class")?
+        .to_string();
+
+    let mut objects = HashMap::new();
+    for node in root_widget.descendants().skip(1) {
+        let (kind, class_name) = match node.tag_name().name() {
+            "widget" => (ObjectKind::Widget, node.attribute("class")),
+            "layout" => (ObjectKind::Layout, node.attribute("class")),
+            "action" => (ObjectKind::Action, Some("QAction")),
+            "actiongroup" => (ObjectKind::Action, Some("QActionGroup")),
+            _ => continue,
+        };
+        if let (Some(name), Some(class_name)) = (node.attribute("name"), class_name) {
+            // Designer's line widget is a `QFrame`.
+            let class_name = if class_name == "Line" {
+                "QFrame"
+            } else {
+                class_name
+            };
+            objects.insert(
+                name.to_string(),
+                UiObject {
+                    kind,
+                    class_name: class_name.to_string(),
+                },
+            );
+        }
+    }
+    Ok(UiFile {
+        root_class_name,
+        objects,
+    })
+}
+
+/// Returns names of the pointer type and the pointee type
+/// (e.g. `("QPtr", "QPushButton")` for `QPtr<QPushButton>`).
+fn pointer_type_names(ty: &Type) -> Option<(String, String)> {
+    let segment = match ty {
+        Type::Path(path) => path.path.segments.last()?,
+        _ => return None,
+    };
+    let args = match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => &args.args[0],
+        _ => return None,
+    };
+    let target = match args {
+        GenericArgument::Type(Type::Path(path)) => path.path.segments.last()?,
+        _ => return None,
+    };
+    Some((segment.ident.to_string(), target.ident.to_string()))
+}
 
+/// Base classes of Qt classes that are commonly used in UI files.
+const BASE_CLASSES: &[(&str, &str)] = &[
+    ("QWidget", "QObject"),
+    ("QLayout", "QObject"),
+    ("QAction", "QObject"),
+    ("QActionGroup", "QObject"),
+    // Layouts
+    ("QBoxLayout", "QLayout"),
+    ("QHBoxLayout", "QBoxLayout"),
+    ("QVBoxLayout", "QBoxLayout"),
+    ("QGridLayout", "QLayout"),
+    ("QFormLayout", "QLayout"),
+    ("QStackedLayout", "QLayout"),
+    // Buttons
+    ("QAbstractButton", "QWidget"),
+    ("QPushButton", "QAbstractButton"),
+    ("QCommandLinkButton", "QPushButton"),
+    ("QToolButton", "QAbstractButton"),
+    ("QCheckBox", "QAbstractButton"),
+    ("QRadioButton", "QAbstractButton"),
+    // Frames and scroll areas
+    ("QFrame", "QWidget"),
+    ("QLabel", "QFrame"),
+    ("QLCDNumber", "QFrame"),
+    ("QSplitter", "QFrame"),
+    ("QStackedWidget", "QFrame"),
+    ("QToolBox", "QFrame"),
+    ("QAbstractScrollArea", "QFrame"),
+    ("QScrollArea", "QAbstractScrollArea"),
+    ("QTextEdit", "QAbstractScrollArea"),
+    ("QTextBrowser", "QTextEdit"),
+    ("QPlainTextEdit", "QAbstractScrollArea"),
+    ("QGraphicsView", "QAbstractScrollArea"),
+    ("QMdiArea", "QAbstractScrollArea"),
+    ("QAbstractItemView", "QAbstractScrollArea"),
+    ("QListView", "QAbstractItemView"),
+    ("QListWidget", "QListView"),
+    ("QUndoView", "QListView"),
+    ("QTreeView", "QAbstractItemView"),
+    ("QTreeWidget", "QTreeView"),
+    ("QTableView", "QAbstractItemView"),
+    ("QTableWidget", "QTableView"),
+    ("QColumnView", "QAbstractItemView"),
+    // Input widgets
+    ("QLineEdit", "QWidget"),
+    ("QComboBox", "QWidget"),
+    ("QFontComboBox", "QComboBox"),
+    ("QAbstractSpinBox", "QWidget"),
+    ("QSpinBox", "QAbstractSpinBox"),
+    ("QDoubleSpinBox", "QAbstractSpinBox"),
+    ("QDateTimeEdit", "QAbstractSpinBox"),
+    ("QDateEdit", "QDateTimeEdit"),
+    ("QTimeEdit", "QDateTimeEdit"),
+    ("QAbstractSlider", "QWidget"),
+    ("QSlider", "QAbstractSlider"),
+    ("QScrollBar", "QAbstractSlider"),
+    ("QDial", "QAbstractSlider"),
+    ("QKeySequenceEdit", "QWidget"),
+    // Other widgets
+    ("QProgressBar", "QWidget"),
+    ("QGroupBox", "QWidget"),
+    ("QTabWidget", "QWidget"),
+    ("QCalendarWidget", "QWidget"),
+    ("QDialogButtonBox", "QWidget"),
+    ("QDockWidget", "QWidget"),
+    ("QMenu", "QWidget"),
+    ("QMenuBar", "QWidget"),
+    ("QStatusBar", "QWidget"),
+    ("QToolBar", "QWidget"),
+    ("QDialog", "QWidget"),
+    ("QMainWindow", "QWidget"),
+];
+
+/// Returns `class_name` and all its base classes, or `None` if the class is unknown.
+fn class_and_bases(class_name: &str) -> Option<Vec<&str>> {
+    if class_name == "QObject" {
+        return Some(vec!["QObject"]);
+    }
+    let mut classes = vec![class_name];
+    let mut current = class_name;
+    while current != "QObject" {
+        current = BASE_CLASSES.iter().find(|(class, _)| *class == current)?.1;
+        classes.push(current);
+    }
+    Some(classes)
+}
+
+/// Checks that `object` can be accessed through a pointer to `target`.
+///
+/// Returns `true` if the relationship between the classes is unknown (e.g. for custom widgets),
+/// in which case `find_child` checks the type at runtime.
+fn is_compatible(object: &UiObject, target: &str) -> bool {
+    if target == object.class_name || target == "QObject" {
+        return true;
+    }
+    let kind_base = match object.kind {
+        ObjectKind::Widget => "QWidget",
+        ObjectKind::Layout => "QLayout",
+        ObjectKind::Action => "QObject",
+    };
+    if let Some(classes) = class_and_bases(&object.class_name) {
+        return classes.contains(&target);
+    }
+    if target == kind_base {
+        return true;
+    }
+    // The class of the object is unknown, but it must inherit `kind_base`,
+    // so `target` is only allowed if it may be a subclass of `kind_base`.
+    class_and_bases(target).map_or(true, |classes| classes.contains(&kind_base))
+}
+
+/// Returns the full path of the UI file.
+///
+/// The path must be relative to the crate root (the directory containing `Cargo.toml`).
+/// The same path is passed to `include_bytes!`, so the checked file is always the embedded one.
+fn ui_file_full_path(manifest_dir: &Path, path: &str) -> Result<PathBuf, String> {
+    if Path::new(path).is_absolute() {
+        return Err(format!(
+            "UI file path `{}` must be relative to the crate root",
+            path
+        ));
+    }
+    let full_path = manifest_dir.join(path);
+    if !full_path.is_file() {
+        return Err(format!(
+            "UI file `{}` is not found in the crate root ({})",
+            path,
+            manifest_dir.display()
+        ));
+    }
+    Ok(full_path)
+}
+
+fn generate(ui_file_path: &LitStr, input: &ItemStruct) -> Result<proc_macro2::TokenStream, Error> {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| Error::new(Span::call_site(), "CARGO_MANIFEST_DIR is not set"))?;
+    let full_path = ui_file_full_path(Path::new(&manifest_dir), &ui_file_path.value())
+        .map_err(|err| Error::new(ui_file_path.span(), err))?;
+    let xml = fs::read_to_string(&full_path).map_err(|err| {
+        Error::new(
+            ui_file_path.span(),
+            format!("failed to read {}: {}", full_path.display(), err),
+        )
+    })?;
+    let ui_file = parse_ui(&xml).map_err(|err| {
+        Error::new(
+            ui_file_path.span(),
+            format!("{}: {}", full_path.display(), err),
+        )
+    })?;
+
+    let mut root_field = None;
+    let mut field_names = Vec::new();
+    let mut object_names = Vec::new();
+    let mut errors = Vec::<Error>::new();
+    for field in &input.fields {
+        let ident = field
+            .ident
+            .as_ref()
+            .ok_or_else(|| Error::new(field.span(), "unnamed fields are not supported"))?;
+        let (pointer, target) = match pointer_type_names(&field.ty) {
+            Some(names) => names,
+            None => {
+                errors.push(Error::new(
+                    field.ty.span(),
+                    "expected `QBox<T>` or `QPtr<T>` type",
+                ));
+                continue;
+            }
+        };
+        match pointer.as_str() {
+            "QBox" => {
+                if root_field.is_some() {
+                    errors.push(Error::new(
+                        field.ty.span(),
+                        "only one `QBox<T>` field (the top level widget) is allowed",
+                    ));
+                    continue;
+                }
+                let root = UiObject {
+                    kind: ObjectKind::Widget,
+                    class_name: ui_file.root_class_name.clone(),
+                };
+                if !is_compatible(&root, &target) {
+                    let message = format!(
+                        "the top level widget has type `{}`, which doesn't inherit `{}`",
+                        root.class_name, target
+                    );
+                    errors.push(Error::new(field.ty.span(), message));
+                }
+                root_field = Some((ident, target == "QWidget"));
+            }
+            "QPtr" => {
+                let name = ident.to_string();
+                let name = name.trim_start_matches("r#");
+                match ui_file.objects.get(name) {
+                    Some(object) if !is_compatible(object, &target) => {
+                        let message = format!(
+                            "object `{}` has type `{}`, which doesn't inherit `{}`",
+                            name, object.class_name, target
+                        );
+                        errors.push(Error::new(field.ty.span(), message));
+                    }
+                    Some(_) => {}
+                    None => {
+                        let message = format!("UI file doesn't contain an object named `{}`", name);
+                        errors.push(Error::new(ident.span(), message));
+                    }
+                }
+                field_names.push(ident);
+                object_names.push(name.to_string());
+            }
+            _ => errors.push(Error::new(
+                field.ty.span(),
+                "expected `QBox<T>` or `QPtr<T>` type",
+            )),
+        }
+    }
+    let mut errors = errors.into_iter();
+    if let Some(mut error) = errors.next() {
+        for other in errors {
+            error.combine(other);
+        }
+        return Err(error);
+    }
+
+    let (root_field, root_is_q_widget) = root_field.ok_or_else(|| {
+        Error::new(
+            input.ident.span(),
+            "a field with `QBox<T>` type for the top level widget is required",
+        )
+    })?;
+    let root_value = if root_is_q_widget {
+        quote! { widget }
+    } else {
+        quote! {{
+            let root = widget.dynamic_cast();
+            assert!(!root.is_null(), "unexpected type of the top level widget");
+            widget.into_q_ptr();
+            ::qt_core::QBox::from_q_ptr(root)
+        }}
+    };
+
+    let struct_name = &input.ident;
+    Ok(quote! {
         impl #struct_name {
             pub fn load() -> Self {
                 unsafe {
                     let loader = ::qt_ui_tools::QUiLoader::new_0a();
-                    let widget = loader.load_bytes(include_bytes!(#ui_file_path));
+                    let widget = loader.load_bytes(include_bytes!(concat!(
+                        env!("CARGO_MANIFEST_DIR"),
+                        "/",
+                        #ui_file_path
+                    )));
                     assert!(!widget.is_null(), "invalid ui file");
 
                     Self {
                         #(
-                            #fields: widget.find_child(#field_names).unwrap(),
+                            #field_names: widget.find_child(#object_names).unwrap(),
                         )*
-                        #first_field: widget,
+                        #root_field: #root_value,
                     }
                 }
             }
         }
-    };
+    })
+}
+
+pub fn ui_form(attrs: TokenStream, input: TokenStream) -> TokenStream {
+    let ui_file_path = parse_macro_input!(attrs as LitStr);
+    let input = parse_macro_input!(input as ItemStruct);
 
+    let implementation =
+        generate(&ui_file_path, &input).unwrap_or_else(|err| err.to_compile_error());
+    let expanded = quote! {
+        #input
+        #implementation
+    };
     TokenStream::from(expanded)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        is_compatible, parse_ui, pointer_type_names, ui_file_full_path, ObjectKind, UiObject,
+    };
+    use std::{env, fs, process};
+    use syn::parse_quote;
+
+    const FORM: &str = r#"<ui version="4.0">
+ <class>Form</class>
+ <widget class="QDialog" name="Form">
+  <layout class="QVBoxLayout" name="verticalLayout">
+   <item>
+    <layout class="QHBoxLayout" name="buttons">
+     <item>
+      <widget class="QPushButton" name="add"/>
+     </item>
+     <item>
+      <widget class="Line" name="line"/>
+     </item>
+    </layout>
+   </item>
+  </layout>
+  <action name="actionQuit"/>
+ </widget>
+</ui>
+"#;
+
+    #[test]
+    fn parse() {
+        let ui_file = parse_ui(FORM).unwrap();
+        assert_eq!(ui_file.root_class_name, "QDialog");
+        assert_eq!(ui_file.objects.len(), 5);
+        assert_eq!(
+            ui_file.objects["buttons"],
+            UiObject {
+                kind: ObjectKind::Layout,
+                class_name: "QHBoxLayout".into(),
+            }
+        );
+        assert_eq!(ui_file.objects["line"].class_name, "QFrame");
+        assert_eq!(ui_file.objects["actionQuit"].kind, ObjectKind::Action);
+        assert!(parse_ui("<ui></ui>").is_err());
+    }
+
+    #[test]
+    fn types() {
+        assert_eq!(
+            pointer_type_names(&parse_quote!(::qt_core::QPtr<qt_widgets::QPushButton>)),
+            Some(("QPtr".into(), "QPushButton".into()))
+        );
+        assert_eq!(pointer_type_names(&parse_quote!(QPushButton)), None);
+
+        let ui_file = parse_ui(FORM).unwrap();
+        let add = &ui_file.objects["add"];
+        assert!(is_compatible(add, "QPushButton"));
+        assert!(is_compatible(add, "QWidget"));
+        assert!(is_compatible(add, "QObject"));
+        assert!(!is_compatible(add, "QLineEdit"));
+        assert!(!is_compatible(add, "QLayout"));
+        assert!(is_compatible(&ui_file.objects["buttons"], "QLayout"));
+    }
+
+    #[test]
+    fn base_classes() {
+        let ui_file = parse_ui(FORM).unwrap();
+        let add = &ui_file.objects["add"];
+        assert!(is_compatible(add, "QAbstractButton"));
+        assert!(!is_compatible(add, "QFrame"));
+        assert!(!is_compatible(add, "QCheckBox"));
+        assert!(!is_compatible(add, "MyButton"));
+
+        let line = &ui_file.objects["line"];
+        assert!(is_compatible(line, "QFrame"));
+        assert!(!is_compatible(line, "QLabel"));
+
+        let buttons = &ui_file.objects["buttons"];
+        assert!(is_compatible(buttons, "QBoxLayout"));
+        assert!(!is_compatible(buttons, "QVBoxLayout"));
+        assert!(!is_compatible(buttons, "QWidget"));
+    }
+
+    #[test]
+    fn unknown_classes() {
+        let widget = UiObject {
+            kind: ObjectKind::Widget,
+            class_name: "MyButton".into(),
+        };
+        // Checked by `find_child` at runtime.
+        assert!(is_compatible(&widget, "QPushButton"));
+        assert!(is_compatible(&widget, "QWidget"));
+        assert!(is_compatible(&widget, "OtherWidget"));
+        assert!(!is_compatible(&widget, "QLayout"));
+        assert!(!is_compatible(&widget, "QHBoxLayout"));
+
+        let layout = UiObject {
+            kind: ObjectKind::Layout,
+            class_name: "MyLayout".into(),
+        };
+        assert!(is_compatible(&layout, "QBoxLayout"));
+        assert!(!is_compatible(&layout, "QLabel"));
+    }
+
+    #[test]
+    fn ui_file_location() {
+        let dir = env::temp_dir().join(format!("qt_macros_ui_form_{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::create_dir_all(dir.join("tests")).unwrap();
+        fs::write(dir.join("src/form.ui"), FORM).unwrap();
+        fs::write(dir.join("tests/form.ui"), FORM).unwrap();
+
+        // Files with the same name in different directories are not ambiguous.
+        assert_eq!(
+            ui_file_full_path(&dir, "src/form.ui"),
+            Ok(dir.join("src/form.ui"))
+        );
+        assert_eq!(
+            ui_file_full_path(&dir, "tests/form.ui"),
+            Ok(dir.join("tests/form.ui"))
+        );
+
+        let err = ui_file_full_path(&dir, "form.ui").unwrap_err();
+        assert!(err.contains("`form.ui` is not found"));
+        let absolute = dir.join("src/form.ui");
+        let err = ui_file_full_path(&dir, absolute.to_str().unwrap()).unwrap_err();
+        assert!(err.contains("must be relative to the crate root"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
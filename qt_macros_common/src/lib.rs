@@ -0,0 +1,12 @@
+//! Code shared by `qt_macros` and `qt_ritual_build`.
+//!
+//! This crate is a dependency of the `qt_macros` procedural macro crate, so it only
+//! depends on `syn`.
+//!
+//! This crate is part of the [ritual](https://github.com/rust-qt/ritual) project.
+
+#![forbid(unsafe_code)]
+
+pub mod q_object;
+pub mod signals;
+pub mod string_utils;
@@ -0,0 +1,350 @@
+//! Common parts of `#[derive(QObject)]` implementation used by `qt_macros`
+//! and `qt_ritual_build`.
+
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
+use syn::{
+    Attribute, Error, FnArg, GenericArgument, Ident, PathArguments, ReturnType, Signature, Type,
+};
+
+/// Types of properties, arguments and return values supported by meta-object classes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    Bool,
+    I32,
+    U32,
+    I64,
+    U64,
+    F32,
+    F64,
+    String,
+}
+
+impl ValueType {
+    /// Returns the value type corresponding to the Rust type `ty`.
+    pub fn from_type(ty: &Type) -> syn::Result<Self> {
+        let name = match ty {
+            Type::Path(path) if path.qself.is_none() => path.path.get_ident().map(Ident::to_string),
+            _ => None,
+        };
+        let value_type =
+            match name.as_deref() {
+                Some("bool") => ValueType::Bool,
+                Some("i32") => ValueType::I32,
+                Some("u32") => ValueType::U32,
+                Some("i64") => ValueType::I64,
+                Some("u64") => ValueType::U64,
+                Some("f32") => ValueType::F32,
+                Some("f64") => ValueType::F64,
+                Some("String") => ValueType::String,
+                _ => return Err(Error::new_spanned(
+                    ty,
+                    "unsupported type (expected one of bool, i32, u32, i64, u64, f32, f64, String)",
+                )),
+            };
+        Ok(value_type)
+    }
+
+    /// Returns the C++ type corresponding to this type.
+    pub fn cpp_type(self) -> &'static str {
+        match self {
+            ValueType::Bool => "bool",
+            ValueType::I32 => "int",
+            ValueType::U32 => "uint",
+            ValueType::I64 => "qlonglong",
+            ValueType::U64 => "qulonglong",
+            ValueType::F32 => "float",
+            ValueType::F64 => "double",
+            ValueType::String => "QString",
+        }
+    }
+
+    /// Returns the C++ type used for passing a value of this type as an argument.
+    pub fn cpp_argument_type(self) -> &'static str {
+        match self {
+            ValueType::String => "const QString&",
+            _ => self.cpp_type(),
+        }
+    }
+
+    /// Returns the type used for passing a value of this type to a Rust function.
+    pub fn ffi_argument_type(self) -> &'static str {
+        match self {
+            ValueType::String => "const QString*",
+            _ => self.cpp_type(),
+        }
+    }
+
+    /// Returns the expression converting `name` received from a Rust function
+    /// to the C++ argument type.
+    pub fn cpp_argument(self, name: &str) -> String {
+        match self {
+            ValueType::String => format!("*{}", name),
+            _ => name.to_string(),
+        }
+    }
+
+    /// Returns the expression passing `name` to a Rust function.
+    pub fn ffi_argument(self, name: &str) -> String {
+        match self {
+            ValueType::String => format!("&{}", name),
+            _ => name.to_string(),
+        }
+    }
+}
+
+/// Kind of a field of a struct with `#[derive(QObject)]` marked with a `qt` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    /// `#[qt(property)]`
+    Property,
+    /// `#[qt(signal)]`
+    Signal,
+}
+
+/// Returns the kind of the field with `attrs`, or `None` if it doesn't have a `qt` attribute.
+pub fn field_kind(attrs: &[Attribute]) -> syn::Result<Option<FieldKind>> {
+    for attr in attrs {
+        if attr.path.is_ident("qt") {
+            let arg: Ident = attr.parse_args()?;
+            let kind = if arg == "property" {
+                FieldKind::Property
+            } else if arg == "signal" {
+                FieldKind::Signal
+            } else {
+                return Err(Error::new(arg.span(), "expected `property` or `signal`"));
+            };
+            return Ok(Some(kind));
+        }
+    }
+    Ok(None)
+}
+
+/// Returns argument types of the signal declared by a field of type `ty`,
+/// e.g. `qt_core::DeclaredSignal<(i32, String)>`.
+pub fn signal_arguments(ty: &Type) -> syn::Result<Vec<ValueType>> {
+    let error = || {
+        Error::new_spanned(
+            ty,
+            "expected `DeclaredSignal<(...)>` with a tuple of argument types",
+        )
+    };
+    let segment = match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last(),
+        _ => None,
+    }
+    .ok_or_else(error)?;
+    if segment.ident != "DeclaredSignal" {
+        return Err(error());
+    }
+    let arguments = match &segment.arguments {
+        PathArguments::AngleBracketed(arguments) if arguments.args.len() == 1 => &arguments.args,
+        _ => return Err(error()),
+    };
+    match arguments.first() {
+        Some(GenericArgument::Type(Type::Tuple(tuple))) => {
+            tuple.elems.iter().map(ValueType::from_type).collect()
+        }
+        _ => Err(error()),
+    }
+}
+
+/// Returns an error about a method that can't be invokable.
+fn invokable_error(span: impl Spanned, message: String) -> Error {
+    Error::new(
+        span.span(),
+        format!(
+            "{}; declare methods that should not be invokable \
+             in a separate impl block without `#[invokables]`",
+            message
+        ),
+    )
+}
+
+/// Checks that the method with signature `sig` declared in an impl block with `#[invokables]`
+/// can be exposed as an invokable method.
+pub fn check_invokable_signature(sig: &Signature) -> syn::Result<()> {
+    let name = sig.ident.unraw();
+    if !sig.generics.params.is_empty() {
+        return Err(invokable_error(
+            &sig.generics,
+            format!("invokable method `{}` must not be generic", name),
+        ));
+    }
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(invokable_error(
+            asyncness,
+            format!("invokable method `{}` must not be async", name),
+        ));
+    }
+    for arg in &sig.inputs {
+        if let FnArg::Typed(arg) = arg {
+            ValueType::from_type(&arg.ty).map_err(|err| {
+                invokable_error(
+                    &arg.ty,
+                    format!("invalid argument of invokable method `{}`: {}", name, err),
+                )
+            })?;
+        }
+    }
+    if let ReturnType::Type(_, ty) = &sig.output {
+        ValueType::from_type(ty).map_err(|err| {
+            invokable_error(
+                ty,
+                format!(
+                    "invalid return type of invokable method `{}`: {}",
+                    name, err
+                ),
+            )
+        })?;
+    }
+    Ok(())
+}
+
+/// Converts `snake_case` name to `lowerCamelCase`.
+pub fn lower_camel_case(name: &str) -> String {
+    let mut result = String::new();
+    let mut capitalize_next = false;
+    for c in name.chars() {
+        if c == '_' {
+            capitalize_next = !result.is_empty();
+        } else if capitalize_next {
+            result.extend(c.to_uppercase());
+            capitalize_next = false;
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Converts `snake_case` name to `UpperCamelCase`.
+pub fn upper_camel_case(name: &str) -> String {
+    let name = lower_camel_case(name);
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Returns the normalized C++ signature of the signal `name` with `arguments`
+/// used for identifying it in the meta-object system, e.g. `valueChanged(int,QString)`.
+pub fn signal_signature(name: &str, arguments: &[ValueType]) -> String {
+    let arguments = arguments
+        .iter()
+        .map(|arg| arg.cpp_type())
+        .collect::<Vec<_>>()
+        .join(",");
+    format!("{}({})", lower_camel_case(name), arguments)
+}
+
+/// Returns name of the function exchanging data between the C++ class `class_name`
+/// and the Rust struct, e.g. `ritual_qobject_Counter_get_value`.
+pub fn ffi_function_name(class_name: &str, suffix: &str) -> String {
+    format!("ritual_qobject_{}_{}", class_name, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        check_invokable_signature, ffi_function_name, field_kind, lower_camel_case,
+        signal_arguments, signal_signature, upper_camel_case, FieldKind, ValueType,
+    };
+    use syn::{parse_quote, Attribute, ImplItem, ItemImpl};
+
+    #[test]
+    fn value_types() {
+        assert_eq!(
+            ValueType::from_type(&parse_quote!(bool)).unwrap(),
+            ValueType::Bool
+        );
+        assert_eq!(
+            ValueType::from_type(&parse_quote!(String)).unwrap(),
+            ValueType::String
+        );
+        assert!(ValueType::from_type(&parse_quote!(i8)).is_err());
+        assert!(ValueType::from_type(&parse_quote!(Vec<i32>)).is_err());
+        assert!(ValueType::from_type(&parse_quote!(&str)).is_err());
+        assert_eq!(ValueType::U64.cpp_type(), "qulonglong");
+        assert_eq!(ValueType::String.cpp_argument_type(), "const QString&");
+        assert_eq!(ValueType::String.ffi_argument("value"), "&value");
+        assert_eq!(ValueType::F64.ffi_argument("value"), "value");
+        assert_eq!(ValueType::String.cpp_argument("value"), "*value");
+    }
+
+    #[test]
+    fn field_kinds() {
+        let property: Attribute = parse_quote!(#[qt(property)]);
+        let signal: Attribute = parse_quote!(#[qt(signal)]);
+        let other: Attribute = parse_quote!(#[serde(skip)]);
+        let invalid: Attribute = parse_quote!(#[qt(slot)]);
+        assert_eq!(
+            field_kind(&[other.clone(), property]).unwrap(),
+            Some(FieldKind::Property)
+        );
+        assert_eq!(field_kind(&[signal]).unwrap(), Some(FieldKind::Signal));
+        assert_eq!(field_kind(&[other]).unwrap(), None);
+        assert_eq!(field_kind(&[]).unwrap(), None);
+        assert!(field_kind(&[invalid]).is_err());
+    }
+
+    #[test]
+    fn signals() {
+        assert_eq!(
+            signal_arguments(&parse_quote!(qt_core::DeclaredSignal<(i32, String)>)).unwrap(),
+            vec![ValueType::I32, ValueType::String]
+        );
+        assert_eq!(
+            signal_arguments(&parse_quote!(DeclaredSignal<()>)).unwrap(),
+            vec![]
+        );
+        assert!(signal_arguments(&parse_quote!(DeclaredSignal<i32>)).is_err());
+        assert!(signal_arguments(&parse_quote!(DeclaredSignal<(Vec<i32>,)>)).is_err());
+        assert!(signal_arguments(&parse_quote!(Signal<(i32,)>)).is_err());
+        assert_eq!(
+            signal_signature("value_overflowed", &[ValueType::I32, ValueType::String]),
+            "valueOverflowed(int,QString)"
+        );
+        assert_eq!(signal_signature("reset", &[]), "reset()");
+    }
+
+    #[test]
+    fn invokable_signatures() {
+        let input: ItemImpl = parse_quote! {
+            impl Counter {
+                fn add(&mut self, value: i32) {}
+                fn describe(&self, prefix: String) -> String {}
+                fn generic<T>(&mut self, value: i32) {}
+                async fn asynchronous(&mut self, value: i32) {}
+                fn slice_argument(&mut self, values: &[i32]) {}
+                fn vec_result(&self) -> Vec<i32> {}
+            }
+        };
+        let results = input
+            .items
+            .iter()
+            .map(|item| match item {
+                ImplItem::Method(method) => check_invokable_signature(&method.sig).is_ok(),
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(results, vec![true, true, false, false, false, false]);
+    }
+
+    #[test]
+    fn camel_case() {
+        assert_eq!(lower_camel_case("value"), "value");
+        assert_eq!(lower_camel_case("item_label"), "itemLabel");
+        assert_eq!(lower_camel_case("_private_value"), "privateValue");
+        assert_eq!(upper_camel_case("item_label"), "ItemLabel");
+    }
+
+    #[test]
+    fn function_names() {
+        assert_eq!(
+            ffi_function_name("Counter", "get_value"),
+            "ritual_qobject_Counter_get_value"
+        );
+    }
+}
@@ -0,0 +1,185 @@
+//! Names of signal wrapper types used by the `signals` attribute of `qt_macros`.
+
+use crate::string_utils::{to_class_case, to_snake_case};
+use syn::{Error, GenericArgument, PathArguments, Type};
+
+/// Returns the single generic type argument of the last segment of `ty`,
+/// e.g. `T` for `cpp_core::Ref<T>`.
+fn generic_argument(ty: &Type) -> Option<&Type> {
+    let path = match ty {
+        Type::Path(path) if path.qself.is_none() => &path.path,
+        _ => return None,
+    };
+    let arguments = match &path.segments.last()?.arguments {
+        PathArguments::AngleBracketed(arguments) if arguments.args.len() == 1 => arguments,
+        _ => return None,
+    };
+    match arguments.args.first()? {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
+}
+
+/// Returns the caption of `ty` used in the name of the wrapper type. This is the same
+/// caption `ritual` generates for the corresponding type of the signal argument.
+fn caption(ty: &Type) -> syn::Result<String> {
+    match ty {
+        Type::Paren(ty) => caption(&ty.elem),
+        Type::Group(ty) => caption(&ty.elem),
+        Type::Tuple(tuple) => Ok(tuple
+            .elems
+            .iter()
+            .map(caption)
+            .collect::<syn::Result<Vec<_>>>()?
+            .join("_")),
+        Type::Ptr(ptr) => caption(&ptr.elem),
+        Type::Reference(reference) => caption(&reference.elem),
+        Type::Path(path) if path.qself.is_none() => {
+            let segment = path
+                .path
+                .segments
+                .last()
+                .ok_or_else(|| Error::new_spanned(ty, "expected a type name"))?;
+            let name = segment.ident.to_string();
+            match name.as_str() {
+                "Ptr" | "Ref" | "CppBox" | "SharedPtr" => {
+                    let target = generic_argument(ty).ok_or_else(|| {
+                        Error::new_spanned(
+                            ty,
+                            format!("expected a single type argument of {}", name),
+                        )
+                    })?;
+                    return caption(target);
+                }
+                // C++ types of Qt signals are mapped to `std::os::raw` types.
+                "i32" => return Ok("int".to_string()),
+                "u32" => return Ok("uint".to_string()),
+                "f32" => return Ok("float".to_string()),
+                "f64" => return Ok("double".to_string()),
+                "bool" | "i8" | "u8" | "i16" | "u16" | "i64" | "u64" | "isize" | "usize" => {
+                    return Ok(name);
+                }
+                _ => {}
+            }
+            let name = if name.starts_with("c_") {
+                &name[2..]
+            } else {
+                &name
+            };
+            let mut result = to_snake_case(name);
+            if let PathArguments::AngleBracketed(arguments) = &segment.arguments {
+                let mut argument_captions = Vec::new();
+                for argument in &arguments.args {
+                    match argument {
+                        GenericArgument::Type(ty) => argument_captions.push(caption(ty)?),
+                        _ => {
+                            return Err(Error::new_spanned(
+                                argument,
+                                "only type arguments are supported",
+                            ))
+                        }
+                    }
+                }
+                result = format!("{}_{}", result, argument_captions.join("_"));
+            }
+            Ok(result)
+        }
+        _ => Err(Error::new_spanned(ty, "unsupported signal argument type")),
+    }
+}
+
+/// Returns the name of the signal wrapper type generated by `ritual` for a signal
+/// with `arguments`, e.g. `SignalOfInt` for `(i32,)` and `SignalNoArgs` for `()`.
+///
+/// `i32`, `u32`, `f32` and `f64` are treated as `c_int`, `c_uint`, `c_float` and `c_double`
+/// because signal arguments are declared with C++ types.
+pub fn signal_wrapper_name(arguments: &[Type]) -> syn::Result<String> {
+    if arguments.is_empty() {
+        return Ok("SignalNoArgs".to_string());
+    }
+    let captions = arguments
+        .iter()
+        .map(caption)
+        .collect::<syn::Result<Vec<_>>>()?;
+    Ok(to_class_case(&format!("SignalOf_{}", captions.join("_"))))
+}
+
+/// Returns argument types of `ty` if it's a `Signal<(...)>` type.
+///
+/// Returns `Ok(None)` if `ty` is not a `Signal`, and an error if its argument is not a tuple.
+pub fn signal_arguments(ty: &Type) -> syn::Result<Option<Vec<Type>>> {
+    let is_signal = match ty {
+        Type::Path(path) if path.qself.is_none() => path
+            .path
+            .segments
+            .last()
+            .map_or(false, |segment| segment.ident == "Signal"),
+        _ => false,
+    };
+    if !is_signal {
+        return Ok(None);
+    }
+    match generic_argument(ty) {
+        Some(Type::Tuple(tuple)) => Ok(Some(tuple.elems.iter().cloned().collect())),
+        _ => Err(Error::new_spanned(
+            ty,
+            "expected a tuple of argument types, e.g. `Signal<(i32,)>`",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{signal_arguments, signal_wrapper_name};
+    use syn::{parse_quote, Type};
+
+    fn wrapper_name(ty: Type) -> syn::Result<String> {
+        signal_wrapper_name(&signal_arguments(&ty)?.unwrap())
+    }
+
+    #[test]
+    fn wrapper_names() {
+        assert_eq!(
+            wrapper_name(parse_quote!(Signal<()>)).unwrap(),
+            "SignalNoArgs"
+        );
+        assert_eq!(
+            wrapper_name(parse_quote!(Signal<(i32,)>)).unwrap(),
+            "SignalOfInt"
+        );
+        assert_eq!(
+            wrapper_name(parse_quote!(qt_core::Signal<(::std::os::raw::c_int,)>)).unwrap(),
+            "SignalOfInt"
+        );
+        assert_eq!(
+            wrapper_name(parse_quote!(Signal<(bool, f64)>)).unwrap(),
+            "SignalOfBoolDouble"
+        );
+        assert_eq!(
+            wrapper_name(parse_quote!(Signal<(i64,)>)).unwrap(),
+            "SignalOfI64"
+        );
+        assert_eq!(
+            wrapper_name(parse_quote!(Signal<(Ref<QString>,)>)).unwrap(),
+            "SignalOfQString"
+        );
+        assert_eq!(
+            wrapper_name(parse_quote!(
+                Signal<(cpp_core::Ref<QModelIndex>, c_int, Ref<QVectorOfInt>)>
+            ))
+            .unwrap(),
+            "SignalOfQModelIndexIntQVectorOfInt"
+        );
+    }
+
+    #[test]
+    fn invalid_signals() {
+        assert!(signal_arguments(&parse_quote!(SignalOfInt))
+            .unwrap()
+            .is_none());
+        assert!(signal_arguments(&parse_quote!(Signal<i32>)).is_err());
+        assert!(signal_arguments(&parse_quote!(Signal)).is_err());
+        assert!(wrapper_name(parse_quote!(Signal<([i32; 2],)>)).is_err());
+        assert!(wrapper_name(parse_quote!(Signal<(Ref,)>)).is_err());
+    }
+}
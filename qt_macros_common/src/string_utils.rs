@@ -0,0 +1,107 @@
+//! Case conversions used in names of generated types.
+//!
+//! This is the subset of `ritual_common::string_utils` needed to reproduce names generated
+//! by `ritual`. `ritual_common` is not used here because it's too heavy for a dependency
+//! of a procedural macro crate. `qt_ritual_common` tests that both implementations match.
+
+/// Iterator over words in a camel-case or snake-case string.
+struct WordIterator<'a> {
+    string: &'a str,
+    index: usize,
+}
+
+impl<'a> WordIterator<'a> {
+    fn new(string: &str) -> WordIterator<'_> {
+        WordIterator { string, index: 0 }
+    }
+}
+
+fn char_at(str: &str, index: usize) -> char {
+    str[index..=index].chars().next().unwrap()
+}
+
+impl<'a> Iterator for WordIterator<'a> {
+    type Item = &'a str;
+    fn next(&mut self) -> Option<&'a str> {
+        while self.index < self.string.len() && &self.string[self.index..=self.index] == "_" {
+            self.index += 1;
+        }
+        if self.index >= self.string.len() {
+            return None;
+        }
+        let mut i = self.index + 1;
+        let current_word_is_number =
+            i < self.string.len() && char_at(self.string, i).is_ascii_digit();
+        while i < self.string.len() {
+            let current = char_at(self.string, i);
+            if current == '_' || current.is_uppercase() {
+                break;
+            }
+            if !current_word_is_number && current.is_ascii_digit() {
+                break;
+            }
+            i += 1;
+        }
+        let result = &self.string[self.index..i];
+        self.index = i;
+        Some(result)
+    }
+}
+
+fn replace_all_sub_vecs(parts: &mut Vec<String>, needle: &[&str]) {
+    let mut any_found = true;
+    while any_found {
+        any_found = false;
+        if parts.len() + 1 >= needle.len() {
+            for i in 0..parts.len() + 1 - needle.len() {
+                if parts[i..i + needle.len()] == needle[..] {
+                    for _ in 0..needle.len() - 1 {
+                        parts.remove(i + 1);
+                    }
+                    parts[i] = needle.join("");
+                    any_found = true;
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Converts `string` to class case ("WordWordWord").
+pub fn to_class_case(string: &str) -> String {
+    WordIterator::new(string)
+        .map(|word| {
+            if char_at(word, 0).is_ascii_digit() {
+                word.to_uppercase()
+            } else {
+                format!("{}{}", word[0..1].to_uppercase(), word[1..].to_lowercase())
+            }
+        })
+        .collect()
+}
+
+/// Converts `string` to snake case ("word_word_word").
+pub fn to_snake_case(string: &str) -> String {
+    let mut parts = WordIterator::new(string)
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>();
+    replace_all_sub_vecs(&mut parts, &["na", "n"]);
+    replace_all_sub_vecs(&mut parts, &["open", "g", "l"]);
+    replace_all_sub_vecs(&mut parts, &["i", "o"]);
+    replace_all_sub_vecs(&mut parts, &["2", "d"]);
+    replace_all_sub_vecs(&mut parts, &["3", "d"]);
+    replace_all_sub_vecs(&mut parts, &["4", "d"]);
+    let mut result = String::new();
+    for (i, part) in parts.into_iter().enumerate() {
+        if part.is_empty() {
+            continue;
+        }
+        let all_digits = part.chars().all(|c| c.is_ascii_digit());
+        let ends_with_digit = result.ends_with(|c: char| c.is_ascii_digit());
+        if i > 0 && (!all_digits || ends_with_digit) {
+            result.push('_');
+        }
+        result.push_str(&part);
+    }
+    result
+}
@@ -1,5 +1,5 @@
 use cpp_core::NullPtr;
-use moqt_core::{QObject, QPtr, SlotOfInt};
+use moqt_core::{post_to_thread, ClosureConnection, ConnectionType, QObject, QPtr, SlotOfInt};
 use std::cell::RefCell;
 use std::ffi::CStr;
 use std::rc::Rc;
@@ -59,3 +59,96 @@ fn closure_slot_connect() {
         assert_eq!(*counter.borrow(), 6);
     }
 }
+
+#[test]
+fn signal_connect_closure() {
+    unsafe {
+        let obj1 = QObject::new_0a();
+        let counter = Rc::new(RefCell::new(0));
+        let counter_handle = Rc::clone(&counter);
+        let connection: ClosureConnection<SlotOfInt> =
+            obj1.object_name_changed().connect_closure(move |arg: i32| {
+                *counter_handle.borrow_mut() += arg;
+            });
+        assert!(connection.connection().is_valid());
+
+        let args = QObject::next_connect_args();
+        assert_eq!(args.sender().as_raw_ptr(), obj1.as_raw_ptr());
+
+        let signal = CStr::from_ptr(args.signal()).to_str().unwrap();
+        assert_eq!(signal, "2objectNameChanged(int)");
+
+        let slot_as_qobject: QPtr<QObject> = connection.slot().static_upcast();
+        assert_eq!(args.receiver().as_raw_ptr(), slot_as_qobject.as_raw_ptr());
+
+        let method = CStr::from_ptr(args.method()).to_str().unwrap();
+        assert_eq!(method, "1slot_(int)");
+
+        connection.slot().slot(3);
+        assert_eq!(*counter.borrow(), 3);
+
+        assert!(connection.disconnect());
+    }
+}
+
+#[test]
+fn signal_connect_queued() {
+    unsafe {
+        let obj1 = QObject::new_0a();
+        let context = QObject::new_0a();
+        let connection: ClosureConnection<SlotOfInt> = obj1
+            .object_name_changed()
+            .connect_queued(&context, |_arg: i32| {});
+        assert!(connection.connection().is_valid());
+        let slot_as_qobject: QPtr<QObject> = connection.slot().static_upcast();
+
+        // The slot is deleted when the context is deleted.
+        let args = QObject::next_connect_args();
+        assert_eq!(args.sender().as_raw_ptr(), context.as_raw_ptr());
+        assert_eq!(args.receiver().as_raw_ptr(), slot_as_qobject.as_raw_ptr());
+        let method = CStr::from_ptr(args.method()).to_str().unwrap();
+        assert_eq!(method, "1deleteLater()");
+
+        let args = QObject::next_connect_args();
+        assert_eq!(args.sender().as_raw_ptr(), obj1.as_raw_ptr());
+        assert_eq!(args.receiver().as_raw_ptr(), slot_as_qobject.as_raw_ptr());
+        assert_eq!(args.connection_type(), ConnectionType::QueuedConnection);
+
+        let method = CStr::from_ptr(args.method()).to_str().unwrap();
+        assert_eq!(method, "1slot_(int)");
+
+        assert!(connection.disconnect());
+    }
+}
+
+#[test]
+fn post_to_thread_connections() {
+    unsafe {
+        let context = QObject::new_0a();
+        post_to_thread(&context, || {});
+
+        let args = QObject::next_connect_args();
+        assert_eq!(args.sender().as_raw_ptr(), context.as_raw_ptr());
+        let signal = CStr::from_ptr(args.signal()).to_str().unwrap();
+        assert_eq!(signal, "2destroyed(QObject *)");
+        let method = CStr::from_ptr(args.method()).to_str().unwrap();
+        assert_eq!(method, "1deleteLater()");
+        assert_eq!(args.connection_type(), ConnectionType::AutoConnection);
+        let slot = args.receiver().as_raw_ptr();
+
+        // The closure is called first, and then the slot deletes itself.
+        let args = QObject::next_connect_args();
+        assert_eq!(args.receiver().as_raw_ptr(), slot);
+        let method = CStr::from_ptr(args.method()).to_str().unwrap();
+        assert_eq!(method, "1slot_()");
+        assert_eq!(args.connection_type(), ConnectionType::QueuedConnection);
+        let signal_object = args.sender().as_raw_ptr();
+
+        let args = QObject::next_connect_args();
+        assert_eq!(args.sender().as_raw_ptr(), signal_object);
+        assert_eq!(args.receiver().as_raw_ptr(), slot);
+        let method = CStr::from_ptr(args.method()).to_str().unwrap();
+        assert_eq!(method, "1deleteLater()");
+        assert_eq!(args.connection_type(), ConnectionType::QueuedConnection);
+    }
+}
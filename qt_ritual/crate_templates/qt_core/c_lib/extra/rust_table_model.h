@@ -0,0 +1,150 @@
+// Item model implemented in Rust.
+// See `qt_core::TableModel` trait for the Rust side of this class.
+
+#include <QAbstractTableModel>
+
+// Functions implemented in Rust. `data` is the pointer passed
+// to the `RustTableModel` constructor.
+struct RustTableModelCallbacks {
+    void (*drop)(void* data);
+    int (*row_count)(void* data);
+    int (*column_count)(void* data);
+    void (*data)(void* data, const QModelIndex* index, int role, QVariant* output);
+    bool (*set_data)(void* data, const QModelIndex* index, const QVariant* value, int role);
+    int (*flags)(void* data, const QModelIndex* index);
+    void (*header_data)(void* data, int section, int orientation, int role, QVariant* output);
+    int (*role_count)(void* data);
+    int (*role_name)(void* data, int index, QByteArray* output);
+};
+
+// List or table model that forwards calls of its virtual functions to Rust.
+class RustTableModel : public QAbstractTableModel {
+public:
+    RustTableModel(const void* callbacks, void* data, QObject* parent)
+        : QAbstractTableModel(parent),
+          m_callbacks(static_cast<const RustTableModelCallbacks*>(callbacks)),
+          m_data(data) {}
+
+    ~RustTableModel() {
+        m_callbacks->drop(m_data);
+    }
+
+    void* rustData() const {
+        return m_data;
+    }
+
+    int rowCount(const QModelIndex& parent = QModelIndex()) const override {
+        if (parent.isValid()) {
+            return 0;
+        }
+        return m_callbacks->row_count(m_data);
+    }
+
+    int columnCount(const QModelIndex& parent = QModelIndex()) const override {
+        if (parent.isValid()) {
+            return 0;
+        }
+        return m_callbacks->column_count(m_data);
+    }
+
+    QVariant data(const QModelIndex& index, int role = Qt::DisplayRole) const override {
+        QVariant result;
+        if (index.isValid()) {
+            m_callbacks->data(m_data, &index, role, &result);
+        }
+        return result;
+    }
+
+    bool setData(const QModelIndex& index, const QVariant& value, int role = Qt::EditRole) override {
+        if (!index.isValid()) {
+            return false;
+        }
+        if (!m_callbacks->set_data(m_data, &index, &value, role)) {
+            return false;
+        }
+        // Emitted after the Rust callback returns, so views can read the new data.
+        emit dataChanged(index, index);
+        return true;
+    }
+
+    Qt::ItemFlags flags(const QModelIndex& index) const override {
+        if (!index.isValid()) {
+            return Qt::NoItemFlags;
+        }
+        return Qt::ItemFlags(m_callbacks->flags(m_data, &index));
+    }
+
+    QVariant headerData(int section, Qt::Orientation orientation, int role = Qt::DisplayRole) const override {
+        QVariant result;
+        m_callbacks->header_data(m_data, section, orientation, role, &result);
+        return result;
+    }
+
+    QHash<int, QByteArray> roleNames() const override {
+        int count = m_callbacks->role_count(m_data);
+        if (count == 0) {
+            return QAbstractTableModel::roleNames();
+        }
+        QHash<int, QByteArray> result;
+        for (int i = 0; i < count; i++) {
+            QByteArray name;
+            int role = m_callbacks->role_name(m_data, i, &name);
+            // A negative role is returned if the Rust function failed.
+            if (role >= 0) {
+                result.insert(role, name);
+            }
+        }
+        return result;
+    }
+
+    // Public versions of protected functions of `QAbstractItemModel`
+    // that must be called when the Rust data changes.
+
+    void beginInsertRows(const QModelIndex& parent, int first, int last) {
+        QAbstractTableModel::beginInsertRows(parent, first, last);
+    }
+
+    void endInsertRows() {
+        QAbstractTableModel::endInsertRows();
+    }
+
+    void beginRemoveRows(const QModelIndex& parent, int first, int last) {
+        QAbstractTableModel::beginRemoveRows(parent, first, last);
+    }
+
+    void endRemoveRows() {
+        QAbstractTableModel::endRemoveRows();
+    }
+
+    void beginInsertColumns(const QModelIndex& parent, int first, int last) {
+        QAbstractTableModel::beginInsertColumns(parent, first, last);
+    }
+
+    void endInsertColumns() {
+        QAbstractTableModel::endInsertColumns();
+    }
+
+    void beginRemoveColumns(const QModelIndex& parent, int first, int last) {
+        QAbstractTableModel::beginRemoveColumns(parent, first, last);
+    }
+
+    void endRemoveColumns() {
+        QAbstractTableModel::endRemoveColumns();
+    }
+
+    void beginResetModel() {
+        QAbstractTableModel::beginResetModel();
+    }
+
+    void endResetModel() {
+        QAbstractTableModel::endResetModel();
+    }
+
+    void emitDataChanged(const QModelIndex& topLeft, const QModelIndex& bottomRight) {
+        emit dataChanged(topLeft, bottomRight);
+    }
+
+private:
+    const RustTableModelCallbacks* m_callbacks;
+    void* m_data;
+};
@@ -0,0 +1,3 @@
+fn main() {
+    qt_ritual_build::add_qobjects("src/lib.rs");
+}
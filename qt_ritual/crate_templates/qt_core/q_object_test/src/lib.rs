@@ -0,0 +1,23 @@
+//! A class used by tests of `#[derive(QObject)]`. The C++ class is generated
+//! by `qt_ritual_build::add_qobjects` called from the build script.
+
+use qt_core::{invokables, DeclaredSignal, QObject};
+
+#[derive(Default, QObject)]
+pub struct Counter {
+    #[qt(property)]
+    pub value: i32,
+    #[qt(property)]
+    pub item_label: String,
+    #[qt(signal)]
+    pub overflowed: DeclaredSignal<(i32, String)>,
+    pub history: Vec<i32>,
+}
+
+#[invokables]
+impl Counter {
+    pub fn add(&mut self, value: i32) {
+        self.history.push(value);
+        self.value += value;
+    }
+}
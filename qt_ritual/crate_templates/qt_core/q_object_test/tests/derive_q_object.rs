@@ -0,0 +1,105 @@
+use cpp_core::NullPtr;
+use qt_core::{qs, QObject, QVariant, SlotOfInt};
+use qt_core_q_object_test::Counter;
+use std::cell::Cell;
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::rc::Rc;
+
+fn c_str(bytes: &'static [u8]) -> *const c_char {
+    bytes.as_ptr() as *const c_char
+}
+
+#[test]
+fn meta_object() {
+    unsafe {
+        let object = Counter::create(NullPtr);
+        let meta_object = object.meta_object();
+        assert_eq!(
+            CStr::from_ptr(meta_object.class_name()).to_str().unwrap(),
+            "Counter"
+        );
+        assert!(meta_object.index_of_property(c_str(b"value\0")) >= 0);
+        assert!(meta_object.index_of_property(c_str(b"itemLabel\0")) >= 0);
+        assert!(meta_object.index_of_property(c_str(b"history\0")) < 0);
+        assert!(meta_object.index_of_signal(c_str(b"itemLabelChanged()\0")) >= 0);
+        assert!(meta_object.index_of_signal(c_str(b"overflowed(int,QString)\0")) >= 0);
+        assert!(meta_object.index_of_method(c_str(b"add(int)\0")) >= 0);
+    }
+}
+
+#[test]
+fn properties() {
+    unsafe {
+        let object = Counter::create(NullPtr);
+        Counter::update(&object, |counter| {
+            counter.add(5);
+            counter.item_label = "items".to_string();
+        });
+        assert_eq!(object.property(c_str(b"value\0")).to_int_0a(), 5);
+        assert_eq!(
+            object
+                .property(c_str(b"itemLabel\0"))
+                .to_string()
+                .to_std_string(),
+            "items"
+        );
+
+        assert!(object.set_property(c_str(b"value\0"), &QVariant::from_int(7)));
+        assert!(object.set_property(c_str(b"itemLabel\0"), &QVariant::from_q_string(&qs("text"))));
+        let counter = Counter::from_object(&object).unwrap().borrow();
+        assert_eq!(counter.value, 7);
+        assert_eq!(counter.item_label, "text");
+        assert_eq!(counter.history, vec![5]);
+        drop(counter);
+
+        let other = QObject::new_0a();
+        assert!(Counter::from_object(&other).is_none());
+    }
+}
+
+#[test]
+fn signals() {
+    unsafe {
+        let object = Counter::create(NullPtr);
+        let received = Rc::new(Cell::new(0));
+        let received2 = Rc::clone(&received);
+        let slot = SlotOfInt::new(NullPtr, move |value| {
+            received2.set(value);
+        });
+        let c = Counter::overflowed(&object).connect(&slot);
+        assert!(c.is_valid());
+
+        Counter::emit_overflowed(&object, 42, "too many");
+        assert_eq!(received.get(), 42);
+    }
+}
+
+#[test]
+fn reentrant_access() {
+    unsafe {
+        let object = Counter::create(NullPtr);
+        Counter::update(&object, |counter| {
+            counter.value = 3;
+            counter.item_label = "items".to_string();
+            // The value is borrowed, so C++ can't access it.
+            assert_eq!(object.property(c_str(b"value\0")).to_int_0a(), 0);
+            assert_eq!(
+                object
+                    .property(c_str(b"itemLabel\0"))
+                    .to_string()
+                    .to_std_string(),
+                ""
+            );
+            object.set_property(c_str(b"value\0"), &QVariant::from_int(7));
+        });
+        assert_eq!(object.property(c_str(b"value\0")).to_int_0a(), 3);
+        assert_eq!(
+            object
+                .property(c_str(b"itemLabel\0"))
+                .to_string()
+                .to_std_string(),
+            "items"
+        );
+    }
+}
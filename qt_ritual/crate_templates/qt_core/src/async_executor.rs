@@ -0,0 +1,247 @@
+use crate::{ArgumentsCompatible, ConnectionType, QBox, QObject, Signal, SignalNoArgs, SlotNoArgs};
+use cpp_core::{NullPtr, Ptr};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::mem::ManuallyDrop;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+/// Spawned task.
+struct Task {
+    future: Pin<Box<dyn Future<Output = ()>>>,
+    /// Slot that polls the task. It's a child of the executor's context object
+    /// and is deleted when the task completes.
+    slot: Ptr<SlotNoArgs>,
+}
+
+/// Executor state of a thread.
+struct Executor {
+    /// Object that lives in the executor's thread. Slots of the tasks are its children.
+    /// It's never deleted, so slots of unfinished tasks stay alive while the thread's
+    /// event loop is running.
+    context: Ptr<QObject>,
+    tasks: HashMap<usize, Task>,
+    next_task_id: usize,
+}
+
+thread_local! {
+    static EXECUTOR: RefCell<Option<Executor>> = RefCell::new(None);
+}
+
+/// Pointer to the wake-up signal of a task that can be sent to another thread.
+struct SignalPtr(*const SignalNoArgs);
+
+// Queued signals can be emitted from any thread.
+unsafe impl Send for SignalPtr {}
+
+struct WakerData {
+    task_id: usize,
+    /// Signal connected to the task's slot with a queued connection, so emitting it
+    /// from any thread polls the task in the executor's thread. The same signal is used
+    /// for all wake-ups of the task. It's deleted (and set to `None`) when the task completes.
+    signal: Mutex<Option<SignalPtr>>,
+    is_scheduled: AtomicBool,
+}
+
+impl WakerData {
+    fn schedule(&self) {
+        if self.is_scheduled.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        // The lock prevents the executor from deleting the signal while it's emitted.
+        if let Some(signal) = &*self.signal.lock().unwrap() {
+            unsafe {
+                (*signal.0).emit();
+            }
+        }
+    }
+
+    fn into_waker(self: Arc<Self>) -> Waker {
+        unsafe { Waker::from_raw(RawWaker::new(Arc::into_raw(self) as *const (), &VTABLE)) }
+    }
+}
+
+static VTABLE: RawWakerVTable =
+    RawWakerVTable::new(waker_clone, waker_wake, waker_wake_by_ref, waker_drop);
+
+unsafe fn waker_clone(data: *const ()) -> RawWaker {
+    let data = ManuallyDrop::new(Arc::from_raw(data as *const WakerData));
+    let cloned = Arc::clone(&data);
+    RawWaker::new(Arc::into_raw(cloned) as *const (), &VTABLE)
+}
+
+unsafe fn waker_wake(data: *const ()) {
+    let data = Arc::from_raw(data as *const WakerData);
+    data.schedule();
+}
+
+unsafe fn waker_wake_by_ref(data: *const ()) {
+    let data = ManuallyDrop::new(Arc::from_raw(data as *const WakerData));
+    data.schedule();
+}
+
+unsafe fn waker_drop(data: *const ()) {
+    drop(Arc::from_raw(data as *const WakerData));
+}
+
+/// Polls the task once. Called by the slot of the task in the executor's thread.
+unsafe fn poll_task(data: &Arc<WakerData>) {
+    data.is_scheduled.store(false, Ordering::SeqCst);
+    // The task is removed from the executor while it's polled, so that it can spawn
+    // other tasks.
+    let task = EXECUTOR.with(|executor| {
+        executor
+            .borrow_mut()
+            .as_mut()
+            .and_then(|executor| executor.tasks.remove(&data.task_id))
+    });
+    let mut task = if let Some(task) = task {
+        task
+    } else {
+        // The task has already completed.
+        return;
+    };
+    let waker = Arc::clone(data).into_waker();
+    if task
+        .future
+        .as_mut()
+        .poll(&mut Context::from_waker(&waker))
+        .is_pending()
+    {
+        EXECUTOR.with(|executor| {
+            if let Some(executor) = executor.borrow_mut().as_mut() {
+                executor.tasks.insert(data.task_id, task);
+            }
+        });
+    } else {
+        if let Some(signal) = data.signal.lock().unwrap().take() {
+            // The signal has no parent, so it's deleted when the `QBox` is dropped.
+            drop(QBox::from_raw(signal.0));
+        }
+        // The slot is being called now, so it can't be deleted immediately.
+        task.slot.static_upcast::<QObject>().delete_later();
+    }
+}
+
+/// Runs `future` on the current thread's Qt event loop.
+///
+/// The future is first polled when the event loop of the current thread processes events,
+/// so it will not make progress until `QCoreApplication::exec` (or another event loop)
+/// is running. When the future is woken up, the wake-up is posted to the event loop
+/// of this thread, so wakers can be used from any thread (e.g. by async I/O libraries
+/// running their own threads).
+///
+/// The future doesn't need to be `Send` because it's always polled in the current thread.
+///
+/// # Safety
+///
+/// The current thread must have a Qt event loop running or started later, and Qt objects
+/// used by the future must be alive when it's polled. This function must not be called
+/// after `QCoreApplication` is destroyed.
+pub unsafe fn spawn_local<F>(future: F)
+where
+    F: Future<Output = ()> + 'static,
+{
+    let signal = SignalNoArgs::new().into_raw_ptr();
+    let data = EXECUTOR.with(|executor| {
+        let mut executor = executor.borrow_mut();
+        let executor = executor.get_or_insert_with(|| Executor {
+            context: QObject::new_0a().into_ptr(),
+            tasks: HashMap::new(),
+            next_task_id: 0,
+        });
+        let task_id = executor.next_task_id;
+        executor.next_task_id += 1;
+
+        let data = Arc::new(WakerData {
+            task_id,
+            signal: Mutex::new(Some(SignalPtr(signal))),
+            is_scheduled: AtomicBool::new(false),
+        });
+        let data2 = Arc::clone(&data);
+        let slot = SlotNoArgs::new(executor.context, move || poll_task(&data2)).into_ptr();
+        (*signal).connect_with_type(ConnectionType::QueuedConnection, slot);
+        executor.tasks.insert(
+            task_id,
+            Task {
+                future: Box::pin(future),
+                slot,
+            },
+        );
+        data
+    });
+    data.schedule();
+}
+
+struct SignalFutureState {
+    is_emitted: bool,
+    waker: Option<Waker>,
+}
+
+/// Future that resolves when a signal is emitted.
+///
+/// Created by `Signal::next`. Dropping the future disconnects it from the signal.
+pub struct SignalFuture {
+    state: Rc<RefCell<SignalFutureState>>,
+    _slot: QBox<SlotNoArgs>,
+}
+
+impl Future for SignalFuture {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.state.borrow_mut();
+        if state.is_emitted {
+            Poll::Ready(())
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl fmt::Debug for SignalFuture {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("SignalFuture")
+            .field("is_emitted", &self.state.borrow().is_emitted)
+            .finish()
+    }
+}
+
+impl<A> Signal<A> {
+    /// Returns a future that resolves when this signal is emitted next time.
+    ///
+    /// The connection is made immediately, so emissions that happen after this call
+    /// and before the future is polled are not lost. Arguments of the signal are discarded.
+    ///
+    /// The future should be awaited in the thread of the sender object,
+    /// e.g. in a task created with `spawn_local`.
+    ///
+    /// # Safety
+    ///
+    /// The `QObject` referenced by `self` must be alive.
+    pub unsafe fn next(&self) -> SignalFuture
+    where
+        A: ArgumentsCompatible<()>,
+    {
+        let state = Rc::new(RefCell::new(SignalFutureState {
+            is_emitted: false,
+            waker: None,
+        }));
+        let state2 = Rc::clone(&state);
+        let slot = SlotNoArgs::new(NullPtr, move || {
+            let mut state = state2.borrow_mut();
+            state.is_emitted = true;
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        });
+        self.connect(&slot);
+        SignalFuture { state, _slot: slot }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{q_meta_object::Connection, ConnectionType, QBox, QObject, QPtr};
-use cpp_core::{CastInto, CppBox, CppDeletable, Ptr, Ref, StaticUpcast};
+use cpp_core::{CastInto, CppBox, CppDeletable, NullPtr, Ptr, Ref, StaticUpcast};
 use std::ffi::CStr;
 use std::fmt;
 use std::marker::PhantomData;
@@ -25,6 +25,27 @@ use std::marker::PhantomData;
 /// `ArgumentsCompatible` is implemented for tuples with up to 16 items.
 pub trait ArgumentsCompatible<T> {}
 
+/// Slot wrapper types that can be created from a Rust closure.
+///
+/// This trait is implemented by `ritual` for slot wrappers (`SlotNoArgs`, `SlotOfInt`, etc.)
+/// for every closure type accepted by their `new` function. It's used by
+/// `Signal::connect_closure` to select a slot wrapper suitable for a closure.
+pub trait ClosureSlot<F>: StaticUpcast<QObject> + CppDeletable + AsReceiver + Sized {
+    /// Creates a slot wrapper that calls `f` when invoked.
+    ///
+    /// # Safety
+    ///
+    /// `parent` must be either null or a valid pointer to a `QObject`.
+    unsafe fn with_closure(parent: Ptr<QObject>, f: F) -> QBox<Self>;
+}
+
+/// Argument types of a signal that can be connected to the slot wrapper `Slot`.
+///
+/// This trait is implemented by `ritual` for argument tuples of all generated
+/// slot wrappers. `Signal::connect_closure` uses it to find the slot wrapper type
+/// that matches the signal.
+pub trait SlotArguments<Slot> {}
+
 /// Reference to a particular signal or slot of a particular object.
 ///
 /// A `Receiver` can be used as the receiving side of a Qt signal connection.
@@ -115,6 +136,33 @@ impl<A> Signal<A> {
     }
 }
 
+/// Declares a signal of a Rust struct exposed as a `QObject` subclass.
+///
+/// A field of this type marked with `#[qt(signal)]` in a struct with `#[derive(QObject)]`
+/// becomes a Qt signal. The `Arguments` generic argument specifies argument types of
+/// the signal as a tuple. The field itself doesn't contain any data.
+pub struct DeclaredSignal<Arguments>(PhantomData<Arguments>);
+
+impl<A> Default for DeclaredSignal<A> {
+    fn default() -> Self {
+        DeclaredSignal(PhantomData)
+    }
+}
+
+impl<A> Clone for DeclaredSignal<A> {
+    fn clone(&self) -> Self {
+        DeclaredSignal(PhantomData)
+    }
+}
+
+impl<A> Copy for DeclaredSignal<A> {}
+
+impl<A> fmt::Debug for DeclaredSignal<A> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.write_str("DeclaredSignal")
+    }
+}
+
 /// Values that can be used for specifying the receiving side of a Qt signal connection.
 pub trait AsReceiver {
     /// Argument types expected by this receiver.
@@ -281,4 +329,227 @@ impl<SignalArguments> Signal<SignalArguments> {
     {
         self.connect_with_type(ConnectionType::AutoConnection, receiver)
     }
+
+    /// Connects this signal to the closure `f`.
+    ///
+    /// A slot wrapper object matching the signal's arguments is created internally
+    /// and added as a child of the sender, so the closure lives until the sender is
+    /// deleted or the returned connection is disconnected.
+    ///
+    /// The slot wrapper type is inferred from the signal's argument types. If several
+    /// slot wrappers accept the same arguments, specify it explicitly, e.g.
+    /// `signal.connect_closure::<SlotOfQString, _>(...)`. Closure arguments may need
+    /// type annotations.
+    ///
+    /// # Safety
+    ///
+    /// The `QObject` referenced by `self` must be alive.
+    pub unsafe fn connect_closure<S, F>(&self, f: F) -> ClosureConnection<S>
+    where
+        SignalArguments: SlotArguments<S> + ArgumentsCompatible<<S as AsReceiver>::Arguments>,
+        S: ClosureSlot<F>,
+    {
+        self.connect_closure_with_context(self.0.q_object.as_ptr(), f)
+    }
+
+    /// Connects this signal to the closure `f`, using `context` as the parent of
+    /// the internally created slot wrapper object.
+    ///
+    /// The connection is automatically removed when `context` is deleted.
+    /// See `connect_closure` for more details.
+    ///
+    /// # Safety
+    ///
+    /// The `QObject`s referenced by `self` and `context` must be alive.
+    pub unsafe fn connect_closure_with_context<S, F>(
+        &self,
+        context: impl CastInto<Ptr<QObject>>,
+        f: F,
+    ) -> ClosureConnection<S>
+    where
+        SignalArguments: SlotArguments<S> + ArgumentsCompatible<<S as AsReceiver>::Arguments>,
+        S: ClosureSlot<F>,
+    {
+        let slot = S::with_closure(context.cast_into(), f);
+        self.connect_slot(ConnectionType::AutoConnection, slot)
+    }
+
+    /// Connects this signal to the closure `f` that will be called in the thread of `context`.
+    ///
+    /// The internally created slot wrapper object is moved to the thread of `context`
+    /// and deleted together with `context`, and a queued connection is used, so the closure
+    /// is always invoked by the event loop of that thread, regardless of the thread that
+    /// emits the signal. Because of that, the closure must be `Send`.
+    ///
+    /// Argument types of the signal must be registered in Qt's meta-type system.
+    /// See `connect_closure` for more details.
+    ///
+    /// # Safety
+    ///
+    /// The `QObject`s referenced by `self` and `context` must be alive.
+    pub unsafe fn connect_queued<S, F>(
+        &self,
+        context: impl CastInto<Ptr<QObject>>,
+        f: F,
+    ) -> ClosureConnection<S>
+    where
+        SignalArguments: SlotArguments<S> + ArgumentsCompatible<<S as AsReceiver>::Arguments>,
+        S: ClosureSlot<F>,
+        F: Send + 'static,
+    {
+        let slot = S::with_closure(Ptr::null(), f);
+        move_to_context_thread(
+            slot.static_upcast::<QObject>().as_ptr(),
+            context.cast_into(),
+        );
+        self.connect_slot(ConnectionType::QueuedConnection, slot)
+    }
+
+    unsafe fn connect_slot<S>(
+        &self,
+        connection_type: ConnectionType,
+        slot: QBox<S>,
+    ) -> ClosureConnection<S>
+    where
+        SignalArguments: ArgumentsCompatible<<S as AsReceiver>::Arguments>,
+        S: StaticUpcast<QObject> + CppDeletable + AsReceiver,
+    {
+        let connection = self.connect_with_type(connection_type, &slot);
+        ClosureConnection {
+            connection,
+            sender: QPtr::new(self.0.q_object.as_ptr()),
+            signal_id: self.0.receiver_id,
+            slot: slot.into_q_ptr(),
+        }
+    }
+
+    /// Alias for `connect_closure`.
+    ///
+    /// # Safety
+    ///
+    /// The `QObject` referenced by `self` must be alive.
+    pub unsafe fn connect_fn<S, F>(&self, f: F) -> ClosureConnection<S>
+    where
+        SignalArguments: SlotArguments<S> + ArgumentsCompatible<<S as AsReceiver>::Arguments>,
+        S: ClosureSlot<F>,
+    {
+        self.connect_closure(f)
+    }
+}
+
+/// Moves `object` to the thread of `context` and schedules its deletion when `context`
+/// is deleted.
+///
+/// `object` is not added as a child of `context` because the list of children of `context`
+/// may only be modified by the thread `context` lives in.
+unsafe fn move_to_context_thread(object: Ptr<QObject>, context: Ptr<QObject>) {
+    object.move_to_thread(context.thread());
+    context.destroyed().connect(object.slot_delete_later());
+}
+
+/// Calls `f` in the thread of `context`.
+///
+/// The closure is posted to the event loop of the thread `context` lives in,
+/// and will be called once the event loop processes it. If `context` is deleted before that,
+/// the closure is dropped without being called. The internally created slot wrapper object
+/// deletes itself after the call.
+///
+/// This function can be called from any thread.
+///
+/// # Safety
+///
+/// `context` must be a valid pointer to a `QObject`.
+pub unsafe fn post_to_thread<F>(context: impl CastInto<Ptr<QObject>>, f: F)
+where
+    F: FnOnce() + Send + 'static,
+{
+    let context = context.cast_into();
+    let context_guard = QPtr::new(context);
+    let mut f = Some(f);
+    let slot = crate::SlotNoArgs::new(NullPtr, move || {
+        if context_guard.is_null() {
+            return;
+        }
+        if let Some(f) = f.take() {
+            f();
+        }
+    })
+    .into_q_ptr();
+    let slot_object = slot.static_upcast::<QObject>();
+    move_to_context_thread(slot_object.as_ptr(), context);
+
+    let signal = crate::SignalNoArgs::new();
+    signal.connect_with_type(ConnectionType::QueuedConnection, &slot);
+    // Queued calls are delivered in order, so the slot is deleted in its own thread
+    // after the closure is called.
+    signal.connect_with_type(
+        ConnectionType::QueuedConnection,
+        slot_object.slot_delete_later(),
+    );
+    signal.emit();
+}
+
+/// Connection between a signal and a Rust closure created by `Signal::connect_closure`
+/// or `Signal::connect_queued`.
+///
+/// Dropping this object does not disconnect the closure. Use `disconnect` to remove
+/// the connection and delete the closure.
+pub struct ClosureConnection<S: StaticUpcast<QObject>> {
+    connection: CppBox<Connection>,
+    sender: QPtr<QObject>,
+    signal_id: &'static CStr,
+    slot: QPtr<S>,
+}
+
+impl<S: StaticUpcast<QObject>> ClosureConnection<S> {
+    /// Returns the underlying Qt connection handle.
+    ///
+    /// You can use `is_valid()` on this object to determine if the connection was successful.
+    pub fn connection(&self) -> Ref<Connection> {
+        unsafe { self.connection.as_ref() }
+    }
+
+    /// Returns the slot wrapper object that calls the closure.
+    ///
+    /// The pointer becomes null when the slot wrapper is deleted.
+    pub fn slot(&self) -> &QPtr<S> {
+        &self.slot
+    }
+
+    /// Disconnects the closure from the signal and schedules deletion of
+    /// the slot wrapper object.
+    ///
+    /// Returns `true` if the connection was successfully removed. Returns `false`
+    /// if the sender or the slot wrapper was already deleted.
+    ///
+    /// # Safety
+    ///
+    /// The sender and the slot wrapper must not be deleted concurrently from another thread.
+    pub unsafe fn disconnect(self) -> bool
+    where
+        S: AsReceiver,
+    {
+        if self.sender.is_null() || self.slot.is_null() {
+            return false;
+        }
+        let receiver = self.slot.as_receiver();
+        let result = crate::QObject::disconnect_4a(
+            self.sender.as_ptr(),
+            self.signal_id.as_ptr(),
+            receiver.q_object.as_ptr(),
+            receiver.receiver_id.as_ptr(),
+        );
+        self.slot.as_ptr().static_upcast::<QObject>().delete_later();
+        result
+    }
+}
+
+impl<S: StaticUpcast<QObject>> fmt::Debug for ClosureConnection<S> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("ClosureConnection")
+            .field("sender", &self.sender)
+            .field("signal_id", &self.signal_id)
+            .field("slot", &self.slot)
+            .finish()
+    }
 }
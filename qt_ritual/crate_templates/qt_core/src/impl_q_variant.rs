@@ -0,0 +1,246 @@
+use crate::{q_variant, QString, QVariant};
+use cpp_core::{CppBox, CppFrom};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::ffi::CStr;
+use std::fmt;
+use std::os::raw::c_char;
+
+impl CppFrom<bool> for QVariant {
+    unsafe fn cpp_from(value: bool) -> CppBox<QVariant> {
+        QVariant::from_bool(value)
+    }
+}
+
+impl CppFrom<i32> for QVariant {
+    unsafe fn cpp_from(value: i32) -> CppBox<QVariant> {
+        QVariant::from_int(value)
+    }
+}
+
+impl CppFrom<u32> for QVariant {
+    unsafe fn cpp_from(value: u32) -> CppBox<QVariant> {
+        QVariant::from_uint(value)
+    }
+}
+
+impl CppFrom<i64> for QVariant {
+    unsafe fn cpp_from(value: i64) -> CppBox<QVariant> {
+        QVariant::from_i64(value)
+    }
+}
+
+impl CppFrom<u64> for QVariant {
+    unsafe fn cpp_from(value: u64) -> CppBox<QVariant> {
+        QVariant::from_u64(value)
+    }
+}
+
+impl CppFrom<f32> for QVariant {
+    unsafe fn cpp_from(value: f32) -> CppBox<QVariant> {
+        QVariant::from_float(value)
+    }
+}
+
+impl CppFrom<f64> for QVariant {
+    unsafe fn cpp_from(value: f64) -> CppBox<QVariant> {
+        QVariant::from_double(value)
+    }
+}
+
+impl<'a> CppFrom<&'a str> for QVariant {
+    unsafe fn cpp_from(value: &'a str) -> CppBox<QVariant> {
+        QVariant::from_q_string(&QString::from_std_str(value))
+    }
+}
+
+impl CppFrom<String> for QVariant {
+    unsafe fn cpp_from(value: String) -> CppBox<QVariant> {
+        QVariant::from_q_string(&QString::from_std_str(value))
+    }
+}
+
+/// An error returned when a `QVariant` cannot be converted to a Rust type
+/// or a `VariantValue` cannot be converted to a `QVariant`.
+pub struct VariantConversionError {
+    source_value: String,
+    target_type: &'static str,
+}
+
+/// Converts a type name returned by `QVariant` to a string.
+unsafe fn type_name(name: *const c_char) -> String {
+    if name.is_null() {
+        "invalid".to_string()
+    } else {
+        CStr::from_ptr(name).to_string_lossy().into_owned()
+    }
+}
+
+impl VariantConversionError {
+    unsafe fn new(variant: &QVariant, target_type: &'static str) -> Self {
+        VariantConversionError {
+            source_value: format!("QVariant of type {}", type_name(variant.type_name())),
+            target_type,
+        }
+    }
+
+    unsafe fn from_other_value(type_: q_variant::Type) -> Self {
+        VariantConversionError {
+            source_value: format!(
+                "VariantValue::Other({})",
+                type_name(QVariant::type_to_name(type_.to_int()))
+            ),
+            target_type: "QVariant",
+        }
+    }
+}
+
+impl fmt::Display for VariantConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} cannot be converted to {}",
+            self.source_value, self.target_type
+        )
+    }
+}
+
+impl fmt::Debug for VariantConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl Error for VariantConversionError {}
+
+macro_rules! impl_try_from_variant {
+    ($target:ty, $func:ident) => {
+        impl<'a> TryFrom<&'a QVariant> for $target {
+            type Error = VariantConversionError;
+
+            fn try_from(variant: &'a QVariant) -> Result<Self, VariantConversionError> {
+                unsafe {
+                    let mut ok = false;
+                    let value = variant.$func(&mut ok);
+                    if ok {
+                        Ok(value)
+                    } else {
+                        Err(VariantConversionError::new(variant, stringify!($target)))
+                    }
+                }
+            }
+        }
+    };
+}
+
+impl_try_from_variant!(i32, to_int_1a);
+impl_try_from_variant!(u32, to_u_int_1a);
+impl_try_from_variant!(i64, to_long_long_1a);
+impl_try_from_variant!(u64, to_u_long_long_1a);
+impl_try_from_variant!(f32, to_float_1a);
+impl_try_from_variant!(f64, to_double_1a);
+
+impl<'a> TryFrom<&'a QVariant> for bool {
+    type Error = VariantConversionError;
+
+    fn try_from(variant: &'a QVariant) -> Result<Self, VariantConversionError> {
+        unsafe {
+            if variant.can_convert(q_variant::Type::Bool.to_int()) {
+                Ok(variant.to_bool())
+            } else {
+                Err(VariantConversionError::new(variant, "bool"))
+            }
+        }
+    }
+}
+
+impl<'a> TryFrom<&'a QVariant> for String {
+    type Error = VariantConversionError;
+
+    fn try_from(variant: &'a QVariant) -> Result<Self, VariantConversionError> {
+        unsafe {
+            if variant.can_convert(q_variant::Type::String.to_int()) {
+                Ok(variant.to_string().to_std_string())
+            } else {
+                Err(VariantConversionError::new(variant, "String"))
+            }
+        }
+    }
+}
+
+/// Rust representation of a value stored in a `QVariant`.
+///
+/// Use `QVariant::to_value` to inspect a `QVariant` and
+/// `CppBox::<QVariant>::try_from` to convert the value back.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VariantValue {
+    /// An invalid (null) variant.
+    Invalid,
+    /// A `bool` value.
+    Bool(bool),
+    /// An `int` value.
+    Int(i32),
+    /// A `uint` value.
+    UInt(u32),
+    /// A `qlonglong` value.
+    LongLong(i64),
+    /// A `qulonglong` value.
+    ULongLong(u64),
+    /// A `double` value.
+    Double(f64),
+    /// A `QString` value.
+    String(String),
+    /// A value of another type. Use `QVariant`'s methods to access it.
+    Other(q_variant::Type),
+}
+
+impl QVariant {
+    /// Returns the value stored in this variant if it has one of the types
+    /// supported by `VariantValue`.
+    pub unsafe fn to_value(&self) -> VariantValue {
+        let type_ = self.type_();
+        if type_ == q_variant::Type::Invalid {
+            VariantValue::Invalid
+        } else if type_ == q_variant::Type::Bool {
+            VariantValue::Bool(self.to_bool())
+        } else if type_ == q_variant::Type::Int {
+            VariantValue::Int(self.to_int_0a())
+        } else if type_ == q_variant::Type::UInt {
+            VariantValue::UInt(self.to_u_int_0a())
+        } else if type_ == q_variant::Type::LongLong {
+            VariantValue::LongLong(self.to_long_long_0a())
+        } else if type_ == q_variant::Type::ULongLong {
+            VariantValue::ULongLong(self.to_u_long_long_0a())
+        } else if type_ == q_variant::Type::Double {
+            VariantValue::Double(self.to_double_0a())
+        } else if type_ == q_variant::Type::String {
+            VariantValue::String(self.to_string().to_std_string())
+        } else {
+            VariantValue::Other(type_)
+        }
+    }
+}
+
+impl TryFrom<VariantValue> for CppBox<QVariant> {
+    type Error = VariantConversionError;
+
+    /// Creates a `QVariant` from `value`.
+    ///
+    /// Returns an error if `value` is `VariantValue::Other` because it doesn't
+    /// contain the value.
+    fn try_from(value: VariantValue) -> Result<Self, VariantConversionError> {
+        Ok(match value {
+            VariantValue::Invalid => unsafe { QVariant::new() },
+            VariantValue::Bool(value) => value.into(),
+            VariantValue::Int(value) => value.into(),
+            VariantValue::UInt(value) => value.into(),
+            VariantValue::LongLong(value) => value.into(),
+            VariantValue::ULongLong(value) => value.into(),
+            VariantValue::Double(value) => value.into(),
+            VariantValue::String(value) => value.into(),
+            VariantValue::Other(type_) => {
+                return Err(unsafe { VariantConversionError::from_other_value(type_) })
+            }
+        })
+    }
+}
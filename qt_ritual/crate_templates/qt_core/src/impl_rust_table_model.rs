@@ -0,0 +1,238 @@
+use crate::{
+    ItemFlag, Orientation, QBox, QByteArray, QFlags, QModelIndex, QObject, QVariant, RustTableModel,
+};
+use cpp_core::{CastInto, CppBox, Ptr, Ref};
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::ffi::c_void;
+use std::os::raw::c_int;
+use std::panic::{self, AssertUnwindSafe};
+
+/// Data source of a list or table model implemented in Rust.
+///
+/// Use `RustTableModel::from_model` to create a `QAbstractItemModel` backed by
+/// an object implementing this trait. The model can be used with any Qt view
+/// (e.g. `QListView`, `QTableView`) or exposed to QML.
+///
+/// Row and column indexes passed to the functions are always valid for the current
+/// `row_count` and `column_count`. When the data changes outside of `set_data`,
+/// notify the views using `RustTableModel`'s functions (e.g. `begin_insert_rows`,
+/// `end_insert_rows`, `emit_data_changed`).
+///
+/// The functions are called from C++, so panics can't propagate to the caller.
+/// If a function panics, the panic is caught and a default value (e.g. zero rows
+/// or an invalid `QVariant`) is returned to Qt. This also happens if Qt calls a function
+/// while the model is mutably borrowed through `RustTableModel::model`.
+pub trait TableModel: 'static {
+    /// Returns the number of rows.
+    unsafe fn row_count(&self) -> i32;
+
+    /// Returns the number of columns. The default implementation returns 1,
+    /// which is suitable for list models.
+    unsafe fn column_count(&self) -> i32 {
+        1
+    }
+
+    /// Returns the data stored under `role` for the item referred to by `index`.
+    /// Return an invalid `QVariant` if there is no such data.
+    unsafe fn data(&self, index: Ref<QModelIndex>, role: i32) -> CppBox<QVariant>;
+
+    /// Sets the `role` data for the item at `index` to `value`.
+    ///
+    /// Returns `true` if successful. The `dataChanged` signal is emitted automatically
+    /// after a successful change. The default implementation doesn't allow editing
+    /// and returns `false`.
+    unsafe fn set_data(
+        &mut self,
+        _index: Ref<QModelIndex>,
+        _value: Ref<QVariant>,
+        _role: i32,
+    ) -> bool {
+        false
+    }
+
+    /// Returns the item flags for `index`. The default implementation returns
+    /// `ItemIsSelectable | ItemIsEnabled`.
+    unsafe fn flags(&self, _index: Ref<QModelIndex>) -> QFlags<ItemFlag> {
+        ItemFlag::ItemIsSelectable | ItemFlag::ItemIsEnabled
+    }
+
+    /// Returns the data for the given `role` and `section` in the header with
+    /// the specified `orientation`. The default implementation returns an invalid `QVariant`.
+    unsafe fn header_data(
+        &self,
+        _section: i32,
+        _orientation: Orientation,
+        _role: i32,
+    ) -> CppBox<QVariant> {
+        QVariant::new()
+    }
+
+    /// Returns names of the roles used by QML. If the returned list is empty (the default),
+    /// default role names of `QAbstractItemModel` are used.
+    unsafe fn role_names(&self) -> Vec<(i32, &'static str)> {
+        Vec::new()
+    }
+}
+
+/// Pointers to Rust functions used by the C++ class.
+/// Must match `RustTableModelCallbacks` in `c_lib/extra/rust_table_model.h`.
+#[repr(C)]
+struct Callbacks {
+    drop: unsafe extern "C" fn(*mut c_void),
+    row_count: unsafe extern "C" fn(*mut c_void) -> c_int,
+    column_count: unsafe extern "C" fn(*mut c_void) -> c_int,
+    data: unsafe extern "C" fn(*mut c_void, *const QModelIndex, c_int, *mut QVariant),
+    set_data: unsafe extern "C" fn(*mut c_void, *const QModelIndex, *const QVariant, c_int) -> bool,
+    flags: unsafe extern "C" fn(*mut c_void, *const QModelIndex) -> c_int,
+    header_data: unsafe extern "C" fn(*mut c_void, c_int, c_int, c_int, *mut QVariant),
+    role_count: unsafe extern "C" fn(*mut c_void) -> c_int,
+    role_name: unsafe extern "C" fn(*mut c_void, c_int, *mut QByteArray) -> c_int,
+}
+
+/// Data owned by the C++ object. `type_id` must be the first field,
+/// so that it can be checked without knowing `M`.
+#[repr(C)]
+struct ModelData<M> {
+    type_id: TypeId,
+    callbacks: Callbacks,
+    model: RefCell<M>,
+}
+
+unsafe fn model_ref<'a, M>(data: *mut c_void) -> &'a RefCell<M> {
+    &(*(data as *const ModelData<M>)).model
+}
+
+/// Calls `f` and returns its result, or `default` if `f` panics.
+/// Panics must not unwind into C++ code.
+fn catch_panic<R>(default: R, f: impl FnOnce() -> R) -> R {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(default)
+}
+
+unsafe extern "C" fn drop_callback<M: TableModel>(data: *mut c_void) {
+    catch_panic((), || {
+        let _ = Box::from_raw(data as *mut ModelData<M>);
+    })
+}
+
+unsafe extern "C" fn row_count_callback<M: TableModel>(data: *mut c_void) -> c_int {
+    catch_panic(0, || model_ref::<M>(data).borrow().row_count())
+}
+
+unsafe extern "C" fn column_count_callback<M: TableModel>(data: *mut c_void) -> c_int {
+    catch_panic(0, || model_ref::<M>(data).borrow().column_count())
+}
+
+unsafe extern "C" fn data_callback<M: TableModel>(
+    data: *mut c_void,
+    index: *const QModelIndex,
+    role: c_int,
+    output: *mut QVariant,
+) {
+    catch_panic((), || {
+        let index = Ref::from_raw(index).expect("null index");
+        let value = model_ref::<M>(data).borrow().data(index, role);
+        Ptr::from_raw(output).copy_from(value.as_ref());
+    })
+}
+
+unsafe extern "C" fn set_data_callback<M: TableModel>(
+    data: *mut c_void,
+    index: *const QModelIndex,
+    value: *const QVariant,
+    role: c_int,
+) -> bool {
+    catch_panic(false, || {
+        let index = Ref::from_raw(index).expect("null index");
+        let value = Ref::from_raw(value).expect("null value");
+        model_ref::<M>(data)
+            .borrow_mut()
+            .set_data(index, value, role)
+    })
+}
+
+unsafe extern "C" fn flags_callback<M: TableModel>(
+    data: *mut c_void,
+    index: *const QModelIndex,
+) -> c_int {
+    catch_panic(0, || {
+        let index = Ref::from_raw(index).expect("null index");
+        model_ref::<M>(data).borrow().flags(index).to_int()
+    })
+}
+
+unsafe extern "C" fn header_data_callback<M: TableModel>(
+    data: *mut c_void,
+    section: c_int,
+    orientation: c_int,
+    role: c_int,
+    output: *mut QVariant,
+) {
+    catch_panic((), || {
+        let value = model_ref::<M>(data).borrow().header_data(
+            section,
+            Orientation::from(orientation),
+            role,
+        );
+        Ptr::from_raw(output).copy_from(value.as_ref());
+    })
+}
+
+unsafe extern "C" fn role_count_callback<M: TableModel>(data: *mut c_void) -> c_int {
+    catch_panic(0, || {
+        model_ref::<M>(data).borrow().role_names().len() as c_int
+    })
+}
+
+unsafe extern "C" fn role_name_callback<M: TableModel>(
+    data: *mut c_void,
+    index: c_int,
+    output: *mut QByteArray,
+) -> c_int {
+    catch_panic(-1, || {
+        let (role, name) = model_ref::<M>(data).borrow().role_names()[index as usize];
+        Ptr::from_raw(output).copy_from(QByteArray::from_slice(name.as_bytes()).as_ref());
+        role
+    })
+}
+
+impl RustTableModel {
+    /// Creates a Qt item model that takes ownership of `model` and uses it as the data source.
+    ///
+    /// `model` is dropped when the C++ object is deleted.
+    pub unsafe fn from_model<M: TableModel>(
+        model: M,
+        parent: impl CastInto<Ptr<QObject>>,
+    ) -> QBox<RustTableModel> {
+        let data = Box::into_raw(Box::new(ModelData {
+            type_id: TypeId::of::<M>(),
+            callbacks: Callbacks {
+                drop: drop_callback::<M>,
+                row_count: row_count_callback::<M>,
+                column_count: column_count_callback::<M>,
+                data: data_callback::<M>,
+                set_data: set_data_callback::<M>,
+                flags: flags_callback::<M>,
+                header_data: header_data_callback::<M>,
+                role_count: role_count_callback::<M>,
+                role_name: role_name_callback::<M>,
+            },
+            model: RefCell::new(model),
+        }));
+        let callbacks: *const Callbacks = &(*data).callbacks;
+        RustTableModel::new(callbacks as *const c_void, data as *mut c_void, parent)
+    }
+
+    /// Returns the data source of this model if its type is `M`.
+    ///
+    /// Don't keep the `RefCell` borrowed while calling functions of this model or
+    /// emitting its signals, because views may access the data immediately.
+    /// Such accesses fail and return default values while the model is mutably borrowed.
+    pub unsafe fn model<M: TableModel>(&self) -> Option<&RefCell<M>> {
+        let data = self.rust_data();
+        if data.is_null() || *(data as *const TypeId) != TypeId::of::<M>() {
+            return None;
+        }
+        Some(model_ref(data))
+    }
+}
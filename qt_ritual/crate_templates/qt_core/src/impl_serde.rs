@@ -0,0 +1,657 @@
+use crate::{
+    q_variant, qs, DateFormat, QByteArray, QDateTime, QListOfQVariant, QMapOfQStringQVariant,
+    QPoint, QRect, QString, QVariant, VariantValue,
+};
+use cpp_core::serde::de::{self, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor};
+use cpp_core::serde::ser::{self, SerializeMap, SerializeSeq};
+use cpp_core::serde::{Deserialize, Deserializer, Serialize, Serializer};
+use cpp_core::{cpp_iter, CppBox, CppDeserialize};
+use std::convert::TryFrom;
+use std::fmt;
+use std::slice;
+
+impl Serialize for QString {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_std_string())
+    }
+}
+
+impl<'de> CppDeserialize<'de> for QString {
+    fn cpp_deserialize<D>(deserializer: D) -> Result<CppBox<QString>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        Ok(QString::from_std_str(value))
+    }
+}
+
+impl Serialize for QByteArray {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        unsafe {
+            let bytes = slice::from_raw_parts(self.const_data() as *const u8, self.size() as usize);
+            serializer.serialize_bytes(bytes)
+        }
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a byte array")
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Vec<u8>, E> {
+        Ok(value.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, value: Vec<u8>) -> Result<Vec<u8>, E> {
+        Ok(value)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+        // Formats without a native byte array type (e.g. JSON) store bytes as a sequence.
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
+
+impl<'de> CppDeserialize<'de> for QByteArray {
+    fn cpp_deserialize<D>(deserializer: D) -> Result<CppBox<QByteArray>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let bytes = deserializer.deserialize_byte_buf(BytesVisitor)?;
+        unsafe { Ok(QByteArray::from_slice(&bytes)) }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "cpp_core::serde", rename = "QPoint")]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+impl Serialize for QPoint {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let point = unsafe {
+            Point {
+                x: self.x(),
+                y: self.y(),
+            }
+        };
+        point.serialize(serializer)
+    }
+}
+
+impl<'de> CppDeserialize<'de> for QPoint {
+    fn cpp_deserialize<D>(deserializer: D) -> Result<CppBox<QPoint>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let point = Point::deserialize(deserializer)?;
+        unsafe { Ok(QPoint::new_2a(point.x, point.y)) }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "cpp_core::serde", rename = "QRect")]
+struct Rect {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+impl Serialize for QRect {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let rect = unsafe {
+            Rect {
+                x: self.x(),
+                y: self.y(),
+                width: self.width(),
+                height: self.height(),
+            }
+        };
+        rect.serialize(serializer)
+    }
+}
+
+impl<'de> CppDeserialize<'de> for QRect {
+    fn cpp_deserialize<D>(deserializer: D) -> Result<CppBox<QRect>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let rect = Rect::deserialize(deserializer)?;
+        unsafe { Ok(QRect::from_4_int(rect.x, rect.y, rect.width, rect.height)) }
+    }
+}
+
+/// `QDateTime` is stored as an ISO 8601 string with milliseconds.
+/// An invalid `QDateTime` is stored as an empty string.
+impl Serialize for QDateTime {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let text = unsafe {
+            if self.is_valid() {
+                self.to_string_date_format(DateFormat::ISODateWithMs)
+                    .to_std_string()
+            } else {
+                String::new()
+            }
+        };
+        serializer.serialize_str(&text)
+    }
+}
+
+impl<'de> CppDeserialize<'de> for QDateTime {
+    fn cpp_deserialize<D>(deserializer: D) -> Result<CppBox<QDateTime>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let text = String::deserialize(deserializer)?;
+        unsafe {
+            if text.is_empty() {
+                return Ok(QDateTime::new());
+            }
+            let value =
+                QDateTime::from_string_q_string_date_format(&qs(&text), DateFormat::ISODateWithMs);
+            if value.is_valid() {
+                Ok(value)
+            } else {
+                Err(de::Error::invalid_value(
+                    de::Unexpected::Str(&text),
+                    &"an ISO 8601 date and time",
+                ))
+            }
+        }
+    }
+}
+
+/// Names of the enum variants used for storing `VariantValue` and `QVariant`
+/// in formats that are not human-readable. `VariantValue` only uses the first
+/// `VALUE_VARIANTS_COUNT` variants.
+const VARIANTS: &[&str] = &[
+    "Invalid",
+    "Bool",
+    "Int",
+    "UInt",
+    "LongLong",
+    "ULongLong",
+    "Double",
+    "String",
+    "List",
+    "Map",
+];
+const VALUE_VARIANTS_COUNT: usize = 8;
+const LIST_VARIANT: u32 = 8;
+const MAP_VARIANT: u32 = 9;
+
+fn unsupported_type_error<E: ser::Error>(type_: &q_variant::Type) -> E {
+    E::custom(format!("QVariant of type {:?} cannot be serialized", type_))
+}
+
+/// Serializes `value` as a variant of the enum `name`.
+fn serialize_tagged_value<S>(
+    value: &VariantValue,
+    name: &'static str,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match value {
+        VariantValue::Invalid => serializer.serialize_unit_variant(name, 0, VARIANTS[0]),
+        VariantValue::Bool(value) => {
+            serializer.serialize_newtype_variant(name, 1, VARIANTS[1], value)
+        }
+        VariantValue::Int(value) => {
+            serializer.serialize_newtype_variant(name, 2, VARIANTS[2], value)
+        }
+        VariantValue::UInt(value) => {
+            serializer.serialize_newtype_variant(name, 3, VARIANTS[3], value)
+        }
+        VariantValue::LongLong(value) => {
+            serializer.serialize_newtype_variant(name, 4, VARIANTS[4], value)
+        }
+        VariantValue::ULongLong(value) => {
+            serializer.serialize_newtype_variant(name, 5, VARIANTS[5], value)
+        }
+        VariantValue::Double(value) => {
+            serializer.serialize_newtype_variant(name, 6, VARIANTS[6], value)
+        }
+        VariantValue::String(value) => {
+            serializer.serialize_newtype_variant(name, 7, VARIANTS[7], value)
+        }
+        VariantValue::Other(type_) => Err(unsupported_type_error(type_)),
+    }
+}
+
+/// Only the types supported by `VariantValue` can be serialized.
+///
+/// Human-readable formats (e.g. JSON) store the value itself. Other formats (e.g. bincode)
+/// can't deserialize a value without knowing its type, so the value is stored
+/// as an enum variant named after the type, e.g. `Int(42)`.
+impl Serialize for VariantValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if !serializer.is_human_readable() {
+            return serialize_tagged_value(self, "VariantValue", serializer);
+        }
+        match self {
+            VariantValue::Invalid => serializer.serialize_unit(),
+            VariantValue::Bool(value) => serializer.serialize_bool(*value),
+            VariantValue::Int(value) => serializer.serialize_i32(*value),
+            VariantValue::UInt(value) => serializer.serialize_u32(*value),
+            VariantValue::LongLong(value) => serializer.serialize_i64(*value),
+            VariantValue::ULongLong(value) => serializer.serialize_u64(*value),
+            VariantValue::Double(value) => serializer.serialize_f64(*value),
+            VariantValue::String(value) => serializer.serialize_str(value),
+            VariantValue::Other(type_) => Err(unsupported_type_error(type_)),
+        }
+    }
+}
+
+/// Index of a variant in `VARIANTS`.
+struct VariantTag(u32);
+
+struct VariantTagVisitor;
+
+impl<'de> Visitor<'de> for VariantTagVisitor {
+    type Value = VariantTag;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a variant identifier")
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<VariantTag, E> {
+        if value < VARIANTS.len() as u64 {
+            Ok(VariantTag(value as u32))
+        } else {
+            Err(E::invalid_value(
+                de::Unexpected::Unsigned(value),
+                &"a variant index less than 10",
+            ))
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<VariantTag, E> {
+        match VARIANTS.iter().position(|name| *name == value) {
+            Some(index) => Ok(VariantTag(index as u32)),
+            None => Err(E::unknown_variant(value, VARIANTS)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for VariantTag {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(VariantTagVisitor)
+    }
+}
+
+/// Deserializes the content of the enum variant `tag` stored by `serialize_tagged_value`.
+fn deserialize_tagged_value<'de, A>(tag: u32, access: A) -> Result<VariantValue, A::Error>
+where
+    A: VariantAccess<'de>,
+{
+    match tag {
+        0 => access.unit_variant().map(|()| VariantValue::Invalid),
+        1 => access.newtype_variant().map(VariantValue::Bool),
+        2 => access.newtype_variant().map(VariantValue::Int),
+        3 => access.newtype_variant().map(VariantValue::UInt),
+        4 => access.newtype_variant().map(VariantValue::LongLong),
+        5 => access.newtype_variant().map(VariantValue::ULongLong),
+        6 => access.newtype_variant().map(VariantValue::Double),
+        7 => access.newtype_variant().map(VariantValue::String),
+        _ => Err(de::Error::unknown_variant(
+            VARIANTS[tag as usize],
+            &VARIANTS[..VALUE_VARIANTS_COUNT],
+        )),
+    }
+}
+
+struct VariantValueVisitor;
+
+impl<'de> Visitor<'de> for VariantValueVisitor {
+    type Value = VariantValue;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "null, a boolean, a number or a string")
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<VariantValue, E> {
+        Ok(VariantValue::Invalid)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<VariantValue, E> {
+        Ok(VariantValue::Invalid)
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<VariantValue, E> {
+        Ok(VariantValue::Bool(value))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<VariantValue, E> {
+        Ok(match i32::try_from(value) {
+            Ok(value) => VariantValue::Int(value),
+            Err(_) => VariantValue::LongLong(value),
+        })
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<VariantValue, E> {
+        if let Ok(value) = i32::try_from(value) {
+            Ok(VariantValue::Int(value))
+        } else if let Ok(value) = i64::try_from(value) {
+            Ok(VariantValue::LongLong(value))
+        } else {
+            Ok(VariantValue::ULongLong(value))
+        }
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<VariantValue, E> {
+        Ok(VariantValue::Double(value))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<VariantValue, E> {
+        Ok(VariantValue::String(value.into()))
+    }
+
+    fn visit_string<E: de::Error>(self, value: String) -> Result<VariantValue, E> {
+        Ok(VariantValue::String(value))
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<VariantValue, A::Error> {
+        let (VariantTag(tag), access) = data.variant()?;
+        deserialize_tagged_value(tag, access)
+    }
+}
+
+/// In human-readable formats, integers are deserialized as `Int` if they fit into `i32`,
+/// and as `LongLong` or `ULongLong` otherwise. Other formats restore the exact type.
+impl<'de> Deserialize<'de> for VariantValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(VariantValueVisitor)
+        } else {
+            deserializer.deserialize_enum(
+                "VariantValue",
+                &VARIANTS[..VALUE_VARIANTS_COUNT],
+                VariantValueVisitor,
+            )
+        }
+    }
+}
+
+/// Variants holding a `QVariantMap` or a `QVariantList` are serialized recursively
+/// as a map and a sequence. Other variants can only be serialized if their type is
+/// supported by `VariantValue`. For example, a variant holding a `QByteArray` or
+/// a `QDateTime` can't be serialized because its type couldn't be restored
+/// on deserialization.
+///
+/// Formats that are not human-readable store the variant as an enum, in the same way
+/// as `VariantValue`, with additional `List` and `Map` variants.
+impl Serialize for QVariant {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        unsafe {
+            let type_ = self.type_();
+            if serializer.is_human_readable() {
+                if type_ == q_variant::Type::Map {
+                    self.to_map().serialize(serializer)
+                } else if type_ == q_variant::Type::List {
+                    self.to_list().serialize(serializer)
+                } else {
+                    self.to_value().serialize(serializer)
+                }
+            } else if type_ == q_variant::Type::Map {
+                serializer.serialize_newtype_variant(
+                    "QVariant",
+                    MAP_VARIANT,
+                    VARIANTS[MAP_VARIANT as usize],
+                    &*self.to_map(),
+                )
+            } else if type_ == q_variant::Type::List {
+                serializer.serialize_newtype_variant(
+                    "QVariant",
+                    LIST_VARIANT,
+                    VARIANTS[LIST_VARIANT as usize],
+                    &*self.to_list(),
+                )
+            } else {
+                serialize_tagged_value(&self.to_value(), "QVariant", serializer)
+            }
+        }
+    }
+}
+
+/// Converts a deserialized value to a `QVariant`.
+fn variant_from_value<E: de::Error>(value: VariantValue) -> Result<CppBox<QVariant>, E> {
+    CppBox::try_from(value).map_err(E::custom)
+}
+
+struct VariantVisitor;
+
+impl<'de> Visitor<'de> for VariantVisitor {
+    type Value = CppBox<QVariant>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "null, a boolean, a number, a string, a sequence or a map"
+        )
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<CppBox<QVariant>, E> {
+        VariantValueVisitor
+            .visit_unit()
+            .and_then(variant_from_value)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<CppBox<QVariant>, E> {
+        VariantValueVisitor
+            .visit_none()
+            .and_then(variant_from_value)
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<CppBox<QVariant>, E> {
+        VariantValueVisitor
+            .visit_bool(value)
+            .and_then(variant_from_value)
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<CppBox<QVariant>, E> {
+        VariantValueVisitor
+            .visit_i64(value)
+            .and_then(variant_from_value)
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<CppBox<QVariant>, E> {
+        VariantValueVisitor
+            .visit_u64(value)
+            .and_then(variant_from_value)
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<CppBox<QVariant>, E> {
+        VariantValueVisitor
+            .visit_f64(value)
+            .and_then(variant_from_value)
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<CppBox<QVariant>, E> {
+        VariantValueVisitor
+            .visit_str(value)
+            .and_then(variant_from_value)
+    }
+
+    fn visit_string<E: de::Error>(self, value: String) -> Result<CppBox<QVariant>, E> {
+        VariantValueVisitor
+            .visit_string(value)
+            .and_then(variant_from_value)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<CppBox<QVariant>, A::Error> {
+        let list = VariantListVisitor.visit_seq(seq)?;
+        unsafe { Ok(QVariant::from_q_list_of_q_variant(&list)) }
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, access: A) -> Result<CppBox<QVariant>, A::Error> {
+        let map = VariantMapVisitor.visit_map(access)?;
+        unsafe { Ok(QVariant::from_q_map_of_q_string_q_variant(&map)) }
+    }
+
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<CppBox<QVariant>, A::Error> {
+        let (VariantTag(tag), access) = data.variant()?;
+        unsafe {
+            match tag {
+                LIST_VARIANT => {
+                    let list: CppBox<QListOfQVariant> = access.newtype_variant()?;
+                    Ok(QVariant::from_q_list_of_q_variant(&list))
+                }
+                MAP_VARIANT => {
+                    let map: CppBox<QMapOfQStringQVariant> = access.newtype_variant()?;
+                    Ok(QVariant::from_q_map_of_q_string_q_variant(&map))
+                }
+                _ => deserialize_tagged_value(tag, access).and_then(variant_from_value),
+            }
+        }
+    }
+}
+
+/// Sequences and maps are deserialized as `QVariantList` and `QVariantMap`.
+/// Other values are deserialized in the same way as `VariantValue`.
+impl<'de> CppDeserialize<'de> for QVariant {
+    fn cpp_deserialize<D>(deserializer: D) -> Result<CppBox<QVariant>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(VariantVisitor)
+        } else {
+            deserializer.deserialize_enum("QVariant", VARIANTS, VariantVisitor)
+        }
+    }
+}
+
+/// `QVariantList` is stored as a sequence.
+impl Serialize for QListOfQVariant {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        unsafe {
+            let mut seq = serializer.serialize_seq(Some(self.size() as usize))?;
+            for i in 0..self.size() {
+                seq.serialize_element(&*self.at(i))?;
+            }
+            seq.end()
+        }
+    }
+}
+
+struct VariantListVisitor;
+
+impl<'de> Visitor<'de> for VariantListVisitor {
+    type Value = CppBox<QListOfQVariant>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a sequence")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<CppBox<QListOfQVariant>, A::Error> {
+        unsafe {
+            let list = QListOfQVariant::new();
+            while let Some(value) = seq.next_element::<CppBox<QVariant>>()? {
+                list.append_q_variant(&value);
+            }
+            Ok(list)
+        }
+    }
+}
+
+impl<'de> CppDeserialize<'de> for QListOfQVariant {
+    fn cpp_deserialize<D>(deserializer: D) -> Result<CppBox<QListOfQVariant>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(VariantListVisitor)
+    }
+}
+
+/// `QVariantMap` is stored as a map with string keys.
+impl Serialize for QMapOfQStringQVariant {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        unsafe {
+            let mut map = serializer.serialize_map(Some(self.size() as usize))?;
+            for (key, value) in cpp_iter(self.const_begin(), self.const_end()).key_value() {
+                map.serialize_entry(&key.to_std_string(), &*value)?;
+            }
+            map.end()
+        }
+    }
+}
+
+struct VariantMapVisitor;
+
+impl<'de> Visitor<'de> for VariantMapVisitor {
+    type Value = CppBox<QMapOfQStringQVariant>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a map")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(
+        self,
+        mut access: A,
+    ) -> Result<CppBox<QMapOfQStringQVariant>, A::Error> {
+        unsafe {
+            let map = QMapOfQStringQVariant::new();
+            while let Some((key, value)) = access.next_entry::<String, CppBox<QVariant>>()? {
+                map.insert_2a(&qs(key), &value);
+            }
+            Ok(map)
+        }
+    }
+}
+
+impl<'de> CppDeserialize<'de> for QMapOfQStringQVariant {
+    fn cpp_deserialize<D>(deserializer: D) -> Result<CppBox<QMapOfQStringQVariant>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(VariantMapVisitor)
+    }
+}
@@ -1,5 +1,7 @@
 use proc_macro_hack::proc_macro_hack;
 
+#[cfg(feature = "async")]
+mod async_executor;
 mod connect;
 mod impl_arguments_compatible;
 mod impl_ptr_ops;
@@ -7,22 +9,37 @@ mod impl_q_byte_array;
 mod impl_q_core_application;
 mod impl_q_object;
 mod impl_q_string;
+#[cfg(feature = "variant")]
+mod impl_q_variant;
+#[cfg(feature = "item_model")]
+mod impl_rust_table_model;
+#[cfg(feature = "serde")]
+mod impl_serde;
 mod q_box;
 mod q_debug_shim;
 mod q_flags;
 mod q_message_logger_macros;
 mod q_ptr;
 
-pub use crate::connect::{ArgumentsCompatible, AsReceiver, Receiver, Signal};
+#[cfg(feature = "async")]
+pub use crate::async_executor::{spawn_local, SignalFuture};
+pub use crate::connect::{
+    post_to_thread, ArgumentsCompatible, AsReceiver, ClosureConnection, ClosureSlot,
+    DeclaredSignal, Receiver, Signal, SlotArguments,
+};
 pub use crate::impl_q_core_application::QCoreApplicationArgs;
 pub use crate::impl_q_object::FindChildError;
 pub use crate::impl_q_string::qs;
+#[cfg(feature = "variant")]
+pub use crate::impl_q_variant::{VariantConversionError, VariantValue};
+#[cfg(feature = "item_model")]
+pub use crate::impl_rust_table_model::TableModel;
 pub use crate::q_box::QBox;
 pub use crate::q_debug_shim::{qdbg, QDebugShim};
 pub use crate::q_flags::QFlags;
 pub use crate::q_ptr::QPtr;
 
-pub use qt_macros::slot;
+pub use qt_macros::{invokables, signals, slot, QObject};
 
 /// Initializes Qt resources specified by the `.qrc` file with the specified base name.
 ///
@@ -0,0 +1,70 @@
+#![cfg(feature = "async")]
+
+use qt_core::{spawn_local, QCoreApplication, QTimer};
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::task::{Context, Poll};
+
+#[test]
+fn signal_future() {
+    QCoreApplication::init(|_app| unsafe {
+        let value = Rc::new(Cell::new(0));
+        let value2 = Rc::clone(&value);
+
+        let timer = QTimer::new_0a();
+        let timeout = timer.timeout().next();
+        spawn_local(async move {
+            value2.set(1);
+            timeout.await;
+            value2.set(2);
+            QCoreApplication::quit();
+        });
+        assert_eq!(value.get(), 0);
+
+        timer.start_1a(100);
+        let r = QCoreApplication::exec();
+        assert_eq!(value.get(), 2);
+        r
+    })
+}
+
+/// Future that wakes itself and returns `Pending` `count` times before completing.
+struct YieldTimes {
+    count: usize,
+}
+
+impl Future for YieldTimes {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.count == 0 {
+            Poll::Ready(())
+        } else {
+            self.count -= 1;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+#[test]
+fn repeated_wake_ups() {
+    QCoreApplication::init(|_app| unsafe {
+        let completed = Rc::new(Cell::new(0));
+        for _ in 0..2 {
+            let completed = Rc::clone(&completed);
+            spawn_local(async move {
+                YieldTimes { count: 100 }.await;
+                completed.set(completed.get() + 1);
+                if completed.get() == 2 {
+                    QCoreApplication::quit();
+                }
+            });
+        }
+        let r = QCoreApplication::exec();
+        assert_eq!(completed.get(), 2);
+        r
+    })
+}
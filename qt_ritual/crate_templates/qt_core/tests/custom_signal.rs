@@ -1,4 +1,4 @@
-use qt_core::{QCoreApplication, QTimer, SignalOfInt};
+use qt_core::{signals, QCoreApplication, QTimer, SignalNoArgs, SignalOfInt};
 
 #[test]
 fn timer_quit() {
@@ -16,3 +16,36 @@ fn timer_quit() {
         0
     })
 }
+
+#[signals]
+struct CounterSignals {
+    value_changed: SignalOfInt,
+    limit_changed: Signal<(i32,)>,
+    reset: Signal<()>,
+}
+
+#[test]
+fn signals_macro() {
+    QCoreApplication::init(|_app| unsafe {
+        let signals = CounterSignals::new();
+
+        let timer = QTimer::new_0a();
+        let c = signals.value_changed().connect(timer.slot_start());
+        assert!(c.is_valid());
+
+        signals.value_changed.emit(300);
+        assert_eq!(timer.interval(), 300);
+
+        let c = signals.limit_changed().connect(timer.slot_start());
+        assert!(c.is_valid());
+        signals.limit_changed.emit(400);
+        assert_eq!(timer.interval(), 400);
+
+        let c = signals.reset().connect(timer.slot_stop());
+        assert!(c.is_valid());
+        assert!(timer.is_active());
+        signals.reset.emit();
+        assert!(!timer.is_active());
+        0
+    })
+}
@@ -0,0 +1,41 @@
+use cpp_core::Ptr;
+use qt_core::{post_to_thread, QCoreApplication, QObject};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+#[test]
+fn post_to_thread_from_other_thread() {
+    QCoreApplication::init(|app| unsafe {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+
+        let deleted_context = QObject::new_0a();
+        let calls2 = Arc::clone(&calls);
+        post_to_thread(&deleted_context, move || {
+            calls2.lock().unwrap().push("deleted context");
+        });
+        drop(deleted_context);
+
+        let main_thread = thread::current().id();
+        let app_ptr = app.as_raw_ptr() as usize;
+        let calls2 = Arc::clone(&calls);
+        thread::spawn(move || {
+            let app = Ptr::from_raw(app_ptr as *const QCoreApplication);
+            post_to_thread(app, move || {
+                let name = if thread::current().id() == main_thread {
+                    "main thread"
+                } else {
+                    "other thread"
+                };
+                calls2.lock().unwrap().push(name);
+                QCoreApplication::quit();
+            });
+        })
+        .join()
+        .unwrap();
+
+        assert!(calls.lock().unwrap().is_empty());
+        let r = QCoreApplication::exec();
+        assert_eq!(*calls.lock().unwrap(), vec!["main thread"]);
+        r
+    })
+}
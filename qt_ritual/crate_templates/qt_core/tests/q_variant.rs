@@ -0,0 +1,57 @@
+#![cfg(feature = "variant")]
+
+use cpp_core::CppBox;
+use qt_core::{q_variant, QVariant, VariantValue};
+use std::convert::TryFrom;
+
+#[test]
+fn variant_round_trip() {
+    let value = CppBox::<QVariant>::from(42);
+    assert_eq!(i32::try_from(&*value).unwrap(), 42);
+    assert_eq!(i64::try_from(&*value).unwrap(), 42);
+    assert_eq!(f64::try_from(&*value).unwrap(), 42.0);
+
+    let value = CppBox::<QVariant>::from(1.5);
+    assert_eq!(f64::try_from(&*value).unwrap(), 1.5);
+
+    let value = CppBox::<QVariant>::from(true);
+    assert!(bool::try_from(&*value).unwrap());
+
+    let value = CppBox::<QVariant>::from("text");
+    assert_eq!(String::try_from(&*value).unwrap(), "text");
+    assert!(i32::try_from(&*value).is_err());
+
+    let value = CppBox::<QVariant>::from(String::from("123"));
+    assert_eq!(i32::try_from(&*value).unwrap(), 123);
+
+    let value = unsafe { QVariant::new() };
+    let err = i32::try_from(&*value).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "QVariant of type invalid cannot be converted to i32"
+    );
+}
+
+#[test]
+fn variant_value() {
+    let values = vec![
+        VariantValue::Invalid,
+        VariantValue::Bool(false),
+        VariantValue::Int(-3),
+        VariantValue::UInt(3),
+        VariantValue::LongLong(-1 << 40),
+        VariantValue::ULongLong(1 << 40),
+        VariantValue::Double(2.5),
+        VariantValue::String("text".into()),
+    ];
+    for value in values {
+        let variant = CppBox::<QVariant>::try_from(value.clone()).unwrap();
+        assert_eq!(unsafe { variant.to_value() }, value);
+    }
+
+    let err = CppBox::<QVariant>::try_from(VariantValue::Other(q_variant::Type::Date)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "VariantValue::Other(QDate) cannot be converted to QVariant"
+    );
+}
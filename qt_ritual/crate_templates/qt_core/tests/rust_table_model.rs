@@ -0,0 +1,121 @@
+#![cfg(feature = "item_model")]
+
+use cpp_core::{CppBox, NullPtr, Ref};
+use qt_core::{
+    qs, ItemDataRole, ItemFlag, QFlags, QModelIndex, QString, QVariant, RustTableModel, SlotNoArgs,
+    TableModel,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+struct Names(Vec<String>);
+
+impl TableModel for Names {
+    unsafe fn row_count(&self) -> i32 {
+        self.0.len() as i32
+    }
+
+    unsafe fn data(&self, index: Ref<QModelIndex>, role: i32) -> CppBox<QVariant> {
+        if role == ItemDataRole::DisplayRole.to_int() || role == ItemDataRole::EditRole.to_int() {
+            QVariant::from_q_string(&qs(&self.0[index.row() as usize]))
+        } else {
+            QVariant::new()
+        }
+    }
+
+    unsafe fn set_data(
+        &mut self,
+        index: Ref<QModelIndex>,
+        value: Ref<QVariant>,
+        role: i32,
+    ) -> bool {
+        if role != ItemDataRole::EditRole.to_int() {
+            return false;
+        }
+        self.0[index.row() as usize] = value.to_string().to_std_string();
+        true
+    }
+
+    unsafe fn flags(&self, _index: Ref<QModelIndex>) -> QFlags<ItemFlag> {
+        ItemFlag::ItemIsSelectable | ItemFlag::ItemIsEnabled | ItemFlag::ItemIsEditable
+    }
+
+    unsafe fn role_names(&self) -> Vec<(i32, &'static str)> {
+        vec![(ItemDataRole::DisplayRole.to_int(), "name")]
+    }
+}
+
+#[test]
+fn rust_table_model() {
+    unsafe {
+        let model = RustTableModel::from_model(Names(vec!["a".into(), "b".into()]), NullPtr);
+        assert_eq!(model.row_count_0a(), 2);
+        assert_eq!(model.column_count_0a(), 1);
+
+        let index1 = model.index_2a(1, 0);
+        assert_eq!(model.data_1a(&index1).to_string().to_std_string(), "b");
+        assert!(model.flags(&index1).test_flag(ItemFlag::ItemIsEditable));
+
+        assert!(model.set_data_2a(
+            &index1,
+            &QVariant::from_q_string(&QString::from_std_str("c"))
+        ));
+        assert_eq!(model.data_1a(&index1).to_string().to_std_string(), "c");
+
+        let names = model.role_names();
+        assert_eq!(names.size(), 1);
+
+        let data = model.model::<Names>().unwrap();
+        assert_eq!(data.borrow().0, vec!["a".to_string(), "c".to_string()]);
+
+        model.begin_insert_rows(&QModelIndex::new(), 2, 2);
+        data.borrow_mut().0.push("d".into());
+        model.end_insert_rows();
+        assert_eq!(model.row_count_0a(), 3);
+    }
+}
+
+#[test]
+fn set_data_emits_data_changed() {
+    unsafe {
+        let model = RustTableModel::from_model(Names(vec!["a".into(), "b".into()]), NullPtr);
+        let model_ptr = model.as_ptr();
+        let values = Rc::new(RefCell::new(Vec::new()));
+        let values2 = Rc::clone(&values);
+        let slot = SlotNoArgs::new(NullPtr, move || {
+            // Views read the data when `dataChanged` is emitted.
+            let index = model_ptr.index_2a(1, 0);
+            let value = model_ptr.data_1a(&index).to_string().to_std_string();
+            values2.borrow_mut().push(value);
+        });
+        model.data_changed().connect(&slot);
+
+        let index1 = model.index_2a(1, 0);
+        assert!(model.set_data_2a(&index1, &QVariant::from_q_string(&qs("c"))));
+        assert_eq!(*values.borrow(), vec!["c".to_string()]);
+
+        assert!(!model.set_data_3a(
+            &index1,
+            &QVariant::from_q_string(&qs("d")),
+            ItemDataRole::DisplayRole.to_int()
+        ));
+        assert_eq!(*values.borrow(), vec!["c".to_string()]);
+    }
+}
+
+#[test]
+fn access_while_borrowed() {
+    unsafe {
+        let model = RustTableModel::from_model(Names(vec!["a".into(), "b".into()]), NullPtr);
+        let index = model.index_2a(0, 0);
+        let data = model.model::<Names>().unwrap();
+        {
+            let _borrowed = data.borrow_mut();
+            // The panic caused by the borrow is not propagated to C++.
+            assert_eq!(model.row_count_0a(), 0);
+            assert!(!model.data_1a(&index).is_valid());
+        }
+        assert_eq!(model.row_count_0a(), 2);
+        assert_eq!(model.data_1a(&index).to_string().to_std_string(), "a");
+    }
+}
@@ -0,0 +1,166 @@
+#![cfg(feature = "serde")]
+
+use cpp_core::CppBox;
+use qt_core::{
+    qs, QByteArray, QDateTime, QListOfQVariant, QMapOfQStringQVariant, QPoint, QRect, QString,
+    QVariant, VariantValue,
+};
+use std::convert::TryFrom;
+
+#[test]
+fn serde_string_and_bytes() {
+    unsafe {
+        let string = qs("text");
+        let json = serde_json::to_string(&string).unwrap();
+        assert_eq!(json, r#""text""#);
+        let string2: CppBox<QString> = serde_json::from_str(&json).unwrap();
+        assert_eq!(string2.to_std_string(), "text");
+
+        let bytes = QByteArray::from_slice(&[1, 2, 255]);
+        let json = serde_json::to_string(&bytes).unwrap();
+        assert_eq!(json, "[1,2,255]");
+        let bytes2: CppBox<QByteArray> = serde_json::from_str(&json).unwrap();
+        assert_eq!(bytes2.size(), 3);
+        assert_eq!(bytes2.at(2) as u8, 255);
+    }
+}
+
+#[test]
+fn serde_geometry() {
+    unsafe {
+        let point = QPoint::new_2a(2, -3);
+        let json = serde_json::to_string(&point).unwrap();
+        assert_eq!(json, r#"{"x":2,"y":-3}"#);
+        let point2: CppBox<QPoint> = serde_json::from_str(&json).unwrap();
+        assert_eq!(point2.x(), 2);
+        assert_eq!(point2.y(), -3);
+
+        let rect = QRect::from_4_int(1, 2, 3, 4);
+        let json = serde_json::to_string(&rect).unwrap();
+        assert_eq!(json, r#"{"x":1,"y":2,"width":3,"height":4}"#);
+        let rect2: CppBox<QRect> = serde_json::from_str(&json).unwrap();
+        assert_eq!(rect2.width(), 3);
+        assert_eq!(rect2.height(), 4);
+    }
+}
+
+#[test]
+fn serde_date_time() {
+    unsafe {
+        let json = r#""2020-01-02T03:04:05.678Z""#;
+        let date_time: CppBox<QDateTime> = serde_json::from_str(json).unwrap();
+        assert!(date_time.is_valid());
+        assert_eq!(date_time.to_m_secs_since_epoch(), 1_577_934_245_678);
+        assert_eq!(serde_json::to_string(&date_time).unwrap(), json);
+
+        let invalid = QDateTime::new();
+        assert_eq!(serde_json::to_string(&invalid).unwrap(), r#""""#);
+        assert!(serde_json::from_str::<CppBox<QDateTime>>(r#""not a date""#).is_err());
+    }
+}
+
+#[test]
+fn serde_variant_map() {
+    unsafe {
+        let map = QMapOfQStringQVariant::new();
+        map.insert_2a(&qs("flag"), &CppBox::<QVariant>::from(true));
+        map.insert_2a(&qs("name"), &CppBox::<QVariant>::from("text"));
+        map.insert_2a(&qs("size"), &CppBox::<QVariant>::from(42));
+        let json = serde_json::to_string(&map).unwrap();
+        assert_eq!(json, r#"{"flag":true,"name":"text","size":42}"#);
+
+        let map2: CppBox<QMapOfQStringQVariant> = serde_json::from_str(&json).unwrap();
+        assert_eq!(map2.size(), 3);
+        assert_eq!(serde_json::to_string(&map2).unwrap(), json);
+    }
+}
+
+#[test]
+fn serde_nested_variant_map() {
+    unsafe {
+        let inner = QMapOfQStringQVariant::new();
+        inner.insert_2a(&qs("x"), &CppBox::<QVariant>::from(1));
+        let list = QListOfQVariant::new();
+        list.append_q_variant(&CppBox::<QVariant>::from("a"));
+        list.append_q_variant(&QVariant::from_q_map_of_q_string_q_variant(&inner));
+
+        let map = QMapOfQStringQVariant::new();
+        map.insert_2a(&qs("list"), &QVariant::from_q_list_of_q_variant(&list));
+        map.insert_2a(
+            &qs("map"),
+            &QVariant::from_q_map_of_q_string_q_variant(&inner),
+        );
+        let json = serde_json::to_string(&map).unwrap();
+        assert_eq!(json, r#"{"list":["a",{"x":1}],"map":{"x":1}}"#);
+
+        let map2: CppBox<QMapOfQStringQVariant> = serde_json::from_str(&json).unwrap();
+        assert_eq!(map2.size(), 2);
+        assert_eq!(map2.value_1a(&qs("list")).to_list().size(), 2);
+        assert_eq!(serde_json::to_string(&map2).unwrap(), json);
+
+        let list2: CppBox<QListOfQVariant> = serde_json::from_str("[1,[true]]").unwrap();
+        assert_eq!(list2.size(), 2);
+        assert_eq!(serde_json::to_string(&list2).unwrap(), "[1,[true]]");
+    }
+}
+
+#[test]
+fn bincode_variants() {
+    unsafe {
+        let list = QListOfQVariant::new();
+        list.append_q_variant(&CppBox::<QVariant>::try_from(VariantValue::Double(1.5)).unwrap());
+        list.append_q_variant(&QVariant::new());
+        let map = QMapOfQStringQVariant::new();
+        map.insert_2a(&qs("list"), &QVariant::from_q_list_of_q_variant(&list));
+        map.insert_2a(&qs("name"), &CppBox::<QVariant>::from("text"));
+        map.insert_2a(
+            &qs("size"),
+            &CppBox::<QVariant>::try_from(VariantValue::ULongLong(42)).unwrap(),
+        );
+
+        let bytes = bincode::serialize(&map).unwrap();
+        let map2: CppBox<QMapOfQStringQVariant> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(map2.size(), 3);
+        assert_eq!(
+            map2.value_1a(&qs("size")).to_value(),
+            VariantValue::ULongLong(42)
+        );
+        let list2 = map2.value_1a(&qs("list")).to_list();
+        assert_eq!(list2.size(), 2);
+        assert_eq!(list2.at(0).to_value(), VariantValue::Double(1.5));
+        assert_eq!(list2.at(1).to_value(), VariantValue::Invalid);
+        assert_eq!(
+            serde_json::to_string(&map2).unwrap(),
+            r#"{"list":[1.5,null],"name":"text","size":42}"#
+        );
+
+        let value = VariantValue::LongLong(7);
+        let bytes = bincode::serialize(&value).unwrap();
+        assert_eq!(bincode::deserialize::<VariantValue>(&bytes).unwrap(), value);
+
+        let string = qs("text");
+        let bytes = bincode::serialize(&string).unwrap();
+        let string2: CppBox<QString> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(string2.to_std_string(), "text");
+    }
+}
+
+#[test]
+fn serde_unsupported_variant() {
+    unsafe {
+        let map = QMapOfQStringQVariant::new();
+        map.insert_2a(
+            &qs("bytes"),
+            &QVariant::from_q_byte_array(&QByteArray::from_slice(&[1, 2])),
+        );
+        let err = serde_json::to_string(&map).unwrap_err();
+        assert!(err.to_string().contains("cannot be serialized"));
+
+        let map = QMapOfQStringQVariant::new();
+        map.insert_2a(
+            &qs("time"),
+            &QVariant::from_q_date_time(&QDateTime::current_date_time()),
+        );
+        assert!(serde_json::to_string(&map).is_err());
+    }
+}
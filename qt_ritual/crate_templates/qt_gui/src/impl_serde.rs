@@ -0,0 +1,36 @@
+use crate::{q_color::NameFormat, QColor};
+use cpp_core::serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use cpp_core::{CppBox, CppDeserialize};
+use qt_core::qs;
+
+/// `QColor` is stored as a `#AARRGGBB` string.
+impl Serialize for QColor {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let name = unsafe { self.name_1a(NameFormat::HexArgb).to_std_string() };
+        serializer.serialize_str(&name)
+    }
+}
+
+/// Any color name accepted by `QColor::setNamedColor` can be deserialized.
+impl<'de> CppDeserialize<'de> for QColor {
+    fn cpp_deserialize<D>(deserializer: D) -> Result<CppBox<QColor>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let name = String::deserialize(deserializer)?;
+        unsafe {
+            let color = QColor::from_q_string(&qs(&name));
+            if color.is_valid() {
+                Ok(color)
+            } else {
+                Err(de::Error::invalid_value(
+                    de::Unexpected::Str(&name),
+                    &"a color name",
+                ))
+            }
+        }
+    }
+}
@@ -1 +1,3 @@
 mod impl_q_gui_application;
+#[cfg(feature = "serde")]
+mod impl_serde;
@@ -0,0 +1,20 @@
+#![cfg(feature = "serde")]
+
+use cpp_core::CppBox;
+use qt_gui::QColor;
+
+#[test]
+fn serde_color() {
+    unsafe {
+        let color = QColor::from_3_int(255, 0, 128);
+        let json = serde_json::to_string(&color).unwrap();
+        assert_eq!(json, r##""#ffff0080""##);
+        let color2: CppBox<QColor> = serde_json::from_str(&json).unwrap();
+        assert_eq!(color2.red(), 255);
+        assert_eq!(color2.blue(), 128);
+
+        let color3: CppBox<QColor> = serde_json::from_str(r#""red""#).unwrap();
+        assert_eq!(color3.red(), 255);
+        assert!(serde_json::from_str::<CppBox<QColor>>(r#""not a color""#).is_err());
+    }
+}
@@ -41,6 +41,52 @@ fn empty_config(_config: &mut Config) -> Result<()> {
     Ok(())
 }
 
+/// Returns the table stored in the `key` field of `table`. An empty table is inserted
+/// if the field is not present, so the caller can add values to the existing table
+/// instead of replacing it.
+fn table_entry<'a>(
+    table: &'a mut toml::value::Table,
+    key: &str,
+) -> Result<&'a mut toml::value::Table> {
+    table
+        .entry(key.to_string())
+        .or_insert_with(|| toml::Value::Table(toml::value::Table::new()))
+        .as_table_mut()
+        .ok_or_else(|| format_err!("custom field is not a table: {}", key))
+}
+
+/// Adds `feature` that enables `enabled_features` to the custom `Cargo.toml` fields
+/// of the crate.
+fn add_feature(config: &mut Config, feature: &str, enabled_features: &[&str]) -> Result<()> {
+    let mut custom_fields = config.crate_properties().custom_fields().clone();
+    let enabled_features = enabled_features
+        .iter()
+        .map(|&name| toml::Value::String(name.into()))
+        .collect();
+    table_entry(&mut custom_fields, "features")?
+        .insert(feature.into(), toml::Value::Array(enabled_features));
+    config
+        .crate_properties_mut()
+        .set_custom_fields(custom_fields);
+    Ok(())
+}
+
+/// Adds the `serde` feature that enables `enabled_features`, and the `serde_json`
+/// and `bincode` dev-dependencies used by tests of the feature.
+/// The feature is enabled when the crate's tests are run.
+fn add_serde_feature(config: &mut Config, enabled_features: &[&str]) -> Result<()> {
+    add_feature(config, "serde", enabled_features)?;
+    config.add_test_feature("serde");
+    let mut custom_fields = config.crate_properties().custom_fields().clone();
+    let dev_dependencies = table_entry(&mut custom_fields, "dev-dependencies")?;
+    dev_dependencies.insert("serde_json".into(), toml::Value::String("1.0".into()));
+    dev_dependencies.insert("bincode".into(), toml::Value::String("1.2".into()));
+    config
+        .crate_properties_mut()
+        .set_custom_fields(custom_fields);
+    Ok(())
+}
+
 /// Executes the generator for a single Qt module with given configuration.
 pub fn create_config(
     mut crate_properties: CrateProperties,
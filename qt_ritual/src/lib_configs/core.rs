@@ -1,3 +1,4 @@
+use super::{add_feature, add_serde_feature};
 use itertools::Itertools;
 use ritual::config::{Config, CrateDependencyKind, CrateDependencySource};
 use ritual::cpp_checker::{PreliminaryTest, Snippet};
@@ -30,6 +31,23 @@ pub fn core_config(config: &mut Config) -> Result<()> {
         },
     )?;
 
+    // Optional components of the crate template.
+    add_feature(config, "async", &[])?;
+    config.add_test_feature("async");
+    if config.crate_properties().name() == "qt_core" {
+        // `RustTableModel` and `QVariant` are not available in the mock library used for tests.
+        add_feature(config, "item_model", &[])?;
+        add_feature(config, "variant", &[])?;
+        add_serde_feature(config, &["cpp_core/serde", "variant"])?;
+        add_feature(config, "default", &["item_model", "variant"])?;
+        // `#[derive(QObject)]` tests need a build script that compiles the test classes.
+        config.add_test_crate("q_object_test");
+    } else {
+        // Template files are shared with `moqt_core`, but the mock library
+        // doesn't have item models.
+        config.add_excluded_extra_file("rust_table_model.h");
+    }
+
     let crate_name = config.crate_properties().name().to_string();
     let crate_name2 = crate_name.clone();
 
@@ -181,6 +199,8 @@ pub fn core_config(config: &mut Config) -> Result<()> {
             "swap",
             // is not cross-platform and is deprecated anyway
             "QProcess::pid",
+            // implementation detail of `RustTableModel`
+            "RustTableModelCallbacks",
         ];
         if blocked.contains(&string.as_str()) {
             return Ok(false);
@@ -191,6 +211,7 @@ pub fn core_config(config: &mut Config) -> Result<()> {
     // TODO: replace QVariant::Type with QMetaType::Type?
 
     let connect_path = CppPath::from_good_str("QObject::connect");
+    let disconnect_path = CppPath::from_good_str("QObject::disconnect");
     let connection_to_bool_function = connection_to_bool_function();
     let connection_is_valid_path = RustPath::from_good_str(&format!(
         "{}::q_meta_object::Connection::is_valid",
@@ -223,6 +244,26 @@ pub fn core_config(config: &mut Config) -> Result<()> {
                     }
                 }
 
+                // `ClosureConnection::disconnect` relies on the name of the
+                // `const char*` overload, so both static overloads are named explicitly
+                // instead of depending on the caption strategy chosen for all overloads.
+                if cpp_function.path == disconnect_path
+                    && cpp_function.is_static_member()
+                    && cpp_function.arguments.len() == 4
+                {
+                    let arg = &cpp_function.arguments[1].argument_type;
+                    let name = if arg == &qmetamethod_ref_type {
+                        "disconnect_by_meta_methods"
+                    } else {
+                        "disconnect_4a"
+                    };
+                    return Ok(Some(RustPath::from_good_str(&format!(
+                        "{}::QObject::{}",
+                        data.db.crate_name(),
+                        name
+                    ))));
+                }
+
                 if cpp_function.is_same(&connection_to_bool_function) {
                     return Ok(Some(connection_is_valid_path.clone()));
                 }
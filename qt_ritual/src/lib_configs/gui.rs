@@ -1,3 +1,4 @@
+use super::add_serde_feature;
 use ritual::config::Config;
 use ritual_common::errors::Result;
 
@@ -33,5 +34,10 @@ pub fn gui_config(config: &mut Config) -> Result<()> {
         }
         Ok(true)
     });
+
+    if config.crate_properties().name() == "qt_gui" {
+        // `QColor` is not available in the mock library used for tests.
+        add_serde_feature(config, &["cpp_core/serde", "qt_core/serde"])?;
+    }
     Ok(())
 }
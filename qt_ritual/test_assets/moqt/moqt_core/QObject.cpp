@@ -20,6 +20,18 @@ QObject::~QObject() {
 
 }
 
+void QObject::setParent(QObject *parent) {
+
+}
+
+QThread *QObject::thread() const {
+    return nullptr;
+}
+
+void QObject::moveToThread(QThread *targetThread) {
+
+}
+
 void QObject::destroyed(QObject *objectName) {
 
 }
@@ -33,13 +45,14 @@ void QObject::deleteLater() {
 }
 
 QMetaObject::Connection QObject::connect(const QObject *sender, const char *signal,
-                                       const QObject *receiver, const char *member, Qt::ConnectionType) {
+                                       const QObject *receiver, const char *member, Qt::ConnectionType type) {
     std::lock_guard<std::mutex> lock(connectArgsMutex);
     ConnectArgs args;
     args.sender = sender;
     args.signal = copyToHeap(signal);
     args.receiver = receiver;
     args.method = copyToHeap(member);
+    args.connectionType = type;
     connectArgs.push_back(args);
 
     return QMetaObject::Connection();
@@ -51,6 +64,16 @@ QMetaObject::Connection QObject::connect(const QObject *sender, const QMetaMetho
     return QMetaObject::Connection();
 }
 
+bool QObject::disconnect(const QObject *sender, const char *signal,
+                         const QObject *receiver, const char *member) {
+    return true;
+}
+
+bool QObject::disconnect(const QObject *sender, const QMetaMethod &signal,
+                         const QObject *receiver, const QMetaMethod &member) {
+    return true;
+}
+
 QObject::ConnectArgs QObject::nextConnectArgs() {
     std::lock_guard<std::mutex> lock(connectArgsMutex);
     if (connectArgs.empty()) {
@@ -61,3 +84,7 @@ QObject::ConnectArgs QObject::nextConnectArgs() {
     connectArgs.pop_front();
     return result;
 }
+
+QThread::QThread(QObject* parent) : QObject(parent) {
+
+}
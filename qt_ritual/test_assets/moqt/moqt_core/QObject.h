@@ -36,11 +36,17 @@ namespace Qt {
 
 class MOQT_CORE_EXPORT QMetaMethod {};
 
+class QThread;
+
 class MOQT_CORE_EXPORT QObject {
 public:
     QObject(QObject* parent = nullptr);
     virtual ~QObject();
     QObject *parent() const { return nullptr; }
+    void setParent(QObject *parent);
+
+    QThread *thread() const;
+    void moveToThread(QThread *targetThread);
 
     static QMetaObject::Connection connect(const QObject *sender, const char *signal,
                                            const QObject *receiver, const char *member, Qt::ConnectionType = Qt::AutoConnection);
@@ -54,12 +60,19 @@ public:
         return QMetaObject::Connection();
     }
 
+    static bool disconnect(const QObject *sender, const char *signal,
+                           const QObject *receiver, const char *member);
+
+    static bool disconnect(const QObject *sender, const QMetaMethod &signal,
+                           const QObject *receiver, const QMetaMethod &member);
+
     class ConnectArgs {
     public:
         const QObject* sender;
         const char* signal;
         const QObject* receiver;
         const char* method;
+        Qt::ConnectionType connectionType;
     };
 
     static ConnectArgs nextConnectArgs();
@@ -88,6 +101,12 @@ public Q_SLOTS:
     void deleteLater();
 };
 
+class MOQT_CORE_EXPORT QThread : public QObject {
+    Q_OBJECT
+public:
+    QThread(QObject* parent = nullptr);
+};
+
 template<class T>
 class QPointer {
 public:
@@ -4,6 +4,11 @@
 //!
 //! The build script uses `qmake` available in `PATH` to determine paths to the Qt installation and passes them to
 //! `ritual_build`.
+//!
+//! This crate also provides helpers for build scripts of crates using Qt:
+//! `add_resources` builds Qt resource files, `compile_ui` generates Rust code
+//! from Qt Designer UI files, and `add_qobjects` generates C++ classes for Rust structs
+//! with `#[derive(QObject)]`.
 
 #![forbid(unsafe_code)]
 #![deny(missing_docs)]
@@ -22,6 +27,12 @@ use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 
+mod q_object;
+mod ui;
+
+pub use crate::q_object::{add_qobjects, try_add_qobjects};
+pub use crate::ui::{compile_ui, try_compile_ui};
+
 #[allow(clippy::op_ref)] // false positive
 fn detect_closest_version(known: &[&str], current: &str) -> Result<Option<String>> {
     let known = known.map_if_ok(|i| Version::parse(i))?;
@@ -114,15 +125,9 @@ pub fn run(crate_name: &str) -> ! {
     std::process::exit(0);
 }
 
-/// Builds and links a [Qt resource file](https://doc.qt.io/qt-5/resources.html).
-///
-/// The resource file must also be registered using the `qt_core::q_init_resource` macro.
-///
-/// Note that the resource file may not be rebuilt when files referenced
-/// by the resource file are changed.
-/// You may have to run `cargo clean -p crate_name` to force a rebuild.
-pub fn try_add_resources(path: impl AsRef<Path>) -> Result<()> {
-    let path = path.as_ref();
+/// Checks that `path` is an existing file and returns its base name
+/// with special characters replaced by the underscore character.
+fn escaped_base_name(path: &Path) -> Result<String> {
     if !path.exists() {
         bail!("no such file: {:?}", path);
     }
@@ -133,7 +138,7 @@ pub fn try_add_resources(path: impl AsRef<Path>) -> Result<()> {
         path.file_stem()
             .ok_or_else(|| format_err!("can't extract base name from path: {:?}", path))?,
     )?;
-    let escaped_base_name = base_name
+    Ok(base_name
         .chars()
         .map(|c| {
             if c.is_ascii_alphanumeric() || c == '_' {
@@ -142,13 +147,56 @@ pub fn try_add_resources(path: impl AsRef<Path>) -> Result<()> {
                 '_'
             }
         })
-        .collect::<String>();
-    let project_name = format!("ritual_qt_resources_{}", escaped_base_name);
+        .collect())
+}
 
-    let out_dir =
-        PathBuf::from(env::var("OUT_DIR").with_context(|_| "OUT_DIR env var is missing")?);
-    let dir = out_dir.join(&project_name);
+/// Returns the output directory of the build script.
+fn out_dir() -> Result<PathBuf> {
+    Ok(PathBuf::from(
+        env::var("OUT_DIR").with_context(|_| "OUT_DIR env var is missing")?,
+    ))
+}
+
+/// Returns a directory for the qmake project `project_name` inside `OUT_DIR`.
+fn project_dir(project_name: &str) -> Result<PathBuf> {
+    let dir = out_dir()?.join(project_name);
     create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Builds the static library project `project_name` located in `dir`
+/// and links it to the current crate.
+fn build_static_lib(dir: &Path, project_name: &str) -> Result<()> {
+    let pro_file_path = dir.join(format!("{}.pro", project_name));
+    run_command(Command::new("qmake").arg(pro_file_path).current_dir(&dir))?;
+    let make_command = if target::current_env() == target::Env::Msvc {
+        "nmake"
+    } else {
+        "make"
+    };
+    run_command(Command::new(make_command).current_dir(&dir))?;
+    println!("cargo:rustc-link-lib=static={}", project_name);
+    let lib_dir = if target::current_os() == target::OS::Windows {
+        dir.join("release")
+    } else {
+        dir.to_path_buf()
+    };
+    println!("cargo:rustc-link-search={}", path_to_str(&lib_dir)?);
+    Ok(())
+}
+
+/// Builds and links a [Qt resource file](https://doc.qt.io/qt-5/resources.html).
+///
+/// The resource file must also be registered using the `qt_core::q_init_resource` macro.
+///
+/// Note that the resource file may not be rebuilt when files referenced
+/// by the resource file are changed.
+/// You may have to run `cargo clean -p crate_name` to force a rebuild.
+pub fn try_add_resources(path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
+    let escaped_base_name = escaped_base_name(path)?;
+    let project_name = format!("ritual_qt_resources_{}", escaped_base_name);
+    let dir = project_dir(&project_name)?;
 
     let pro_file_path = dir.join(format!("{}.pro", project_name));
     let mut pro_file = create_file(&pro_file_path)?;
@@ -174,20 +222,7 @@ pub fn try_add_resources(path: impl AsRef<Path>) -> Result<()> {
     )?;
     drop(cpp_file);
 
-    run_command(Command::new("qmake").arg(pro_file_path).current_dir(&dir))?;
-    let make_command = if target::current_env() == target::Env::Msvc {
-        "nmake"
-    } else {
-        "make"
-    };
-    run_command(Command::new(make_command).current_dir(&dir))?;
-    println!("cargo:rustc-link-lib=static={}", project_name);
-    let lib_dir = if target::current_os() == target::OS::Windows {
-        dir.join("release")
-    } else {
-        dir
-    };
-    println!("cargo:rustc-link-search={}", path_to_str(&lib_dir)?);
+    build_static_lib(&dir, &project_name)?;
     println!("cargo:rerun-if-changed={}", path_to_str(&path)?);
     Ok(())
 }
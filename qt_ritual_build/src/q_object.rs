@@ -0,0 +1,933 @@
+use crate::{build_static_lib, escaped_base_name, project_dir};
+use qt_ritual_common::q_object::{
+    check_invokable_signature, ffi_function_name, field_kind, lower_camel_case, signal_arguments,
+    upper_camel_case, FieldKind, ValueType,
+};
+use qt_ritual_common::{framework_name, get_installation_data, real_lib_name};
+use ritual_build::common::errors::{bail, format_err, FancyUnwrap, Result, ResultExt};
+use ritual_build::common::file_utils::{canonicalize, create_file, file_to_string, path_to_str};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::Write;
+use std::path::Path;
+use syn::ext::IdentExt;
+use syn::{Attribute, Fields, FnArg, ImplItem, Item, Meta, NestedMeta, Pat, ReturnType, Type};
+
+/// A property of a meta-object class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Property {
+    /// Name of the Rust field.
+    name: String,
+    value_type: ValueType,
+}
+
+/// A signal of a meta-object class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Signal {
+    /// Name of the Rust field.
+    name: String,
+    arguments: Vec<ValueType>,
+}
+
+/// An argument of an invokable method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Argument {
+    name: String,
+    value_type: ValueType,
+}
+
+/// An invokable method of a meta-object class.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Method {
+    /// Name of the Rust method.
+    name: String,
+    /// True if the method takes `&mut self`.
+    is_mut: bool,
+    arguments: Vec<Argument>,
+    return_type: Option<ValueType>,
+}
+
+/// A Rust struct exposed as a `QObject` subclass.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Class {
+    name: String,
+    properties: Vec<Property>,
+    signals: Vec<Signal>,
+    methods: Vec<Method>,
+}
+
+fn has_derive_q_object(attrs: &[Attribute]) -> Result<bool> {
+    for attr in attrs {
+        if !attr.path.is_ident("derive") {
+            continue;
+        }
+        if let Meta::List(list) = attr.parse_meta()? {
+            for item in list.nested {
+                if let NestedMeta::Meta(Meta::Path(path)) = item {
+                    if path.segments.last().map_or(false, |s| s.ident == "QObject") {
+                        return Ok(true);
+                    }
+                }
+            }
+        }
+    }
+    Ok(false)
+}
+
+fn is_invokables(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path
+            .segments
+            .last()
+            .map_or(false, |s| s.ident == "invokables")
+    })
+}
+
+fn parse_methods(item: &syn::ItemImpl) -> Result<Vec<Method>> {
+    let mut methods = Vec::new();
+    for item in &item.items {
+        let method = match item {
+            ImplItem::Method(method) => method,
+            _ => continue,
+        };
+        let name = method.sig.ident.unraw().to_string();
+        let is_mut = match method.sig.receiver() {
+            Some(FnArg::Receiver(receiver)) if receiver.reference.is_some() => {
+                receiver.mutability.is_some()
+            }
+            Some(_) => bail!("invokable method {} must take &self or &mut self", name),
+            None => continue,
+        };
+        check_invokable_signature(&method.sig)
+            .with_context(|_| format!("invalid invokable method {}", name))?;
+        let mut arguments = Vec::new();
+        for arg in method.sig.inputs.iter().skip(1) {
+            let arg = match arg {
+                FnArg::Typed(arg) => arg,
+                FnArg::Receiver(_) => unreachable!(),
+            };
+            let arg_name = match &*arg.pat {
+                Pat::Ident(pat) => pat.ident.unraw().to_string(),
+                _ => bail!("method {} has an argument without a name", name),
+            };
+            let value_type = ValueType::from_type(&arg.ty)
+                .with_context(|_| format!("invalid argument {} of method {}", arg_name, name))?;
+            arguments.push(Argument {
+                name: arg_name,
+                value_type,
+            });
+        }
+        let return_type = match &method.sig.output {
+            ReturnType::Default => None,
+            ReturnType::Type(_, ty) => Some(
+                ValueType::from_type(ty)
+                    .with_context(|_| format!("invalid return type of method {}", name))?,
+            ),
+        };
+        methods.push(Method {
+            name,
+            is_mut,
+            arguments,
+            return_type,
+        });
+    }
+    Ok(methods)
+}
+
+fn parse_items(
+    items: &[Item],
+    classes: &mut Vec<Class>,
+    impls: &mut Vec<(String, Vec<Method>)>,
+) -> Result<()> {
+    for item in items {
+        match item {
+            Item::Struct(item) if has_derive_q_object(&item.attrs)? => {
+                let name = item.ident.unraw().to_string();
+                let fields = match &item.fields {
+                    Fields::Named(fields) => &fields.named,
+                    _ => bail!("struct {} must have named fields", name),
+                };
+                let mut properties = Vec::new();
+                let mut signals = Vec::new();
+                for field in fields {
+                    let kind = field_kind(&field.attrs)
+                        .with_context(|_| format!("invalid qt attribute in struct {}", name))?;
+                    let kind = match kind {
+                        Some(kind) => kind,
+                        None => continue,
+                    };
+                    let field_name = field
+                        .ident
+                        .as_ref()
+                        .ok_or_else(|| format_err!("named field expected"))?;
+                    let field_name = field_name.unraw().to_string();
+                    match kind {
+                        FieldKind::Property => {
+                            let value_type =
+                                ValueType::from_type(&field.ty).with_context(|_| {
+                                    format!("invalid property {}::{}", name, field_name)
+                                })?;
+                            properties.push(Property {
+                                name: field_name,
+                                value_type,
+                            });
+                        }
+                        FieldKind::Signal => {
+                            let arguments = signal_arguments(&field.ty).with_context(|_| {
+                                format!("invalid signal {}::{}", name, field_name)
+                            })?;
+                            signals.push(Signal {
+                                name: field_name,
+                                arguments,
+                            });
+                        }
+                    }
+                }
+                classes.push(Class {
+                    name,
+                    properties,
+                    signals,
+                    methods: Vec::new(),
+                });
+            }
+            Item::Impl(item) if is_invokables(&item.attrs) => {
+                let name = match &*item.self_ty {
+                    Type::Path(path) if path.qself.is_none() => path.path.get_ident(),
+                    _ => None,
+                }
+                .ok_or_else(|| format_err!("invokables must be declared for a struct name"))?;
+                impls.push((name.unraw().to_string(), parse_methods(item)?));
+            }
+            Item::Mod(item) => {
+                if let Some((_, items)) = &item.content {
+                    parse_items(items, classes, impls)?;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn parse_classes(code: &str) -> Result<Vec<Class>> {
+    let file = syn::parse_file(code)?;
+    let mut classes = Vec::new();
+    let mut impls = Vec::new();
+    parse_items(&file.items, &mut classes, &mut impls)?;
+
+    for (class_name, methods) in impls {
+        let class = classes
+            .iter_mut()
+            .find(|class| class.name == class_name)
+            .ok_or_else(|| {
+                format_err!(
+                    "invokables are declared for {}, but it doesn't derive QObject",
+                    class_name
+                )
+            })?;
+        class.methods.extend(methods);
+    }
+    for class in &classes {
+        check_name_conflicts(class)?;
+    }
+    Ok(classes)
+}
+
+/// Checks that C++ names of the members generated for `class` are unique.
+fn check_name_conflicts(class: &Class) -> Result<()> {
+    let mut names = HashMap::new();
+    let mut add_name = |cpp_name: String, description: String| -> Result<()> {
+        if let Some(other) = names.get(&cpp_name) {
+            bail!(
+                "{} conflicts with {} (both are named {} in C++)",
+                description,
+                other,
+                cpp_name
+            );
+        }
+        names.insert(cpp_name, description);
+        Ok(())
+    };
+    add_name("rustData".to_string(), "rustData method".to_string())?;
+    for property in &class.properties {
+        let full_name = format!("{}::{}", class.name, property.name);
+        add_name(
+            lower_camel_case(&property.name),
+            format!("getter of property {}", full_name),
+        )?;
+        add_name(
+            format!("set{}", upper_camel_case(&property.name)),
+            format!("setter of property {}", full_name),
+        )?;
+        add_name(
+            format!("{}Changed", lower_camel_case(&property.name)),
+            format!("notify signal of property {}", full_name),
+        )?;
+    }
+    for signal in &class.signals {
+        add_name(
+            lower_camel_case(&signal.name),
+            format!("signal {}::{}", class.name, signal.name),
+        )?;
+    }
+    for method in &class.methods {
+        add_name(
+            lower_camel_case(&method.name),
+            format!("invokable method {}::{}", class.name, method.name),
+        )?;
+    }
+    Ok(())
+}
+
+fn generate_header(classes: &[Class]) -> String {
+    let mut code = String::new();
+    writeln!(code, "#pragma once").unwrap();
+    writeln!(code, "#include <QObject>").unwrap();
+    writeln!(code, "#include <QString>").unwrap();
+    for class in classes {
+        writeln!(code).unwrap();
+        writeln!(code, "class {} : public QObject {{", class.name).unwrap();
+        writeln!(code, "    Q_OBJECT").unwrap();
+        for property in &class.properties {
+            let name = lower_camel_case(&property.name);
+            writeln!(
+                code,
+                "    Q_PROPERTY({type} {name} READ {name} WRITE set{upper} NOTIFY {name}Changed)",
+                type = property.value_type.cpp_type(),
+                name = name,
+                upper = upper_camel_case(&property.name)
+            )
+            .unwrap();
+        }
+        writeln!(code, "public:").unwrap();
+        writeln!(
+            code,
+            "    explicit {}(QObject* parent = nullptr);",
+            class.name
+        )
+        .unwrap();
+        writeln!(code, "    ~{}() override;", class.name).unwrap();
+        writeln!(code, "    void* rustData() const;").unwrap();
+        for property in &class.properties {
+            writeln!(
+                code,
+                "    {} {}() const;",
+                property.value_type.cpp_type(),
+                lower_camel_case(&property.name)
+            )
+            .unwrap();
+            writeln!(
+                code,
+                "    void set{}({} value);",
+                upper_camel_case(&property.name),
+                property.value_type.cpp_argument_type()
+            )
+            .unwrap();
+        }
+        for method in &class.methods {
+            writeln!(code, "    Q_INVOKABLE {};", method_signature(method, None)).unwrap();
+        }
+        writeln!(code, "Q_SIGNALS:").unwrap();
+        for property in &class.properties {
+            writeln!(
+                code,
+                "    void {}Changed();",
+                lower_camel_case(&property.name)
+            )
+            .unwrap();
+        }
+        for signal in &class.signals {
+            let arguments = signal
+                .arguments
+                .iter()
+                .enumerate()
+                .map(|(index, arg)| format!("{} arg{}", arg.cpp_argument_type(), index))
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(
+                code,
+                "    void {}({});",
+                lower_camel_case(&signal.name),
+                arguments
+            )
+            .unwrap();
+        }
+        writeln!(code, "private:").unwrap();
+        writeln!(code, "    void* m_data;").unwrap();
+        writeln!(code, "}};").unwrap();
+    }
+    code
+}
+
+/// Returns the name of the C++ parameter at `index`.
+///
+/// Rust argument names are not used because they may be C++ keywords (e.g. `class`).
+fn cpp_argument_name(index: usize) -> String {
+    format!("arg{}", index)
+}
+
+/// Returns C++ signature of an invokable method,
+/// qualified with `class_name` if specified.
+fn method_signature(method: &Method, class_name: Option<&str>) -> String {
+    let arguments = method
+        .arguments
+        .iter()
+        .enumerate()
+        .map(|(index, arg)| {
+            format!(
+                "{} {}",
+                arg.value_type.cpp_argument_type(),
+                cpp_argument_name(index)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!(
+        "{} {}{}({})",
+        method.return_type.map_or("void", ValueType::cpp_type),
+        class_name
+            .map(|name| format!("{}::", name))
+            .unwrap_or_default(),
+        lower_camel_case(&method.name),
+        arguments
+    )
+}
+
+fn generate_ffi_declarations(class: &Class, code: &mut String) {
+    let name = |suffix: &str| ffi_function_name(&class.name, suffix);
+    writeln!(code, "void* {}();", name("new")).unwrap();
+    writeln!(code, "void {}(void* data);", name("drop")).unwrap();
+    for property in &class.properties {
+        let value_type = property.value_type;
+        if value_type == ValueType::String {
+            writeln!(
+                code,
+                "void {}(const void* data, QString* output);",
+                name(&format!("get_{}", property.name))
+            )
+            .unwrap();
+        } else {
+            writeln!(
+                code,
+                "{} {}(const void* data);",
+                value_type.cpp_type(),
+                name(&format!("get_{}", property.name))
+            )
+            .unwrap();
+        }
+        writeln!(
+            code,
+            "bool {}(const void* data, {} value);",
+            name(&format!("set_{}", property.name)),
+            value_type.ffi_argument_type()
+        )
+        .unwrap();
+    }
+    for method in &class.methods {
+        let mut arguments = vec!["const void* data".to_string()];
+        for (index, arg) in method.arguments.iter().enumerate() {
+            arguments.push(format!(
+                "{} {}",
+                arg.value_type.ffi_argument_type(),
+                cpp_argument_name(index)
+            ));
+        }
+        let return_type = match method.return_type {
+            Some(ValueType::String) => {
+                arguments.push("QString* output".to_string());
+                "void"
+            }
+            Some(value_type) => value_type.cpp_type(),
+            None => "void",
+        };
+        writeln!(
+            code,
+            "{} {}({});",
+            return_type,
+            name(&format!("invoke_{}", method.name)),
+            arguments.join(", ")
+        )
+        .unwrap();
+    }
+}
+
+fn generate_class_implementation(class: &Class, code: &mut String) {
+    let name = |suffix: &str| ffi_function_name(&class.name, suffix);
+    writeln!(
+        code,
+        "{class}::{class}(QObject* parent) : QObject(parent), m_data({new}()) {{}}",
+        class = class.name,
+        new = name("new")
+    )
+    .unwrap();
+    writeln!(
+        code,
+        "{class}::~{class}() {{ {drop}(m_data); }}",
+        class = class.name,
+        drop = name("drop")
+    )
+    .unwrap();
+    writeln!(
+        code,
+        "void* {}::rustData() const {{ return m_data; }}",
+        class.name
+    )
+    .unwrap();
+
+    for property in &class.properties {
+        let value_type = property.value_type;
+        let camel_name = lower_camel_case(&property.name);
+        let getter = name(&format!("get_{}", property.name));
+        writeln!(code).unwrap();
+        writeln!(
+            code,
+            "{} {}::{}() const {{",
+            value_type.cpp_type(),
+            class.name,
+            camel_name
+        )
+        .unwrap();
+        if value_type == ValueType::String {
+            writeln!(code, "    QString result;").unwrap();
+            writeln!(code, "    {}(m_data, &result);", getter).unwrap();
+            writeln!(code, "    return result;").unwrap();
+        } else {
+            writeln!(code, "    return {}(m_data);", getter).unwrap();
+        }
+        writeln!(code, "}}").unwrap();
+        writeln!(
+            code,
+            "void {}::set{}({} value) {{",
+            class.name,
+            upper_camel_case(&property.name),
+            value_type.cpp_argument_type()
+        )
+        .unwrap();
+        writeln!(
+            code,
+            "    if ({}(m_data, {})) {{",
+            name(&format!("set_{}", property.name)),
+            value_type.ffi_argument("value")
+        )
+        .unwrap();
+        writeln!(code, "        Q_EMIT {}Changed();", camel_name).unwrap();
+        writeln!(code, "    }}").unwrap();
+        writeln!(code, "}}").unwrap();
+    }
+
+    for method in &class.methods {
+        writeln!(code).unwrap();
+        writeln!(code, "{} {{", method_signature(method, Some(&class.name))).unwrap();
+        // Values of properties are compared before and after the call
+        // to emit notify signals.
+        let changeable_properties = if method.is_mut {
+            &class.properties[..]
+        } else {
+            &[]
+        };
+        for property in changeable_properties {
+            let camel_name = lower_camel_case(&property.name);
+            writeln!(
+                code,
+                "    {} ritual_old_{} = this->{}();",
+                property.value_type.cpp_type(),
+                camel_name,
+                camel_name
+            )
+            .unwrap();
+        }
+        let mut arguments = vec!["m_data".to_string()];
+        for (index, arg) in method.arguments.iter().enumerate() {
+            arguments.push(arg.value_type.ffi_argument(&cpp_argument_name(index)));
+        }
+        let function = name(&format!("invoke_{}", method.name));
+        match method.return_type {
+            Some(ValueType::String) => {
+                arguments.push("&ritual_result".to_string());
+                writeln!(code, "    QString ritual_result;").unwrap();
+                writeln!(code, "    {}({});", function, arguments.join(", ")).unwrap();
+            }
+            Some(value_type) => {
+                writeln!(
+                    code,
+                    "    {} ritual_result = {}({});",
+                    value_type.cpp_type(),
+                    function,
+                    arguments.join(", ")
+                )
+                .unwrap();
+            }
+            None => {
+                writeln!(code, "    {}({});", function, arguments.join(", ")).unwrap();
+            }
+        }
+        for property in changeable_properties {
+            let camel_name = lower_camel_case(&property.name);
+            writeln!(
+                code,
+                "    if (this->{name}() != ritual_old_{name}) {{",
+                name = camel_name
+            )
+            .unwrap();
+            writeln!(code, "        Q_EMIT {}Changed();", camel_name).unwrap();
+            writeln!(code, "    }}").unwrap();
+        }
+        if method.return_type.is_some() {
+            writeln!(code, "    return ritual_result;").unwrap();
+        }
+        writeln!(code, "}}").unwrap();
+    }
+}
+
+fn generate_exports(class: &Class, code: &mut String) {
+    let name = |suffix: &str| ffi_function_name(&class.name, suffix);
+    writeln!(
+        code,
+        "extern \"C\" QObject* {}(QObject* parent) {{ return new {}(parent); }}",
+        name("create"),
+        class.name
+    )
+    .unwrap();
+    writeln!(
+        code,
+        "extern \"C\" void* {}(QObject* object) {{",
+        name("data")
+    )
+    .unwrap();
+    writeln!(
+        code,
+        "    {class}* value = qobject_cast<{class}*>(object);",
+        class = class.name
+    )
+    .unwrap();
+    writeln!(code, "    return value ? value->rustData() : nullptr;").unwrap();
+    writeln!(code, "}}").unwrap();
+    for property in &class.properties {
+        writeln!(
+            code,
+            "extern \"C\" void {}(QObject* object) {{ Q_EMIT static_cast<{}*>(object)->{}Changed(); }}",
+            name(&format!("emit_{}_changed", property.name)),
+            class.name,
+            lower_camel_case(&property.name)
+        )
+        .unwrap();
+    }
+    for signal in &class.signals {
+        let mut arguments = vec!["QObject* object".to_string()];
+        let mut emit_arguments = Vec::new();
+        for (index, arg) in signal.arguments.iter().enumerate() {
+            let arg_name = format!("arg{}", index);
+            arguments.push(format!("{} {}", arg.ffi_argument_type(), arg_name));
+            emit_arguments.push(arg.cpp_argument(&arg_name));
+        }
+        writeln!(
+            code,
+            "extern \"C\" void {}({}) {{ Q_EMIT static_cast<{}*>(object)->{}({}); }}",
+            name(&format!("emit_signal_{}", signal.name)),
+            arguments.join(", "),
+            class.name,
+            lower_camel_case(&signal.name),
+            emit_arguments.join(", ")
+        )
+        .unwrap();
+    }
+    writeln!(
+        code,
+        "extern \"C\" int {}(const char* uri, int versionMajor, int versionMinor, \
+         const char* qmlName) {{",
+        name("register")
+    )
+    .unwrap();
+    writeln!(code, "#ifdef RITUAL_QML").unwrap();
+    writeln!(
+        code,
+        "    return qmlRegisterType<{}>(uri, versionMajor, versionMinor, qmlName);",
+        class.name
+    )
+    .unwrap();
+    writeln!(code, "#else").unwrap();
+    writeln!(code, "    Q_UNUSED(uri);").unwrap();
+    writeln!(code, "    Q_UNUSED(versionMajor);").unwrap();
+    writeln!(code, "    Q_UNUSED(versionMinor);").unwrap();
+    writeln!(code, "    Q_UNUSED(qmlName);").unwrap();
+    writeln!(code, "    return -1;").unwrap();
+    writeln!(code, "#endif").unwrap();
+    writeln!(code, "}}").unwrap();
+}
+
+fn generate_source(classes: &[Class], header_name: &str) -> String {
+    let mut code = String::new();
+    writeln!(code, "#include \"{}\"", header_name).unwrap();
+    writeln!(code, "#ifdef RITUAL_QML").unwrap();
+    writeln!(code, "#include <QtQml>").unwrap();
+    writeln!(code, "#endif").unwrap();
+    writeln!(code).unwrap();
+    writeln!(code, "extern \"C\" {{").unwrap();
+    for class in classes {
+        generate_ffi_declarations(class, &mut code);
+    }
+    writeln!(code, "}}").unwrap();
+    for class in classes {
+        writeln!(code).unwrap();
+        generate_class_implementation(class, &mut code);
+        writeln!(code).unwrap();
+        generate_exports(class, &mut code);
+    }
+    code
+}
+
+/// Generates and links C++ classes for Rust structs with `#[derive(QObject)]`
+/// declared in the Rust source file at `path`.
+///
+/// Each struct with the `qt_core::QObject` derive becomes a `QObject` subclass with the same
+/// name. Fields marked with `#[qt(property)]` become Qt properties, fields marked with
+/// `#[qt(signal)]` become Qt signals, and methods declared in impl blocks with
+/// the `qt_core::invokables` attribute become invokable methods.
+/// The classes are processed by Qt's `moc` tool, so the properties and methods are available
+/// through the meta-object system (e.g. from QML or `QObject::property`).
+///
+/// Structs and impl blocks in inline modules are also processed, but modules in other files
+/// are not. Call this function for each file containing such structs.
+/// Names of the structs must be unique within the crate.
+///
+/// If the Qt QML module is available, the classes can be registered with
+/// `qmlRegisterType` using the generated `register_qml_type` function. In this case,
+/// the Qt QML library is also linked to the crate.
+pub fn try_add_qobjects(path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
+    let escaped_base_name = escaped_base_name(path)?;
+    let path = canonicalize(path)?;
+    let classes = parse_classes(&file_to_string(&path)?)
+        .with_context(|_| format!("failed to process {:?}", path))?;
+    if classes.is_empty() {
+        bail!("no structs with #[derive(QObject)] found in {:?}", path);
+    }
+
+    // The Qt QML library is linked here, so the crate doesn't have to depend on `qt_qml`.
+    let qml_data = get_installation_data("qt_qml", None).ok();
+
+    let project_name = format!("ritual_qt_qobjects_{}", escaped_base_name);
+    let dir = project_dir(&project_name)?;
+
+    let mut pro_file = create_file(dir.join(format!("{}.pro", project_name)))?;
+    writeln!(pro_file, "TEMPLATE = lib")?;
+    writeln!(pro_file, "CONFIG += staticlib")?;
+    writeln!(pro_file, "SOURCES += 1.cpp")?;
+    writeln!(pro_file, "HEADERS += qobjects.h")?;
+    if qml_data.is_some() {
+        writeln!(pro_file, "QT += qml")?;
+        writeln!(pro_file, "DEFINES += RITUAL_QML")?;
+    }
+    drop(pro_file);
+
+    let mut header_file = create_file(dir.join("qobjects.h"))?;
+    write!(header_file, "{}", generate_header(&classes))?;
+    drop(header_file);
+
+    let mut cpp_file = create_file(dir.join("1.cpp"))?;
+    write!(cpp_file, "{}", generate_source(&classes, "qobjects.h"))?;
+    drop(cpp_file);
+
+    build_static_lib(&dir, &project_name)?;
+    if let Some(qml_data) = qml_data {
+        let lib_path = path_to_str(&qml_data.lib_path)?;
+        if qml_data.is_framework {
+            println!("cargo:rustc-link-search=framework={}", lib_path);
+            println!(
+                "cargo:rustc-link-lib=framework={}",
+                framework_name("qt_qml")
+            );
+        } else {
+            println!("cargo:rustc-link-search=native={}", lib_path);
+            println!("cargo:rustc-link-lib={}", real_lib_name("qt_qml"));
+        }
+    }
+    println!("cargo:rerun-if-changed={}", path_to_str(&path)?);
+    Ok(())
+}
+
+/// Calls `try_add_qobjects` and panics on an error.
+pub fn add_qobjects(path: impl AsRef<Path>) {
+    try_add_qobjects(path).fancy_unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        generate_header, generate_source, parse_classes, Argument, Class, Method, Property, Signal,
+        ValueType,
+    };
+
+    const CODE: &str = r#"
+use qt_core::{invokables, QObject};
+
+#[derive(Default, QObject)]
+pub struct Counter {
+    #[qt(property)]
+    value: i32,
+    #[qt(property)]
+    item_label: String,
+    #[qt(signal)]
+    overflowed: DeclaredSignal<(i32, String)>,
+    history: Vec<i32>,
+}
+
+#[invokables]
+impl Counter {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    fn add(&mut self, value: i32) {
+        self.value += value;
+    }
+
+    fn describe(&self, prefix: String) -> String {
+        format!("{}{}", prefix, self.value)
+    }
+}
+
+mod inner {
+    #[derive(Debug, Default, qt_core::QObject)]
+    struct Empty {}
+}
+"#;
+
+    #[test]
+    fn parse() {
+        let classes = parse_classes(CODE).unwrap();
+        assert_eq!(
+            classes,
+            vec![
+                Class {
+                    name: "Counter".into(),
+                    properties: vec![
+                        Property {
+                            name: "value".into(),
+                            value_type: ValueType::I32,
+                        },
+                        Property {
+                            name: "item_label".into(),
+                            value_type: ValueType::String,
+                        },
+                    ],
+                    signals: vec![Signal {
+                        name: "overflowed".into(),
+                        arguments: vec![ValueType::I32, ValueType::String],
+                    }],
+                    methods: vec![
+                        Method {
+                            name: "add".into(),
+                            is_mut: true,
+                            arguments: vec![Argument {
+                                name: "value".into(),
+                                value_type: ValueType::I32,
+                            }],
+                            return_type: None,
+                        },
+                        Method {
+                            name: "describe".into(),
+                            is_mut: false,
+                            arguments: vec![Argument {
+                                name: "prefix".into(),
+                                value_type: ValueType::String,
+                            }],
+                            return_type: Some(ValueType::String),
+                        },
+                    ],
+                },
+                Class {
+                    name: "Empty".into(),
+                    properties: Vec::new(),
+                    signals: Vec::new(),
+                    methods: Vec::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let unsupported = CODE.replace("value: i32", "value: Vec<i32>");
+        assert!(parse_classes(&unsupported).is_err());
+        let conflict = CODE.replace("fn add(", "fn value(");
+        assert!(parse_classes(&conflict).is_err());
+        let setter_conflict = CODE.replace("fn add(", "fn set_value(");
+        assert!(parse_classes(&setter_conflict).is_err());
+        let notify_conflict = CODE.replace("fn add(", "fn value_changed(");
+        assert!(parse_classes(&notify_conflict).is_err());
+        let property_conflict =
+            CODE.replace("history: Vec<i32>", "#[qt(property)] set_value: bool");
+        assert!(parse_classes(&property_conflict).is_err());
+        let signal_conflict = CODE.replace("overflowed:", "value_changed:");
+        assert!(parse_classes(&signal_conflict).is_err());
+        let invalid_signal = CODE.replace("(i32, String)>", "(Vec<i32>,)>");
+        assert!(parse_classes(&invalid_signal).is_err());
+        let generic_method = CODE.replace("fn add(", "fn add<T>(");
+        assert!(parse_classes(&generic_method).is_err());
+        let no_conflict = CODE.replace("fn add(", "fn set_values(");
+        assert!(parse_classes(&no_conflict).is_ok());
+        let no_derive = CODE.replace(", QObject)]\npub", ")]\npub");
+        assert!(parse_classes(&no_derive).is_err());
+        assert!(parse_classes("struct").is_err());
+    }
+
+    #[test]
+    fn generate() {
+        let classes = parse_classes(CODE).unwrap();
+        let header = generate_header(&classes);
+        assert!(header.contains("class Counter : public QObject {"));
+        assert!(header.contains(
+            "Q_PROPERTY(QString itemLabel READ itemLabel WRITE setItemLabel NOTIFY itemLabelChanged)"
+        ));
+        assert!(header.contains("Q_INVOKABLE QString describe(const QString& arg0);"));
+        assert!(header.contains("void valueChanged();"));
+        assert!(header.contains("void overflowed(int arg0, const QString& arg1);"));
+
+        let source = generate_source(&classes, "qobjects.h");
+        assert!(source.contains(
+            "bool ritual_qobject_Counter_set_item_label(const void* data, const QString* value);"
+        ));
+        assert!(source.contains("    if (ritual_qobject_Counter_set_value(m_data, value)) {"));
+        assert!(source.contains("    int ritual_old_value = this->value();"));
+        assert!(source.contains(
+            "    ritual_qobject_Counter_invoke_describe(m_data, &arg0, &ritual_result);"
+        ));
+        assert!(source.contains(
+            "extern \"C\" void ritual_qobject_Counter_emit_signal_overflowed(\
+             QObject* object, int arg0, const QString* arg1) \
+             { Q_EMIT static_cast<Counter*>(object)->overflowed(arg0, *arg1); }"
+        ));
+        assert!(
+            source.contains("qmlRegisterType<Empty>(uri, versionMajor, versionMinor, qmlName);")
+        );
+    }
+
+    #[test]
+    fn cpp_keyword_arguments() {
+        let code = CODE
+            .replace(
+                "fn add(&mut self, value: i32)",
+                "fn add(&mut self, class: i32)",
+            )
+            .replace("self.value += value;", "self.value += class;")
+            .replace(
+                "describe(&self, prefix: String)",
+                "describe(&self, this: String)",
+            )
+            .replace("format!(\"{}{}\", prefix,", "format!(\"{}{}\", this,");
+        let classes = parse_classes(&code).unwrap();
+        assert_eq!(classes[0].methods[0].arguments[0].name, "class");
+
+        let header = generate_header(&classes);
+        assert!(header.contains("Q_INVOKABLE void add(int arg0);"));
+        assert!(header.contains("Q_INVOKABLE QString describe(const QString& arg0);"));
+        let source = generate_source(&classes, "qobjects.h");
+        assert!(
+            source.contains("void ritual_qobject_Counter_invoke_add(const void* data, int arg0);")
+        );
+        assert!(source.contains("void Counter::add(int arg0) {"));
+        assert!(!header.contains("class)") && !source.contains("class)"));
+        assert!(!source.contains("this)") && !source.contains("&this"));
+    }
+}
@@ -0,0 +1,502 @@
+use crate::{build_static_lib, escaped_base_name, out_dir, project_dir};
+use ritual_build::common::errors::{bail, err_msg, format_err, FancyUnwrap, Result};
+use ritual_build::common::file_utils::{
+    canonicalize, create_file, file_to_string, os_str_to_str, path_to_str,
+};
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::io::Write;
+use std::path::Path;
+
+/// Name of the struct field containing the top level widget.
+const ROOT_FIELD_NAME: &str = "widget";
+
+/// Names that can't be used as Rust identifiers, even as raw identifiers.
+const FORBIDDEN_NAMES: &[&str] = &["self", "Self", "super", "crate", "_"];
+
+/// Rust keywords that must be escaped with `r#`.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop",
+    "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static",
+    "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual",
+    "where", "while", "yield",
+];
+
+/// Classes of `qt_widgets` that can be created by a UI file.
+const WIDGETS_CLASSES: &[&str] = &[
+    "QAction",
+    "QActionGroup",
+    "QCalendarWidget",
+    "QCheckBox",
+    "QColumnView",
+    "QComboBox",
+    "QCommandLinkButton",
+    "QDateEdit",
+    "QDateTimeEdit",
+    "QDial",
+    "QDialog",
+    "QDialogButtonBox",
+    "QDockWidget",
+    "QDoubleSpinBox",
+    "QFontComboBox",
+    "QFormLayout",
+    "QFrame",
+    "QGraphicsView",
+    "QGridLayout",
+    "QGroupBox",
+    "QHBoxLayout",
+    "QKeySequenceEdit",
+    "QLCDNumber",
+    "QLabel",
+    "QLineEdit",
+    "QListView",
+    "QListWidget",
+    "QMainWindow",
+    "QMdiArea",
+    "QMenu",
+    "QMenuBar",
+    "QOpenGLWidget",
+    "QPlainTextEdit",
+    "QProgressBar",
+    "QPushButton",
+    "QRadioButton",
+    "QScrollArea",
+    "QScrollBar",
+    "QSlider",
+    "QSpinBox",
+    "QSplitter",
+    "QStackedLayout",
+    "QStackedWidget",
+    "QStatusBar",
+    "QTabWidget",
+    "QTableView",
+    "QTableWidget",
+    "QTextBrowser",
+    "QTextEdit",
+    "QTimeEdit",
+    "QToolBar",
+    "QToolBox",
+    "QToolButton",
+    "QTreeView",
+    "QTreeWidget",
+    "QUndoView",
+    "QVBoxLayout",
+    "QWidget",
+    "QWizard",
+    "QWizardPage",
+];
+
+/// A named object declared in a UI file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct UiObject {
+    name: String,
+    class_name: String,
+}
+
+/// Information about a UI file required to generate the code.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct UiForm {
+    /// Name of the form class (content of the `<class>` element).
+    class_name: String,
+    /// Class of the top level widget.
+    root_class_name: String,
+    /// Widgets, layouts and actions created by the form.
+    objects: Vec<UiObject>,
+}
+
+fn parse_ui(xml: &str) -> Result<UiForm> {
+    let document = roxmltree::Document::parse(xml)
+        .map_err(|err| format_err!("failed to parse UI file: {}", err))?;
+    let root = document.root_element();
+    if !root.has_tag_name("ui") {
+        bail!("unexpected root element: {:?}", root.tag_name().name());
+    }
+    if root
+        .children()
+        .any(|node| node.has_tag_name("customwidgets"))
+    {
+        bail!("custom widgets are not supported");
+    }
+    let class_name = root
+        .children()
+        .find(|node| node.has_tag_name("class"))
+        .and_then(|node| node.text())
+        .ok_or_else(|| err_msg("<class> element is missing"))?
+        .trim()
+        .to_string();
+    let root_widget = root
+        .children()
+        .find(|node| node.has_tag_name("widget"))
+        .ok_or_else(|| err_msg("top level <widget> element is missing"))?;
+    let root_class_name = root_widget
+        .attribute("class")
+        .ok_or_else(|| err_msg("top level widget doesn't have a class"))?
+        .to_string();
+    check_class(ROOT_FIELD_NAME, &root_class_name)?;
+
+    let mut names = HashSet::new();
+    let mut objects = Vec::new();
+    for node in root_widget.descendants().skip(1) {
+        let class_name = match node.tag_name().name() {
+            "widget" | "layout" => node.attribute("class").ok_or_else(|| {
+                format_err!("{} element doesn't have a class", node.tag_name().name())
+            })?,
+            "action" => "QAction",
+            "actiongroup" => "QActionGroup",
+            _ => continue,
+        };
+        // uic generates names for unnamed layouts, but they can't be referenced in Rust.
+        let name = if let Some(name) = node.attribute("name") {
+            name
+        } else {
+            continue;
+        };
+        if name == ROOT_FIELD_NAME {
+            bail!(
+                "object name {:?} is reserved for the top level widget",
+                ROOT_FIELD_NAME
+            );
+        }
+        if FORBIDDEN_NAMES.contains(&name) {
+            bail!(
+                "object name {:?} can't be used as a Rust identifier; rename the object",
+                name
+            );
+        }
+        if !names.insert(name) {
+            bail!("duplicate object name: {:?}", name);
+        }
+        // Designer's line widget is a `QFrame`.
+        let class_name = if class_name == "Line" {
+            "QFrame"
+        } else {
+            class_name
+        };
+        check_class(name, class_name)?;
+        objects.push(UiObject {
+            name: name.to_string(),
+            class_name: class_name.to_string(),
+        });
+    }
+
+    Ok(UiForm {
+        class_name,
+        root_class_name,
+        objects,
+    })
+}
+
+/// Checks that the object's class is available in `qt_widgets`.
+fn check_class(name: &str, class_name: &str) -> Result<()> {
+    if !WIDGETS_CLASSES.contains(&class_name) {
+        bail!(
+            "class {:?} of object {:?} is not supported: only qt_widgets classes can be used",
+            class_name,
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Returns a Rust identifier for the object name, escaping Rust keywords.
+fn rust_identifier(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("r#{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+fn generate_cpp_code(form: &UiForm, header_name: &str, function_name: &str) -> String {
+    let mut code = String::new();
+    writeln!(code, "#include \"{}\"", header_name).unwrap();
+    writeln!(code).unwrap();
+    writeln!(
+        code,
+        "extern \"C\" void* {}(void** objects) {{",
+        function_name
+    )
+    .unwrap();
+    writeln!(
+        code,
+        "    {class}* widget = new {class}();",
+        class = form.root_class_name
+    )
+    .unwrap();
+    writeln!(code, "    Ui::{} ui;", form.class_name).unwrap();
+    writeln!(code, "    ui.setupUi(widget);").unwrap();
+    for (index, object) in form.objects.iter().enumerate() {
+        writeln!(code, "    objects[{}] = ui.{};", index, object.name).unwrap();
+    }
+    writeln!(code, "    return widget;").unwrap();
+    writeln!(code, "}}").unwrap();
+    code
+}
+
+fn generate_rust_code(form: &UiForm, file_name: &str, function_name: &str) -> String {
+    let mut code = String::new();
+    writeln!(code, "/// Widgets created from `{}`.", file_name).unwrap();
+    writeln!(code, "#[derive(Debug)]").unwrap();
+    writeln!(code, "#[allow(non_snake_case)]").unwrap();
+    writeln!(code, "pub struct {} {{", form.class_name).unwrap();
+    writeln!(code, "    /// The top level widget.").unwrap();
+    writeln!(
+        code,
+        "    pub {}: ::qt_core::QBox<::qt_widgets::{}>,",
+        ROOT_FIELD_NAME, form.root_class_name
+    )
+    .unwrap();
+    for object in &form.objects {
+        writeln!(code, "    /// The `{}` object.", object.name).unwrap();
+        writeln!(
+            code,
+            "    pub {}: ::qt_core::QPtr<::qt_widgets::{}>,",
+            rust_identifier(&object.name),
+            object.class_name
+        )
+        .unwrap();
+    }
+    writeln!(code, "}}").unwrap();
+    writeln!(code).unwrap();
+
+    writeln!(code, "impl {} {{", form.class_name).unwrap();
+    writeln!(
+        code,
+        "    /// Creates the top level widget and all its children."
+    )
+    .unwrap();
+    writeln!(code, "    pub unsafe fn new() -> Self {{").unwrap();
+    writeln!(code, "        extern \"C\" {{").unwrap();
+    writeln!(
+        code,
+        "            fn {}(objects: *mut *mut ::std::ffi::c_void) -> *mut ::std::ffi::c_void;",
+        function_name
+    )
+    .unwrap();
+    writeln!(code, "        }}").unwrap();
+    writeln!(
+        code,
+        "        let mut objects = [::std::ptr::null_mut::<::std::ffi::c_void>(); {}];",
+        form.objects.len()
+    )
+    .unwrap();
+    writeln!(
+        code,
+        "        let widget = {}(objects.as_mut_ptr());",
+        function_name
+    )
+    .unwrap();
+    writeln!(code, "        Self {{").unwrap();
+    writeln!(
+        code,
+        "            {}: ::qt_core::QBox::from_raw(widget as *const ::qt_widgets::{}),",
+        ROOT_FIELD_NAME, form.root_class_name
+    )
+    .unwrap();
+    for (index, object) in form.objects.iter().enumerate() {
+        writeln!(
+            code,
+            "            {}: ::qt_core::QPtr::from_raw(objects[{}] as *const ::qt_widgets::{}),",
+            rust_identifier(&object.name),
+            index,
+            object.class_name
+        )
+        .unwrap();
+    }
+    writeln!(code, "        }}").unwrap();
+    writeln!(code, "    }}").unwrap();
+    writeln!(code, "}}").unwrap();
+    code
+}
+
+/// Compiles a [Qt Designer](https://doc.qt.io/qt-5/qtdesigner-manual.html) UI file
+/// into Rust code.
+///
+/// The UI file is processed by Qt's `uic` tool, and the resulting C++ code is built
+/// and linked to the crate. This function also generates
+/// `ui_<base name>.rs` file in `OUT_DIR`, containing a struct named after the
+/// form's class (e.g. `Form`). Include the generated file in your crate:
+/// ```ignore
+/// mod ui {
+///     include!(concat!(env!("OUT_DIR"), "/ui_form.rs"));
+/// }
+/// ```
+/// Special characters (such as '-') in the base name of the UI file
+/// are replaced by the underscore character (`'_'`).
+///
+/// The struct's `widget` field contains the top level widget (`QBox<T>`, where `T` is
+/// the class of the top level widget). All named widgets, layouts and actions of the form
+/// are available as fields with the same names and corresponding types (`QPtr<T>`).
+/// Use `unsafe fn new() -> Self` to create the widgets.
+///
+/// Unlike the `qt_ui_tools::ui_form` macro, the objects are created without
+/// `QUiLoader`, so missing objects or mismatched types are detected at compile time.
+/// The crate must depend on `qt_core` and `qt_widgets`. Custom widgets and classes from
+/// other Qt modules are not supported. Object names must be valid Rust identifiers
+/// (keywords are escaped, but `self`, `Self`, `super` and `crate` are rejected).
+pub fn try_compile_ui(path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
+    let escaped_base_name = escaped_base_name(path)?;
+    let path = canonicalize(path)?;
+    let form = parse_ui(&file_to_string(&path)?)?;
+
+    let project_name = format!("ritual_qt_ui_{}", escaped_base_name);
+    let dir = project_dir(&project_name)?;
+    let function_name = format!("ritual_ui_create_{}", escaped_base_name);
+
+    let mut pro_file = create_file(dir.join(format!("{}.pro", project_name)))?;
+    writeln!(pro_file, "TEMPLATE = lib")?;
+    writeln!(pro_file, "CONFIG += staticlib")?;
+    writeln!(pro_file, "QT += widgets")?;
+    writeln!(pro_file, "SOURCES += 1.cpp")?;
+    writeln!(pro_file, "FORMS += {}", path_to_str(&path)?)?;
+    drop(pro_file);
+
+    let header_name = format!(
+        "ui_{}.h",
+        os_str_to_str(
+            path.file_stem()
+                .ok_or_else(|| format_err!("can't extract base name from path: {:?}", path))?
+        )?
+    );
+    let mut cpp_file = create_file(dir.join("1.cpp"))?;
+    write!(
+        cpp_file,
+        "{}",
+        generate_cpp_code(&form, &header_name, &function_name)
+    )?;
+    drop(cpp_file);
+
+    build_static_lib(&dir, &project_name)?;
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| format_err!("can't extract file name from path: {:?}", path))?;
+    let rust_file_path = out_dir()?.join(format!("ui_{}.rs", escaped_base_name));
+    let mut rust_file = create_file(rust_file_path)?;
+    write!(
+        rust_file,
+        "{}",
+        generate_rust_code(&form, os_str_to_str(file_name)?, &function_name)
+    )?;
+    drop(rust_file);
+
+    println!("cargo:rerun-if-changed={}", path_to_str(&path)?);
+    Ok(())
+}
+
+/// Calls `try_compile_ui` and panics on an error.
+pub fn compile_ui(path: impl AsRef<Path>) {
+    try_compile_ui(path).fancy_unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        generate_cpp_code, generate_rust_code, parse_ui, rust_identifier, UiForm, UiObject,
+    };
+
+    const FORM: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ui version="4.0">
+ <class>Form</class>
+ <widget class="QDialog" name="Form">
+  <layout class="QVBoxLayout" name="verticalLayout">
+   <item>
+    <widget class="QLineEdit" name="input"/>
+   </item>
+   <item>
+    <widget class="Line" name="line"/>
+   </item>
+   <item>
+    <layout class="QHBoxLayout">
+     <item>
+      <widget class="QPushButton" name="type">
+       <property name="text">
+        <string>Add</string>
+       </property>
+      </widget>
+     </item>
+     <item>
+      <spacer name="horizontalSpacer"/>
+     </item>
+    </layout>
+   </item>
+  </layout>
+  <action name="actionQuit"/>
+ </widget>
+ <resources/>
+ <connections/>
+</ui>
+"#;
+
+    fn object(name: &str, class_name: &str) -> UiObject {
+        UiObject {
+            name: name.into(),
+            class_name: class_name.into(),
+        }
+    }
+
+    #[test]
+    fn parse() {
+        let form = parse_ui(FORM).unwrap();
+        assert_eq!(
+            form,
+            UiForm {
+                class_name: "Form".into(),
+                root_class_name: "QDialog".into(),
+                objects: vec![
+                    object("verticalLayout", "QVBoxLayout"),
+                    object("input", "QLineEdit"),
+                    object("line", "QFrame"),
+                    object("type", "QPushButton"),
+                    object("actionQuit", "QAction"),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        let custom = FORM.replace(
+            "<resources/>",
+            "<customwidgets><customwidget><class>W</class></customwidget></customwidgets>",
+        );
+        assert!(parse_ui(&custom).is_err());
+        let duplicate = FORM.replace("name=\"line\"", "name=\"input\"");
+        assert!(parse_ui(&duplicate).is_err());
+        let reserved = FORM.replace("name=\"line\"", "name=\"widget\"");
+        assert!(parse_ui(&reserved).is_err());
+        let forbidden = FORM.replace("name=\"line\"", "name=\"self\"");
+        assert!(parse_ui(&forbidden).is_err());
+        let other_module = FORM.replace("QLineEdit", "QWebEngineView");
+        assert!(parse_ui(&other_module).is_err());
+        let other_root = FORM.replace("QDialog", "QQuickWidget");
+        assert!(parse_ui(&other_root).is_err());
+        assert!(parse_ui("<ui>").is_err());
+    }
+
+    #[test]
+    fn generate() {
+        let form = parse_ui(FORM).unwrap();
+        let cpp = generate_cpp_code(&form, "ui_form.h", "ritual_ui_create_form");
+        assert!(cpp.contains("QDialog* widget = new QDialog();"));
+        assert!(cpp.contains("Ui::Form ui;"));
+        assert!(cpp.contains("objects[3] = ui.type;"));
+
+        let rust = generate_rust_code(&form, "form.ui", "ritual_ui_create_form");
+        assert!(rust.contains("pub struct Form {"));
+        assert!(rust.contains("pub widget: ::qt_core::QBox<::qt_widgets::QDialog>,"));
+        assert!(rust.contains("pub r#type: ::qt_core::QPtr<::qt_widgets::QPushButton>,"));
+        assert!(rust.contains("pub line: ::qt_core::QPtr<::qt_widgets::QFrame>,"));
+        assert!(rust.contains("::std::ffi::c_void>(); 5];"));
+    }
+
+    #[test]
+    fn identifiers() {
+        assert_eq!(rust_identifier("input"), "input");
+        assert_eq!(rust_identifier("type"), "r#type");
+        assert_eq!(rust_identifier("macro"), "r#macro");
+        assert_eq!(rust_identifier("abstract"), "r#abstract");
+    }
+}
@@ -16,6 +16,8 @@ use ritual_common::utils::get_command_output;
 use std::path::PathBuf;
 use std::process::Command;
 
+pub use qt_macros_common::{q_object, signals};
+
 /// Makes a query to `qmake`.
 fn run_qmake_string_query(property: &str, qmake_path: Option<&str>) -> Result<String> {
     let command = qmake_path.unwrap_or("qmake");
@@ -219,3 +221,25 @@ pub fn lib_dependencies(crate_name: &str) -> Result<&'static [&'static str]> {
         _ => bail!("Unknown crate name: {}", crate_name),
     })
 }
+
+#[test]
+fn macros_case_conversions_match_ritual() {
+    use qt_macros_common::string_utils;
+
+    // `qt_macros` must generate the same signal wrapper names as `ritual`.
+    for &name in &[
+        "QString",
+        "QModelIndex",
+        "QVectorOfInt",
+        "QOpenGLContext",
+        "QIODevice",
+        "Qt3DWindow",
+        "QPointF",
+        "QListOfQPairOfIntQString",
+        "SignalOf_q_string_int",
+        "vec2_d",
+    ] {
+        assert_eq!(string_utils::to_snake_case(name), name.to_snake_case());
+        assert_eq!(string_utils::to_class_case(name), name.to_class_case());
+    }
+}
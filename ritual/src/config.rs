@@ -6,11 +6,14 @@ use crate::cpp_parser::CppParserOutput;
 use crate::processor::{ProcessingSteps, ProcessorData};
 use crate::rust_info::{NameType, RustItem, RustPathScope};
 use crate::rust_type::RustPath;
+use itertools::Itertools;
 use ritual_common::cpp_build_config::{CppBuildConfig, CppBuildPaths};
 use ritual_common::errors::{bail, Result};
+use ritual_common::string_utils::{CaseOperations, WordIterator};
 use ritual_common::target::Target;
 use ritual_common::toml;
 use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -170,6 +173,113 @@ impl CrateProperties {
     }
 }
 
+/// Rules for converting names of C++ items to Rust names.
+///
+/// The default policy keeps C++ names as is (except for the case conversion).
+#[derive(Debug, Clone, Default)]
+pub struct RustNamingPolicy {
+    prefixes: Vec<String>,
+    case_exceptions: Vec<String>,
+    renames: HashMap<CppPath, String>,
+}
+
+impl RustNamingPolicy {
+    /// Adds a prefix that should be removed from names of types, enum values and functions
+    /// (e.g. `"vtk"` or `"Q"`). The prefix is only removed if it's followed by
+    /// a word boundary, not followed by a digit, and is not the whole name.
+    /// Prefixes are compared case-insensitively.
+    pub fn add_prefix(&mut self, prefix: impl Into<String>) {
+        self.prefixes.push(prefix.into());
+    }
+
+    /// Adds a word that should be treated as a single word when converting case
+    /// (e.g. `"XML"` results in `xml_reader` and `XmlReader` instead of
+    /// `x_m_l_reader` and `XMLReader`).
+    pub fn add_case_exception(&mut self, word: impl Into<String>) {
+        self.case_exceptions.push(word.into());
+    }
+
+    /// Sets the last part of the Rust path that will be used for the C++ item
+    /// with the specified path, bypassing prefix removal and case conversion.
+    pub fn add_rename(&mut self, cpp_path: CppPath, rust_name: impl Into<String>) {
+        self.renames.insert(cpp_path, rust_name.into());
+    }
+
+    /// Returns prefixes added with `RustNamingPolicy::add_prefix`.
+    pub fn prefixes(&self) -> &[String] {
+        &self.prefixes
+    }
+
+    /// Returns words added with `RustNamingPolicy::add_case_exception`.
+    pub fn case_exceptions(&self) -> &[String] {
+        &self.case_exceptions
+    }
+
+    /// Returns the name set for `cpp_path` with `RustNamingPolicy::add_rename`.
+    pub fn rename(&self, cpp_path: &CppPath) -> Option<&str> {
+        self.renames.get(cpp_path).map(String::as_str)
+    }
+
+    /// Splits `name` into words, removing the first matching prefix
+    /// and merging words of case exceptions.
+    fn words(&self, name: &str) -> Vec<String> {
+        let mut words = WordIterator::new(name).map(String::from).collect_vec();
+        for prefix in &self.prefixes {
+            let prefix_words = WordIterator::new(prefix).collect_vec();
+            if prefix_words.is_empty() || words.len() <= prefix_words.len() {
+                continue;
+            }
+            let is_match = words
+                .iter()
+                .zip(&prefix_words)
+                .all(|(word, prefix_word)| word.eq_ignore_ascii_case(prefix_word));
+            if is_match && !words[prefix_words.len()].starts_with(|c: char| c.is_digit(10)) {
+                words.drain(..prefix_words.len());
+                break;
+            }
+        }
+        for exception in &self.case_exceptions {
+            let exception_words = WordIterator::new(exception).collect_vec();
+            if exception_words.len() < 2 {
+                continue;
+            }
+            let mut i = 0;
+            while i + exception_words.len() <= words.len() {
+                let is_match = words[i..i + exception_words.len()]
+                    .iter()
+                    .zip(&exception_words)
+                    .all(|(word, exception_word)| word.eq_ignore_ascii_case(exception_word));
+                if is_match {
+                    words.drain(i + 1..i + exception_words.len());
+                    words[i] = exception.clone();
+                }
+                i += 1;
+            }
+        }
+        words
+    }
+
+    /// Converts `name` to class case ("OneTwo") according to the policy.
+    pub fn to_class_case(&self, name: &str) -> String {
+        let words = self.words(name);
+        words
+            .iter()
+            .map(String::as_str)
+            .collect_vec()
+            .to_class_case()
+    }
+
+    /// Converts `name` to snake case ("one_two") according to the policy.
+    pub fn to_snake_case(&self, name: &str) -> String {
+        let words = self.words(name);
+        words
+            .iter()
+            .map(String::as_str)
+            .collect_vec()
+            .to_snake_case()
+    }
+}
+
 pub type RustPathScopeHook = dyn Fn(&CppPath) -> Result<Option<RustPathScope>> + 'static;
 pub type RustPathHook =
     dyn Fn(&CppPath, NameType<'_>, &ProcessorData<'_>) -> Result<Option<RustPath>> + 'static;
@@ -177,6 +287,7 @@ pub type RustItemHook = dyn Fn(&mut RustItem, &ProcessorData<'_>) -> Result<()>
 pub type AfterCppParserHook =
     dyn Fn(&mut ProcessorData<'_>, &CppParserOutput) -> Result<()> + 'static;
 pub type CppItemFilterHook = dyn Fn(&CppItem) -> Result<bool> + 'static;
+pub type CrateFeatureHook = dyn Fn(&CppItem) -> Result<Option<String>> + 'static;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkerLibraryConfig {
@@ -207,6 +318,9 @@ pub struct Config {
     cpp_lib_version: Option<String>,
     crate_template_path: Option<PathBuf>,
     include_directives: Vec<PathBuf>,
+    excluded_extra_files: Vec<String>,
+    test_features: Vec<String>,
+    test_crates: Vec<String>,
     target_include_paths: Vec<PathBuf>,
     cpp_build_config: CppBuildConfig,
     cpp_build_paths: CppBuildPaths,
@@ -216,12 +330,19 @@ pub struct Config {
     cpp_parser_path_hook: Option<Box<dyn Fn(&CppPath) -> Result<bool>>>,
     rust_path_scope_hook: Option<Box<RustPathScopeHook>>,
     rust_path_hook: Option<Box<RustPathHook>>,
+    rust_naming_policy: RustNamingPolicy,
+    rust_rename_map_path: Option<PathBuf>,
+    use_arguments_builders: bool,
+    use_std_hash_specializations: bool,
+    closed_enums: Vec<CppPath>,
     rust_item_hook: Option<Box<RustItemHook>>,
     after_cpp_parser_hooks: Vec<Box<AfterCppParserHook>>,
     cpp_item_filter_hook: Option<Box<CppItemFilterHook>>,
+    crate_feature_hook: Option<Box<CrateFeatureHook>>,
     cluster_config: Option<ClusterConfig>,
     cpp_checker_tests: Vec<PreliminaryTest>,
     write_dependencies_local_paths: bool,
+    cpp_wrapper_items_per_file: usize,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -241,6 +362,9 @@ impl Config {
             cpp_build_paths: Default::default(),
             target_include_paths: Default::default(),
             include_directives: Default::default(),
+            excluded_extra_files: Default::default(),
+            test_features: Default::default(),
+            test_crates: Default::default(),
             cpp_parser_arguments: Default::default(),
             cpp_build_config: Default::default(),
             movable_types_hook: Default::default(),
@@ -249,12 +373,19 @@ impl Config {
             cpp_parser_path_hook: Default::default(),
             rust_path_scope_hook: Default::default(),
             rust_path_hook: Default::default(),
+            rust_naming_policy: Default::default(),
+            rust_rename_map_path: Default::default(),
+            use_arguments_builders: false,
+            use_std_hash_specializations: false,
+            closed_enums: Default::default(),
             rust_item_hook: Default::default(),
             after_cpp_parser_hooks: Default::default(),
             cpp_item_filter_hook: Default::default(),
+            crate_feature_hook: Default::default(),
             cluster_config: None,
             cpp_checker_tests: Default::default(),
             write_dependencies_local_paths: true,
+            cpp_wrapper_items_per_file: 300,
         }
     }
 
@@ -329,6 +460,32 @@ impl Config {
         self.include_directives.push(path.into());
     }
 
+    /// Excludes the file `file_name` in the `c_lib/extra` directory of the crate template
+    /// from include directives.
+    ///
+    /// All files in this directory are included by default. Use this if a file
+    /// requires headers that are not available for the processed library.
+    pub fn add_excluded_extra_file(&mut self, file_name: impl Into<String>) {
+        self.excluded_extra_files.push(file_name.into());
+    }
+
+    /// Adds an optional feature of the crate that should be enabled
+    /// when the crate's tests are run by the `build_crate` step.
+    /// Tests of features that are not enabled by default are not run otherwise.
+    pub fn add_test_feature(&mut self, feature: impl Into<String>) {
+        self.test_features.push(feature.into());
+    }
+
+    /// Adds a test crate located in the `dir_name` directory of the crate template.
+    ///
+    /// Use this for tests that can't be placed in the generated crate itself, e.g. if they
+    /// need a custom build script. `Cargo.toml` of the test crate is generated: it depends on
+    /// the generated crate and has the same dependencies and build dependencies as
+    /// the generated crate. Tests of the test crate are run by the `build_crate` step.
+    pub fn add_test_crate(&mut self, dir_name: impl Into<String>) {
+        self.test_crates.push(dir_name.into());
+    }
+
     /// Sets `CppBuildConfig` value that will be passed to the build script
     /// of the generated crate.
     pub fn set_cpp_build_config(&mut self, cpp_build_config: CppBuildConfig) {
@@ -393,6 +550,21 @@ impl Config {
         &self.include_directives
     }
 
+    /// Returns files added by `Config::add_excluded_extra_file`.
+    pub fn excluded_extra_files(&self) -> &[String] {
+        &self.excluded_extra_files
+    }
+
+    /// Returns features added by `Config::add_test_feature`.
+    pub fn test_features(&self) -> &[String] {
+        &self.test_features
+    }
+
+    /// Returns test crates added by `Config::add_test_crate`.
+    pub fn test_crates(&self) -> &[String] {
+        &self.test_crates
+    }
+
     /// Returns current `CppBuildConfig` value.
     pub fn cpp_build_config(&self) -> &CppBuildConfig {
         &self.cpp_build_config
@@ -464,6 +636,100 @@ impl Config {
         self.rust_path_hook.as_ref().map(|b| &**b)
     }
 
+    /// Sets rules for converting C++ names to Rust names.
+    /// The hook set by `Config::set_rust_path_hook` takes precedence over the policy.
+    pub fn set_rust_naming_policy(&mut self, policy: RustNamingPolicy) {
+        self.rust_naming_policy = policy;
+    }
+
+    /// Allows to change rules for converting C++ names to Rust names.
+    pub fn rust_naming_policy_mut(&mut self) -> &mut RustNamingPolicy {
+        &mut self.rust_naming_policy
+    }
+
+    /// Returns current `RustNamingPolicy` value.
+    pub fn rust_naming_policy(&self) -> &RustNamingPolicy {
+        &self.rust_naming_policy
+    }
+
+    /// Sets path to a TOML file that maps C++ paths or function signatures
+    /// to names of the generated Rust items, for example:
+    ///
+    /// ```toml
+    /// "QPushButton::QPushButton(const QString&, QWidget*)" = "from_text_and_parent"
+    /// "QAbstractSlider::setValue" = "set"
+    /// ```
+    ///
+    /// Names from the file take precedence over `RustNamingPolicy` but not over
+    /// the hook set by `Config::set_rust_path_hook`. Generation fails if a name
+    /// from the file conflicts with another item. Entries that don't match
+    /// any C++ item are reported as warnings.
+    pub fn set_rust_rename_map_path<P: Into<PathBuf>>(&mut self, path: P) {
+        self.rust_rename_map_path = Some(path.into());
+    }
+
+    /// Returns value set by `Config::set_rust_rename_map_path`.
+    pub fn rust_rename_map_path(&self) -> Option<&PathBuf> {
+        self.rust_rename_map_path.as_ref()
+    }
+
+    /// Enables generation of argument builders for functions with default arguments.
+    ///
+    /// By default, a separate Rust function is generated for every number of
+    /// omitted default arguments (e.g. `new_0a`, `new_1a`, `new_2a`). If this option
+    /// is enabled, a single function accepting only the required arguments is generated
+    /// instead. It returns a builder that allows to set optional arguments and
+    /// calls the appropriate C++ function in its `build()` method:
+    ///
+    /// ```ignore
+    /// let button = QPushButton::new().parent(widget).build();
+    /// ```
+    ///
+    /// Setters of optional arguments can only be called in the order the arguments
+    /// appear in the C++ function. This is checked at compile time.
+    ///
+    /// Builders are only generated for constructors, static methods and free functions.
+    /// Default arguments of other methods are handled as usual.
+    pub fn set_use_arguments_builders(&mut self, value: bool) {
+        self.use_arguments_builders = value;
+    }
+
+    /// Returns value set by `Config::set_use_arguments_builders`.
+    pub fn use_arguments_builders(&self) -> bool {
+        self.use_arguments_builders
+    }
+
+    /// Enables generation of `Hash` implementations based on `std::hash` specializations.
+    ///
+    /// `Hash` is always implemented for types with a `qHash` overload. If this option
+    /// is enabled, a hash function using `std::hash` is also generated for every class,
+    /// and the C++ checker keeps it only for classes that have a specialization.
+    /// This adds a compilation check per class, so it's disabled by default.
+    pub fn set_use_std_hash_specializations(&mut self, value: bool) {
+        self.use_std_hash_specializations = value;
+    }
+
+    /// Returns value set by `Config::set_use_std_hash_specializations`.
+    pub fn use_std_hash_specializations(&self) -> bool {
+        self.use_std_hash_specializations
+    }
+
+    /// Marks the C++ enum `path` as closed, i.e. guaranteed to only have
+    /// the values present in its declaration.
+    ///
+    /// In addition to the usual wrapper struct, a native Rust `enum` is generated
+    /// for each closed enum, allowing exhaustive `match`. It can be converted
+    /// to and from the wrapper and supports `Display` and `FromStr` by variant name.
+    /// Scoped enums (`enum class`) are always considered closed.
+    pub fn add_closed_enum(&mut self, path: CppPath) {
+        self.closed_enums.push(path);
+    }
+
+    /// Returns enums added by `Config::add_closed_enum`.
+    pub fn closed_enums(&self) -> &[CppPath] {
+        &self.closed_enums
+    }
+
     pub fn set_rust_item_hook(
         &mut self,
         hook: impl Fn(&mut RustItem, &ProcessorData<'_>) -> Result<()> + 'static,
@@ -499,6 +765,32 @@ impl Config {
         self.cpp_item_filter_hook.as_ref().map(|b| &**b)
     }
 
+    /// Sets a function that assigns C++ items to optional Cargo features
+    /// of the generated crate, e.g. by namespace or by class name.
+    ///
+    /// If the hook returns `Some(name)`, all Rust items and C++ wrappers generated
+    /// from the C++ item are only compiled if the feature `name` is enabled.
+    /// Items that use types from other features also require those features.
+    /// All features are enabled by default, so users can opt out with
+    /// `default-features = false`.
+    ///
+    /// Feature names may only contain ASCII letters, digits, `_` and `-`, and must not
+    /// conflict with `default`, features added by ritual or names of dependencies.
+    pub fn set_crate_feature_hook(
+        &mut self,
+        hook: impl Fn(&CppItem) -> Result<Option<String>> + 'static,
+    ) {
+        assert!(
+            self.crate_feature_hook.is_none(),
+            "only one hook can be set"
+        );
+        self.crate_feature_hook = Some(Box::new(hook));
+    }
+
+    pub fn crate_feature_hook(&self) -> Option<&CrateFeatureHook> {
+        self.crate_feature_hook.as_ref().map(|b| &**b)
+    }
+
     pub fn set_cluster_config(&mut self, cluster_config: ClusterConfig) {
         self.cluster_config = Some(cluster_config);
     }
@@ -522,6 +814,20 @@ impl Config {
     pub fn write_dependencies_local_paths(&self) -> bool {
         self.write_dependencies_local_paths
     }
+
+    /// Sets approximate number of items in each source file of the C++ wrapper library.
+    ///
+    /// The wrapper is split into multiple files so that they can be compiled in parallel.
+    /// Items of the same C++ class are always placed in the same file, so
+    /// files may be larger than the specified value. Default value is 300.
+    pub fn set_cpp_wrapper_items_per_file(&mut self, value: usize) {
+        self.cpp_wrapper_items_per_file = value;
+    }
+
+    /// Returns value set by `Config::set_cpp_wrapper_items_per_file`.
+    pub fn cpp_wrapper_items_per_file(&self) -> usize {
+        self.cpp_wrapper_items_per_file
+    }
 }
 
 #[derive(Default)]
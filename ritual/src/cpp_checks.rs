@@ -1,4 +1,5 @@
 use itertools::Itertools;
+use log::debug;
 use ritual_common::target::{Arch, Endian, Env, Family, LibraryTarget, PointerWidth, OS};
 use serde_derive::{Deserialize, Serialize};
 
@@ -58,39 +59,80 @@ impl CppChecks {
         self.0.is_empty()
     }
 
+    /// Returns a condition that is true for all environments in which the checks
+    /// succeeded and false for all environments in which they failed.
+    ///
+    /// The condition is built as a disjunction of conjunctions of target properties.
+    /// Each conjunction is minimized by dropping the properties that are not needed
+    /// to exclude the failed environments. Successful environments that can't be
+    /// distinguished from a failed environment are excluded from the condition.
+    /// Environments from `environments` without a check result are treated as failed.
     pub fn condition(&self, environments: &[LibraryTarget]) -> Condition {
         if !self.any_success() {
             return Condition::False;
         }
-        if self.all_success(environments) {
-            return Condition::True;
-        }
-        if self
+        let failed_envs = self
             .0
             .iter()
-            .all(|item| item.env.cpp_library_version.is_some())
-        {
-            let good_lib_versions = self
-                .0
-                .iter()
-                .filter(|item| item.is_success)
-                .map(|item| item.env.cpp_library_version.clone().unwrap())
-                .collect_vec();
-            if good_lib_versions.len() == 1 {
-                let good_version = good_lib_versions.into_iter().next().unwrap();
-                return Condition::CppLibraryVersion(good_version);
-            } else {
-                return Condition::Or(
-                    good_lib_versions
-                        .into_iter()
-                        .map(Condition::CppLibraryVersion)
-                        .collect(),
+            .filter(|item| !item.is_success)
+            .map(|item| &item.env)
+            .chain(environments.iter().filter(|env| !self.has_env(env)))
+            .collect_vec();
+        if failed_envs.is_empty() {
+            return Condition::True;
+        }
+
+        let mut terms: Vec<Vec<Condition>> = Vec::new();
+        for env in self.successful_envs() {
+            let mut term = env_properties(env);
+            if failed_envs.iter().any(|env| eval_all(&term, env)) {
+                // A failed environment can't be told apart from this one
+                // (e.g. a flaky check), so the item is not enabled for it.
+                debug!(
+                    "excluding environment that matches a failed or unchecked one: {:?}",
+                    env
                 );
+                continue;
+            }
+            // Platform properties are dropped first, so the library version
+            // is preferred when it's enough to tell the environments apart.
+            let mut index = 0;
+            while index < term.len() {
+                let mut reduced = term.clone();
+                reduced.remove(index);
+                if failed_envs.iter().all(|env| !eval_all(&reduced, env)) {
+                    term = reduced;
+                } else {
+                    index += 1;
+                }
+            }
+            if !terms.contains(&term) {
+                terms.push(term);
             }
         }
 
-        // TODO: implement more sensible conditions generation
-        Condition::True
+        // Remove terms that are already covered by a more general term.
+        let terms = terms
+            .iter()
+            .filter(|term| {
+                !terms
+                    .iter()
+                    .any(|other| other.len() < term.len() && other.iter().all(|c| term.contains(c)))
+            })
+            .map(|term| {
+                if term.len() == 1 {
+                    term[0].clone()
+                } else {
+                    Condition::And(term.clone())
+                }
+            })
+            .collect_vec();
+
+        match terms.len() {
+            0 => Condition::False,
+            1 => terms.into_iter().next().unwrap(),
+            _ => Condition::Or(terms),
+        }
     }
 
     pub fn is_always_success_for(&self, other: &CppChecks) -> bool {
@@ -118,3 +160,47 @@ pub enum Condition {
     True,
     False,
 }
+
+impl Condition {
+    /// Evaluate the condition for `env`. Returns true if
+    /// `env` matches the condition.
+    pub fn eval(&self, env: &LibraryTarget) -> bool {
+        match self {
+            Condition::CppLibraryVersion(version) => {
+                env.cpp_library_version.as_ref() == Some(version)
+            }
+            Condition::Arch(arch) => &env.target.arch == arch,
+            Condition::OS(os) => &env.target.os == os,
+            Condition::Family(family) => &env.target.family == family,
+            Condition::Env(target_env) => &env.target.env == target_env,
+            Condition::PointerWidth(pointer_width) => &env.target.pointer_width == pointer_width,
+            Condition::Endian(endian) => &env.target.endian == endian,
+            Condition::And(conditions) => conditions.iter().all(|c| c.eval(env)),
+            Condition::Or(conditions) => conditions.iter().any(|c| c.eval(env)),
+            Condition::Not(condition) => !condition.eval(env),
+            Condition::True => true,
+            Condition::False => false,
+        }
+    }
+}
+
+/// Returns simple conditions describing all properties of `env`,
+/// ordered from the least to the most preferred in generated conditions.
+fn env_properties(env: &LibraryTarget) -> Vec<Condition> {
+    let mut properties = vec![
+        Condition::Endian(env.target.endian),
+        Condition::PointerWidth(env.target.pointer_width),
+        Condition::Env(env.target.env),
+        Condition::Arch(env.target.arch),
+        Condition::OS(env.target.os),
+        Condition::Family(env.target.family),
+    ];
+    if let Some(version) = &env.cpp_library_version {
+        properties.push(Condition::CppLibraryVersion(version.clone()));
+    }
+    properties
+}
+
+fn eval_all(conditions: &[Condition], env: &LibraryTarget) -> bool {
+    conditions.iter().all(|c| c.eval(env))
+}
@@ -1,5 +1,6 @@
 use crate::config::Config;
 use crate::cpp_checks::Condition;
+use crate::cpp_data::{CppItem, CppPath};
 use crate::cpp_ffi_data::{
     CppFfiArgumentMeaning, CppFfiFunctionKind, CppFfiType, CppFieldAccessorType,
     CppToFfiTypeConversion, QtSignalWrapper, QtSlotWrapper,
@@ -8,14 +9,16 @@ use crate::cpp_ffi_data::{CppFfiFunction, CppFfiItem};
 use crate::cpp_function::{CppFunction, ReturnValueAllocationPlace};
 use crate::cpp_type::CppPointerLikeTypeKind;
 use crate::cpp_type::CppType;
+use crate::crate_features::CrateFeatures;
 use crate::database::{DatabaseClient, DbItem};
 use crate::rust_info::{RustItem, RustStructKind};
 use itertools::Itertools;
 use ritual_common::cpp_lib_builder::version_to_number;
 use ritual_common::errors::{bail, err_msg, format_err, Result};
 use ritual_common::file_utils::{create_file, os_str_to_str, path_to_str, read_dir};
+use ritual_common::target::{Arch, Endian, Env, Family, PointerWidth, OS};
 use ritual_common::utils::MapIfOk;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::io::Write;
 use std::iter::once;
 use std::path::{Path, PathBuf};
@@ -110,6 +113,7 @@ impl Generator<'_> {
             ),
             CppToFfiTypeConversion::ReferenceToPointer => format!("&{}", expression),
             CppToFfiTypeConversion::QFlagsToInt => format!("int({})", expression),
+            CppToFfiTypeConversion::UniquePtrToPointer => format!("{}.release()", expression),
         })
     }
 
@@ -161,6 +165,9 @@ impl Generator<'_> {
             CppToFfiTypeConversion::QFlagsToInt => {
                 result = format!("int({})", result);
             }
+            CppToFfiTypeConversion::UniquePtrToPointer => {
+                result = format!("{}.release()", result);
+            }
         }
 
         if method.allocation_place == ReturnValueAllocationPlace::Stack && !is_constructor {
@@ -189,6 +196,14 @@ impl Generator<'_> {
                     | CppToFfiTypeConversion::ReferenceToPointer => result = format!("*{}", result),
                     CppToFfiTypeConversion::NoChange
                     | CppToFfiTypeConversion::ImplicitCast { .. } => {}
+                    CppToFfiTypeConversion::UniquePtrToPointer => {
+                        // the C++ function takes ownership of the object
+                        result = format!(
+                            "{}({})",
+                            argument.argument_type.original_type().to_cpp_code(None)?,
+                            result
+                        );
+                    }
                     CppToFfiTypeConversion::QFlagsToInt => {
                         let type_text = if let CppType::PointerLike {
                             kind,
@@ -225,6 +240,18 @@ impl Generator<'_> {
             .map_or(false, |f| f.is_destructor());
 
         let method = item.item;
+        if let CppFfiFunctionKind::StreamToString = &method.kind {
+            return Ok(format!(
+                "ritual::to_string({})",
+                self.arguments_values(method)?
+            ));
+        }
+        if let CppFfiFunctionKind::StdHash = &method.kind {
+            return Ok(format!(
+                "ritual::std_hash({})",
+                self.arguments_values(method)?
+            ));
+        }
         let result = if is_destructor {
             if let Some(arg) = method
                 .arguments
@@ -351,12 +378,53 @@ impl Generator<'_> {
                 let value = version_to_number(version).expect("version_to_number failed");
                 format!("RITUAL_CPP_LIB_VERSION == {}", value)
             }
-            Condition::Arch(_) => unimplemented!(),
-            Condition::OS(_) => unimplemented!(),
-            Condition::Family(_) => unimplemented!(),
-            Condition::Env(_) => unimplemented!(),
-            Condition::PointerWidth(_) => unimplemented!(),
-            Condition::Endian(_) => unimplemented!(),
+            Condition::Arch(arch) => match arch {
+                Arch::X86 => "defined(__i386__) || defined(_M_IX86)",
+                Arch::X86_64 => "defined(__x86_64__) || defined(_M_X64)",
+                Arch::Mips => "defined(__mips__)",
+                Arch::PowerPC => "defined(__powerpc__) && !defined(__powerpc64__)",
+                Arch::PowerPC64 => "defined(__powerpc64__)",
+                Arch::Arm => "defined(__arm__) || defined(_M_ARM)",
+                Arch::AArch64 => "defined(__aarch64__) || defined(_M_ARM64)",
+            }
+            .to_string(),
+            Condition::OS(os) => match os {
+                OS::Windows => "defined(_WIN32)",
+                OS::MacOS => {
+                    "defined(__APPLE__) && !(defined(TARGET_OS_IPHONE) && TARGET_OS_IPHONE)"
+                }
+                OS::IOS => "defined(__APPLE__) && defined(TARGET_OS_IPHONE) && TARGET_OS_IPHONE",
+                OS::Linux => "defined(__linux__) && !defined(__ANDROID__)",
+                OS::Android => "defined(__ANDROID__)",
+                OS::FreeBSD => "defined(__FreeBSD__)",
+                OS::DragonFly => "defined(__DragonFly__)",
+                OS::Bitrig => "defined(__Bitrig__)",
+                OS::OpenBSD => "defined(__OpenBSD__)",
+                OS::NetBSD => "defined(__NetBSD__)",
+            }
+            .to_string(),
+            Condition::Family(family) => match family {
+                Family::Windows => "defined(_WIN32)",
+                Family::Unix => "defined(__unix__) || defined(__APPLE__)",
+            }
+            .to_string(),
+            Condition::Env(env) => match env {
+                Env::Gnu => "defined(__GLIBC__) || defined(__MINGW32__)",
+                Env::Msvc => "defined(_MSC_VER)",
+                Env::Musl => "defined(__linux__) && !defined(__GLIBC__) && !defined(__ANDROID__)",
+                Env::None => "!defined(__linux__) && !defined(__MINGW32__) && !defined(_MSC_VER)",
+            }
+            .to_string(),
+            Condition::PointerWidth(pointer_width) => match pointer_width {
+                PointerWidth::P64 => "defined(_WIN64) || __SIZEOF_POINTER__ == 8",
+                PointerWidth::P32 => "!defined(_WIN64) && __SIZEOF_POINTER__ != 8",
+            }
+            .to_string(),
+            Condition::Endian(endian) => match endian {
+                Endian::Little => "defined(_MSC_VER) || __BYTE_ORDER__ == __ORDER_LITTLE_ENDIAN__",
+                Endian::Big => "!defined(_MSC_VER) && __BYTE_ORDER__ == __ORDER_BIG_ENDIAN__",
+            }
+            .to_string(),
             Condition::And(conditions) => conditions
                 .iter()
                 .map(|c| format!("({})", self.condition_expression(c)))
@@ -382,11 +450,46 @@ impl Generator<'_> {
         )
     }
 
-    /// Generates a source file with the specified FFI methods.
-    fn generate_cpp_file(&self, file_path: &Path, global_header_name: &str) -> Result<()> {
-        let mut cpp_file = create_file(file_path)?;
-        writeln!(cpp_file, "#include \"{}\"", global_header_name)?;
+    /// Returns the name of the C++ class the FFI item belongs to,
+    /// or the name of the C++ item itself if it's not a class member.
+    fn source_file_group(&self, ffi_item: &DbItem<&CppFfiItem>) -> Result<String> {
+        let cpp_item = if let Some(item) = self.0.original_cpp_item(&ffi_item.id)? {
+            item.item
+        } else {
+            return Ok(String::new());
+        };
+        let path = match cpp_item {
+            CppItem::Function(function) if function.member.is_some() => function.class_path()?,
+            CppItem::ClassField(field) => field.path.parent()?,
+            _ => match cpp_item.path() {
+                Some(path) => path.clone(),
+                None => return Ok(String::new()),
+            },
+        };
+        Ok(path.to_cpp_pseudo_code())
+    }
 
+    /// Generates source files with FFI items belonging to `feature`
+    /// (or items without a feature if `feature` is `None`).
+    ///
+    /// Items of the same C++ class are always placed in the same file.
+    /// A new file is started when the current file contains
+    /// at least `items_per_file` items. File names are `{name_prefix}{index}.cpp`,
+    /// where `index` starts from 1. At least one file is always created.
+    /// Each file only includes headers declaring its items and the types they use.
+    /// If some of these headers are unknown, `include_directives` are used instead.
+    /// Returns names of the created files.
+    #[allow(clippy::too_many_arguments)]
+    fn generate_cpp_files(
+        &self,
+        dir: &Path,
+        name_prefix: &str,
+        global_header_name: &str,
+        include_directives: &[PathBuf],
+        features: &CrateFeatures<'_>,
+        feature: Option<&str>,
+        items_per_file: usize,
+    ) -> Result<Vec<String>> {
         let used_ffi_functions = self
             .0
             .rust_items()
@@ -395,17 +498,157 @@ impl Generator<'_> {
             .map(|item| item.path.last())
             .collect::<HashSet<&str>>();
 
-        let ffi_items = self
-            .0
-            .ffi_items()
-            .filter(|item| {
-                !item.item.is_function()
-                    || used_ffi_functions.contains(item.item.path().last().name.as_str())
-            })
-            .collect_vec();
+        let mut groups = BTreeMap::<String, Vec<_>>::new();
+        for item in self.0.ffi_items() {
+            if item.item.is_function()
+                && !used_ffi_functions.contains(item.item.path().last().name.as_str())
+            {
+                continue;
+            }
+            if features.item_feature(&item.id)?.as_deref() == feature {
+                groups
+                    .entry(self.source_file_group(&item)?)
+                    .or_default()
+                    .push(item);
+            }
+        }
+
+        let mut files = vec![Vec::new()];
+        for (_, group) in groups {
+            let current_file = files.last_mut().expect("files can't be empty");
+            if current_file.len() >= items_per_file.max(1) {
+                files.push(group);
+            } else {
+                current_file.extend(group);
+            }
+        }
+
+        let type_headers = self.type_headers()?;
+        let mut file_names = Vec::new();
+        for (index, ffi_items) in files.iter().enumerate() {
+            let file_name = format!("{}{}.cpp", name_prefix, index + 1);
+            let headers = self.required_headers(ffi_items, &type_headers)?;
+            let headers = match &headers {
+                Some(headers) => headers.iter().map(PathBuf::from).collect(),
+                None => include_directives.to_vec(),
+            };
+            self.generate_cpp_file(
+                &dir.join(&file_name),
+                global_header_name,
+                &headers,
+                ffi_items,
+            )?;
+            file_names.push(file_name);
+        }
+        Ok(file_names)
+    }
+
+    /// Returns headers declaring C++ types, indexed by paths of the types
+    /// without template arguments.
+    fn type_headers(&self) -> Result<HashMap<String, &str>> {
+        let mut headers = HashMap::new();
+        for item in self.0.all_cpp_items() {
+            if let Some(type_declaration) = item.item.as_type_ref() {
+                if let Some(header) = self.0.cpp_header(&item.id)? {
+                    headers.insert(
+                        type_declaration.path.to_templateless_string(),
+                        header.include_path.as_str(),
+                    );
+                }
+            }
+        }
+        Ok(headers)
+    }
+
+    /// Returns headers required by the code generated for `ffi_items`: headers declaring
+    /// the original C++ items and headers declaring all types used by them.
+    /// Returns `None` if any of the headers is unknown.
+    fn required_headers(
+        &self,
+        ffi_items: &[DbItem<&CppFfiItem>],
+        type_headers: &HashMap<String, &str>,
+    ) -> Result<Option<BTreeSet<String>>> {
+        let mut headers = BTreeSet::new();
+        let mut types = Vec::new();
+        for ffi_item in ffi_items {
+            if !self.0.cpp_checks(&ffi_item.id)?.any_success() {
+                continue;
+            }
+            match &ffi_item.item {
+                CppFfiItem::Function(function) => {
+                    // `std::hash` specializations are not necessarily declared
+                    // in the same header as the class.
+                    if function.kind == CppFfiFunctionKind::StdHash {
+                        return Ok(None);
+                    }
+                    let original_item = match self.0.original_cpp_item(&ffi_item.id)? {
+                        Some(item) => item,
+                        None => return Ok(None),
+                    };
+                    match self.0.cpp_header(&original_item.id)? {
+                        // Extra files are written against the whole library,
+                        // so they need all include directives.
+                        Some(header) if !header.include_path.starts_with("extra/") => {
+                            headers.insert(header.include_path.clone());
+                        }
+                        _ => return Ok(None),
+                    }
+                    if let Some(source_item) = self.0.source_cpp_item(&ffi_item.id)? {
+                        types.extend(source_item.item.all_involved_types());
+                    }
+                }
+                CppFfiItem::QtSlotWrapper(QtSlotWrapper {
+                    signal_arguments, ..
+                })
+                | CppFfiItem::QtSignalWrapper(QtSignalWrapper {
+                    signal_arguments, ..
+                }) => {
+                    types.push(CppType::Class(CppPath::from_good_str("QObject")));
+                    types.extend(signal_arguments.iter().cloned());
+                }
+            }
+        }
+
+        while let Some(cpp_type) = types.pop() {
+            match cpp_type {
+                CppType::Class(path) | CppType::Enum { path } => {
+                    match type_headers.get(&path.to_templateless_string()) {
+                        Some(header) => {
+                            headers.insert(header.to_string());
+                        }
+                        None => return Ok(None),
+                    }
+                    for item in path.items() {
+                        if let Some(args) = &item.template_arguments {
+                            types.extend(args.iter().cloned());
+                        }
+                    }
+                }
+                CppType::PointerLike { target, .. } => types.push(*target),
+                CppType::FunctionPointer(function) => {
+                    types.push(*function.return_type);
+                    types.extend(function.arguments);
+                }
+                _ => {}
+            }
+        }
+        Ok(Some(headers))
+    }
+
+    /// Generates a source file with the specified FFI items.
+    fn generate_cpp_file(
+        &self,
+        file_path: &Path,
+        global_header_name: &str,
+        include_directives: &[PathBuf],
+        ffi_items: &[DbItem<&CppFfiItem>],
+    ) -> Result<()> {
+        let mut cpp_file = create_file(file_path)?;
+        writeln!(cpp_file, "#include \"{}\"", global_header_name)?;
+        write_include_directives(&mut cpp_file, include_directives)?;
 
         let mut needs_moc = false;
-        for ffi_item in &ffi_items {
+        for ffi_item in ffi_items {
             match &ffi_item.item {
                 CppFfiItem::QtSlotWrapper(qt_slot_wrapper) => {
                     let checks = self.0.cpp_checks(&ffi_item.id)?;
@@ -432,7 +675,7 @@ impl Generator<'_> {
         }
 
         writeln!(cpp_file, "extern \"C\" {{")?;
-        for ffi_item in &ffi_items {
+        for ffi_item in ffi_items {
             if let Some(item) = ffi_item.clone().filter_map(|item| item.as_function_ref()) {
                 let checks = self.0.cpp_checks(&ffi_item.id)?;
                 if !checks.any_success() {
@@ -509,12 +752,26 @@ pub fn qt_signal_wrapper(db: &DatabaseClient, wrapper: &QtSignalWrapper) -> Resu
     Generator(db).qt_signal_wrapper(wrapper)
 }
 
-pub fn generate_cpp_file(
+#[allow(clippy::too_many_arguments)]
+pub fn generate_cpp_files(
     db: &DatabaseClient,
-    file_path: &Path,
+    dir: &Path,
+    name_prefix: &str,
     global_header_name: &str,
-) -> Result<()> {
-    Generator(db).generate_cpp_file(file_path, global_header_name)
+    include_directives: &[PathBuf],
+    features: &CrateFeatures<'_>,
+    feature: Option<&str>,
+    items_per_file: usize,
+) -> Result<Vec<String>> {
+    Generator(db).generate_cpp_files(
+        dir,
+        name_prefix,
+        global_header_name,
+        include_directives,
+        features,
+        feature,
+        items_per_file,
+    )
 }
 
 pub fn generate_cpp_type_size_requester(
@@ -532,10 +789,16 @@ pub fn all_include_directives(config: &Config) -> Result<Vec<PathBuf>> {
         let extra_template = crate_template_path.join("c_lib/extra");
         if extra_template.exists() {
             for item in read_dir(&extra_template)? {
-                all_include_directives.push(PathBuf::from(format!(
-                    "extra/{}",
-                    os_str_to_str(&item?.file_name())?
-                )));
+                let file_name = item?.file_name();
+                let file_name = os_str_to_str(&file_name)?;
+                if config
+                    .excluded_extra_files()
+                    .iter()
+                    .any(|excluded| excluded == file_name)
+                {
+                    continue;
+                }
+                all_include_directives.push(PathBuf::from(format!("extra/{}", file_name)));
             }
         }
     }
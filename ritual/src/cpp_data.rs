@@ -369,7 +369,10 @@ impl fmt::Debug for CppPathItem {
 /// Information about a C++ type declaration
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Hash)]
 pub enum CppTypeDeclarationKind {
-    Enum,
+    Enum {
+        /// True for `enum class` and `enum struct` declarations.
+        is_scoped: bool,
+    },
     Class,
 }
 
@@ -398,7 +401,7 @@ impl CppTypeDeclarationKind {
 
     pub fn is_enum(&self) -> bool {
         match self {
-            CppTypeDeclarationKind::Enum => true,
+            CppTypeDeclarationKind::Enum { .. } => true,
             _ => false,
         }
     }
@@ -485,7 +488,7 @@ impl CppItem {
     pub fn all_involved_types(&self) -> Vec<CppType> {
         match self {
             CppItem::Type(t) => match t.kind {
-                CppTypeDeclarationKind::Enum => vec![CppType::Enum {
+                CppTypeDeclarationKind::Enum { .. } => vec![CppType::Enum {
                     path: t.path.clone(),
                 }],
                 CppTypeDeclarationKind::Class { .. } => vec![CppType::Class(t.path.clone())],
@@ -579,7 +582,9 @@ impl fmt::Display for CppItem {
                 format!("namespace {}", namespace.path.to_cpp_pseudo_code())
             }
             CppItem::Type(type1) => match type1.kind {
-                CppTypeDeclarationKind::Enum => format!("enum {}", type1.path.to_cpp_pseudo_code()),
+                CppTypeDeclarationKind::Enum { .. } => {
+                    format!("enum {}", type1.path.to_cpp_pseudo_code())
+                }
                 CppTypeDeclarationKind::Class { .. } => {
                     format!("class {}", type1.path.to_cpp_pseudo_code())
                 }
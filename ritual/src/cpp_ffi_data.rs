@@ -4,7 +4,7 @@ use crate::cpp_function::ReturnValueAllocationPlace;
 use crate::cpp_type::{CppBuiltInNumericType, CppFunctionPointerType, CppType};
 use crate::database::DatabaseClient;
 use itertools::Itertools;
-use ritual_common::errors::{bail, Result};
+use ritual_common::errors::{bail, err_msg, Result};
 use serde_derive::{Deserialize, Serialize};
 
 /// Variation of a field accessor method
@@ -69,6 +69,13 @@ pub enum CppFfiFunctionKind {
         /// Type of the accessor
         accessor_type: CppFieldAccessorType,
     },
+    /// This is a non-existing function that formats an object
+    /// using its C++ output stream operator (`operator<<(std::ostream&, const T&)`)
+    /// and returns the resulting string.
+    StreamToString,
+    /// This is a non-existing function that hashes an object
+    /// using its `std::hash` specialization.
+    StdHash,
 }
 
 /// Relation between original C++ method's argument value
@@ -88,6 +95,9 @@ pub enum CppToFfiTypeConversion {
     QFlagsToInt,
     /// Implicit conversion is used.
     ImplicitCast { ffi_type: CppType },
+    /// C++ argument is `std::unique_ptr<T>`
+    /// and FFI argument is an owning pointer (`T*`)
+    UniquePtrToPointer,
 }
 
 /// Information that indicates how an FFI function argument
@@ -198,6 +208,20 @@ impl CppFfiFunction {
                     false
                 }
             }
+            CppFfiFunctionKind::StreamToString => {
+                if let CppFfiFunctionKind::StreamToString = &other.kind {
+                    true
+                } else {
+                    false
+                }
+            }
+            CppFfiFunctionKind::StdHash => {
+                if let CppFfiFunctionKind::StdHash = &other.kind {
+                    true
+                } else {
+                    false
+                }
+            }
         }
     }
 }
@@ -241,6 +265,23 @@ impl CppFfiType {
                 original_type,
                 conversion,
             }),
+            CppToFfiTypeConversion::UniquePtrToPointer => {
+                let target = if let CppType::Class(path) = &original_type {
+                    path.last()
+                        .template_arguments
+                        .as_ref()
+                        .and_then(|args| args.get(0))
+                        .ok_or_else(|| err_msg("unique_ptr must have template arguments"))?
+                        .clone()
+                } else {
+                    bail!("UniquePtrToPointer requires a class type");
+                };
+                Ok(CppFfiType {
+                    ffi_type: CppType::new_pointer(false, target),
+                    original_type,
+                    conversion,
+                })
+            }
             CppToFfiTypeConversion::ImplicitCast { ffi_type } => Ok(CppFfiType {
                 ffi_type,
                 original_type,
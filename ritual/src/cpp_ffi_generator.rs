@@ -9,10 +9,11 @@ use crate::cpp_ffi_data::{CppFfiFunction, CppFfiFunctionKind, CppFieldAccessorTy
 use crate::cpp_ffi_data::{CppFfiFunctionArgument, CppFfiItem};
 use crate::cpp_function::ReturnValueAllocationPlace;
 use crate::cpp_function::{CppFunction, CppFunctionArgument, CppFunctionKind};
+use crate::cpp_type::CppBuiltInNumericType;
 use crate::cpp_type::CppPointerLikeTypeKind;
 use crate::cpp_type::CppType;
 use crate::cpp_type::CppTypeRole;
-use crate::cpp_type::{is_qflags, CppFunctionPointerType};
+use crate::cpp_type::{is_qflags, is_unique_ptr, CppFunctionPointerType};
 use crate::database::DbItem;
 use crate::processor::ProcessorData;
 use itertools::Itertools;
@@ -65,6 +66,8 @@ pub fn ffi_type(original_type: &CppType, role: CppTypeRole) -> Result<CppFfiType
             CppType::Class(path) => {
                 if is_qflags(&path) {
                     CppToFfiTypeConversion::QFlagsToInt
+                } else if is_unique_ptr(&path) {
+                    CppToFfiTypeConversion::UniquePtrToPointer
                 } else {
                     CppToFfiTypeConversion::ValueToPointer {
                         is_ffi_const: role != CppTypeRole::ReturnType,
@@ -170,6 +173,13 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
                 generate_field_accessors(field, &movable_types, &mut name_provider)
                     .map(|v| v.into_iter().collect_vec())
             }
+            CppItem::Type(type_declaration)
+                if type_declaration.kind.is_class()
+                    && data.config.use_std_hash_specializations() =>
+            {
+                std_hash_function(&type_declaration.path, &mut name_provider)
+                    .map(|function| vec![CppFfiItem::Function(function)])
+            }
             CppItem::ClassBase(_)
             | CppItem::Type(_)
             | CppItem::EnumValue(_)
@@ -197,12 +207,21 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
     Ok(())
 }
 
-fn generate_ffi_methods_for_method(
+pub fn generate_ffi_methods_for_method(
     method: &CppFunction,
     movable_types: &[CppPath],
     name_provider: &mut FfiNameProvider,
 ) -> Result<Vec<CppFfiItem>> {
     let mut methods = Vec::new();
+    if method.is_stream_output_operator() {
+        // The stream operator itself is not usable from Rust,
+        // so only the string conversion helper is generated.
+        methods.push(CppFfiItem::Function(stream_to_string_function(
+            method,
+            name_provider,
+        )?));
+        return Ok(methods);
+    }
     methods.push(CppFfiItem::Function(to_ffi_method(
         NewFfiFunctionKind::Function {
             cpp_function: method.clone(),
@@ -214,6 +233,62 @@ fn generate_ffi_methods_for_method(
     Ok(methods)
 }
 
+/// Creates a function that formats the value passed to
+/// the stream output operator `method` and returns the resulting string.
+fn stream_to_string_function(
+    method: &CppFunction,
+    name_provider: &mut FfiNameProvider,
+) -> Result<CppFfiFunction> {
+    let value_arg = &method.arguments[1];
+    let class_path = match value_arg.argument_type.pointer_like_to_target()? {
+        CppType::Class(path) => path,
+        _ => bail!("stream output operator must receive a class value"),
+    };
+    Ok(CppFfiFunction {
+        arguments: vec![CppFfiFunctionArgument {
+            name: "value".to_string(),
+            argument_type: ffi_type(&value_arg.argument_type, CppTypeRole::NotReturnType)?,
+            meaning: CppFfiArgumentMeaning::Argument(1),
+        }],
+        return_type: ffi_type(
+            &CppType::new_pointer(true, CppType::BuiltInNumeric(CppBuiltInNumericType::Char)),
+            CppTypeRole::ReturnType,
+        )?,
+        path: name_provider.create_path(&format!("{}_to_string", class_path.ascii_caption())),
+        allocation_place: ReturnValueAllocationPlace::NotApplicable,
+        kind: CppFfiFunctionKind::StreamToString,
+    })
+}
+
+/// Creates a function that hashes the value of class `class_path`
+/// using its `std::hash` specialization.
+///
+/// The function is created for every class, and the checker removes it if
+/// the class doesn't have a specialization.
+fn std_hash_function(
+    class_path: &CppPath,
+    name_provider: &mut FfiNameProvider,
+) -> Result<CppFfiFunction> {
+    let value_type = CppType::new_reference(true, CppType::Class(class_path.clone()));
+    Ok(CppFfiFunction {
+        arguments: vec![CppFfiFunctionArgument {
+            name: "value".to_string(),
+            argument_type: ffi_type(&value_type, CppTypeRole::NotReturnType)?,
+            meaning: CppFfiArgumentMeaning::Argument(0),
+        }],
+        return_type: ffi_type(
+            &CppType::PointerSizedInteger {
+                path: CppPath::from_good_str("size_t"),
+                is_signed: false,
+            },
+            CppTypeRole::ReturnType,
+        )?,
+        path: name_provider.create_path(&format!("{}_std_hash", class_path.ascii_caption())),
+        allocation_place: ReturnValueAllocationPlace::NotApplicable,
+        kind: CppFfiFunctionKind::StdHash,
+    })
+}
+
 pub enum NewFfiFunctionKind {
     Function {
         cpp_function: CppFunction,
@@ -344,7 +419,11 @@ pub fn to_ffi_method(
     let real_return_type = match &kind {
         NewFfiFunctionKind::Function { cpp_function, .. } => match &cpp_function.member {
             Some(info) if info.kind.is_constructor() => {
-                CppType::Class(cpp_function.class_path().unwrap())
+                let class_path = cpp_function.class_path().unwrap();
+                if is_unique_ptr(&class_path) {
+                    bail!("unique_ptr constructors are not supported");
+                }
+                CppType::Class(class_path)
             }
             _ => cpp_function.return_type.clone(),
         },
@@ -364,8 +443,8 @@ pub fn to_ffi_method(
     };
     let real_return_type_ffi = ffi_type(&real_return_type, CppTypeRole::ReturnType)?;
     match &real_return_type {
-        // QFlags is converted to uint in FFI
-        CppType::Class(path) if !is_qflags(path) => {
+        // QFlags is converted to uint and unique_ptr is converted to a pointer in FFI
+        CppType::Class(path) if !is_qflags(path) && !is_unique_ptr(path) => {
             if movable_types.iter().any(|t| t == path) {
                 r.arguments.push(CppFfiFunctionArgument {
                     name: "output".to_string(),
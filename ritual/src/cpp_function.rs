@@ -3,7 +3,7 @@
 use crate::cpp_data::{CppPath, CppPathItem, CppVisibility};
 use crate::cpp_ffi_data::CppCast;
 pub use crate::cpp_operator::{CppOperator, CppOperatorInfo};
-use crate::cpp_type::{CppPointerLikeTypeKind, CppType};
+use crate::cpp_type::{is_shared_ptr, CppPointerLikeTypeKind, CppSpecificNumericTypeKind, CppType};
 use crate::rust_info::RustQtReceiverType;
 use itertools::Itertools;
 use ritual_common::errors::{bail, err_msg, Result, ResultExt};
@@ -437,6 +437,94 @@ impl CppFunction {
         self.operator.is_some()
     }
 
+    /// Returns true if this method is declared with the `explicit` specifier
+    /// (applicable to constructors and conversion operators).
+    pub fn is_explicit(&self) -> bool {
+        self.declaration_code.as_ref().map_or(false, |code| {
+            code.split(|c: char| !c.is_alphanumeric() && c != '_')
+                .any(|word| word == "explicit")
+        })
+    }
+
+    /// Returns true if this is a free function that writes a class value
+    /// to a standard output stream:
+    /// `std::ostream& operator<<(std::ostream&, const T&)`.
+    pub fn is_stream_output_operator(&self) -> bool {
+        if self.member.is_some()
+            || self.operator != Some(CppOperator::BitwiseLeftShift)
+            || self.arguments.len() != 2
+        {
+            return false;
+        }
+        let is_stream = match &self.arguments[0].argument_type {
+            CppType::PointerLike {
+                kind: CppPointerLikeTypeKind::Reference,
+                is_const: false,
+                target,
+            } => match &**target {
+                CppType::Class(path) => {
+                    let last_name = &path.last().name;
+                    path.items()[0].name == "std"
+                        && (last_name == "ostream" || last_name == "basic_ostream")
+                }
+                _ => false,
+            },
+            _ => false,
+        };
+        let is_value = match &self.arguments[1].argument_type {
+            CppType::PointerLike {
+                kind: CppPointerLikeTypeKind::Reference,
+                is_const: true,
+                target,
+            } => target.is_class(),
+            _ => false,
+        };
+        is_stream && is_value
+    }
+
+    /// Returns true if this is a const member function that provides
+    /// indexed access to the elements of a container: `T at(integer) const`.
+    ///
+    /// `operator[]` is not used even if the container doesn't have `at()`, so that
+    /// the `At` trait implementation doesn't depend on the order of functions
+    /// when the container has both.
+    pub fn is_indexed_access(&self) -> bool {
+        let is_const_member = match &self.member {
+            Some(member) => member.is_const && !member.is_static,
+            None => false,
+        };
+        if !is_const_member || self.arguments.len() != 1 || self.operator.is_some() {
+            return false;
+        }
+        let last = self.path.last();
+        if last.name != "at" || last.template_arguments.is_some() {
+            return false;
+        }
+        match &self.arguments[0].argument_type {
+            CppType::BuiltInNumeric(t) => t.is_signed_integer() || t.is_unsigned_integer(),
+            CppType::SpecificNumeric(t) => t.kind != CppSpecificNumericTypeKind::FloatingPoint,
+            _ => false,
+        }
+    }
+
+    /// Returns the iterator class of a container if this is its `begin()` or `end()`
+    /// member function returning the iterator by value.
+    pub fn container_iterator_type(&self) -> Option<&CppPath> {
+        let member = self.member.as_ref()?;
+        let last = self.path.last();
+        if member.is_static
+            || !self.arguments.is_empty()
+            || last.template_arguments.is_some()
+            || (last.name != "begin" && last.name != "end")
+        {
+            return None;
+        }
+        match &self.return_type {
+            CppType::Class(path) => Some(path),
+            _ => None,
+        }
+    }
+
     /// Returns collection of all types found in the signature of this method,
     /// including argument types, return type and type of `this` implicit parameter.
     pub fn all_involved_types(&self) -> Vec<CppType> {
@@ -12,7 +12,7 @@ use crate::cpp_type::{
     CppBuiltInNumericType, CppFunctionPointerType, CppPointerLikeTypeKind, CppSpecificNumericType,
     CppSpecificNumericTypeKind, CppTemplateParameter, CppType,
 };
-use crate::database::ItemId;
+use crate::database::{CppHeaderItem, ItemId};
 use crate::processor::ProcessorData;
 use clang::diagnostic::{Diagnostic, Severity};
 use clang::*;
@@ -407,6 +407,17 @@ impl CppParser<'_, '_> {
         item: CppItem,
     ) -> Result<()> {
         if let Some(id) = self.data.add_cpp_item(self.source_id.clone(), item)? {
+            // Headers of items parsed from the generated code are
+            // determined by their original items.
+            if self.source_id.is_none() {
+                if let Some(include_path) =
+                    self.include_directive_path(&origin_location.include_file_path)?
+                {
+                    self.data
+                        .db
+                        .add_cpp_header_item(id.clone(), CppHeaderItem { include_path });
+                }
+            }
             self.output.0.push(CppParserOutputItem {
                 include_file,
                 origin_location,
@@ -1271,11 +1282,22 @@ impl CppParser<'_, '_> {
             )
         })?;
         let enum_name = get_path(entity)?;
+        let is_scoped = entity.get_range().map_or(false, |range| {
+            let spellings = range
+                .tokenize()
+                .iter()
+                .take(2)
+                .map(|token| token.get_spelling())
+                .collect::<Vec<_>>();
+            spellings.len() == 2
+                && spellings[0] == "enum"
+                && (spellings[1] == "class" || spellings[1] == "struct")
+        });
         self.add_output(
             include_file.clone(),
             get_origin_location(entity)?,
             CppItem::Type(CppTypeDeclaration {
-                kind: CppTypeDeclarationKind::Enum,
+                kind: CppTypeDeclarationKind::Enum { is_scoped },
                 path: enum_name.clone(),
             }),
         )?;
@@ -1457,6 +1479,36 @@ impl CppParser<'_, '_> {
         Ok(os_str_to_str(file_name)?.to_string())
     }
 
+    /// Determines path of the include file `file_path` relative to the target include path
+    /// it belongs to, so that it can be used in an `#include` directive of the generated code.
+    /// Returns `None` if the file is not in a target include path.
+    fn include_directive_path(&self, file_path: &str) -> Result<Option<String>> {
+        let file_path = match canonicalize(file_path) {
+            Ok(path) => path,
+            Err(_) => return Ok(None),
+        };
+        let extra_path = canonicalize(self.data.workspace.tmp_path())?.join("extra");
+        if let Ok(relative_path) = file_path.strip_prefix(&extra_path) {
+            return Ok(Some(format!(
+                "extra/{}",
+                path_to_str(relative_path)?.replace('\\', "/")
+            )));
+        }
+        for target_path in &self.current_target_paths {
+            if let Ok(relative_path) = file_path.strip_prefix(target_path) {
+                if relative_path.as_os_str().is_empty() {
+                    // The target path is the include file itself.
+                    let file_name = file_path
+                        .file_name()
+                        .ok_or_else(|| err_msg("no file name in file path"))?;
+                    return Ok(Some(os_str_to_str(file_name)?.to_string()));
+                }
+                return Ok(Some(path_to_str(relative_path)?.replace('\\', "/")));
+            }
+        }
+        Ok(None)
+    }
+
     /// Returns false if this `entity` was blacklisted in some way.
     fn should_process_entity(&self, entity: Entity<'_>) -> Result<bool> {
         if entity.get_kind() == EntityKind::TranslationUnit {
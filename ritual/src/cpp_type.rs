@@ -463,6 +463,37 @@ pub fn is_qflags(path: &CppPath) -> bool {
             .map_or(false, |args| args.len() == 1)
 }
 
+/// Returns true if `path` is an instantiation of `std::unique_ptr`
+/// with the default deleter.
+pub fn is_unique_ptr(path: &CppPath) -> bool {
+    path.items()[0].name == "std"
+        && path.last().name == "unique_ptr"
+        && path
+            .last()
+            .template_arguments
+            .as_ref()
+            .map_or(false, |args| match args.as_slice() {
+                [CppType::Class(_)] => true,
+                [CppType::Class(_), CppType::Class(deleter)] => {
+                    deleter.last().name == "default_delete"
+                }
+                _ => false,
+            })
+}
+
+/// Returns true if `path` is an instantiation of `std::shared_ptr` or `QSharedPointer`.
+pub fn is_shared_ptr(path: &CppPath) -> bool {
+    let is_shared_ptr_template = (path.items()[0].name == "std"
+        && path.last().name == "shared_ptr")
+        || (path.last().name == "QSharedPointer" && !path.has_parent());
+    is_shared_ptr_template
+        && path
+            .last()
+            .template_arguments
+            .as_ref()
+            .map_or(false, |args| args.len() == 1)
+}
+
 impl CppType {
     pub fn contains_reference(&self) -> bool {
         if let CppType::PointerLike { kind, target, .. } = self {
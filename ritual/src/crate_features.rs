@@ -0,0 +1,187 @@
+//! Assignment of the generated items to optional Cargo features.
+
+use crate::config::CrateFeatureHook;
+use crate::database::{DatabaseClient, DbItem, ItemId};
+use crate::rust_info::{RustExtraImplKind, RustFunction, RustItem, RustStructKind};
+use crate::rust_type::{RustPath, RustType};
+use ritual_common::errors::{bail, Result};
+use std::collections::BTreeSet;
+
+/// Determines Cargo features that enable generated items
+/// based on `Config::set_crate_feature_hook`.
+pub struct CrateFeatures<'a> {
+    db: &'a DatabaseClient,
+    hook: Option<&'a CrateFeatureHook>,
+}
+
+/// Adds paths of all types mentioned in `rust_type` to `output`.
+fn collect_type_paths(rust_type: &RustType, output: &mut Vec<RustPath>) {
+    match rust_type {
+        RustType::Tuple(types) => {
+            for t in types {
+                collect_type_paths(t, output);
+            }
+        }
+        RustType::Common(common) | RustType::ImplTrait(common) => {
+            output.push(common.path.clone());
+            if let Some(args) = &common.generic_arguments {
+                for arg in args {
+                    collect_type_paths(arg, output);
+                }
+            }
+        }
+        RustType::FunctionPointer(pointer) => {
+            collect_type_paths(&pointer.return_type, output);
+            for arg in &pointer.arguments {
+                collect_type_paths(arg, output);
+            }
+        }
+        RustType::PointerLike { target, .. } => collect_type_paths(target, output),
+        RustType::Primitive(_) | RustType::GenericParameter(_) => {}
+    }
+}
+
+fn collect_function_type_paths(function: &RustFunction, output: &mut Vec<RustPath>) {
+    for arg in &function.arguments {
+        collect_type_paths(arg.argument_type.api_type(), output);
+        collect_type_paths(arg.argument_type.ffi_type(), output);
+    }
+    collect_type_paths(function.return_type.api_type(), output);
+    collect_type_paths(function.return_type.ffi_type(), output);
+}
+
+/// Checks that `name` can be used as a Cargo feature of the crate.
+fn check_feature_name(name: &str, reserved_names: &BTreeSet<String>) -> Result<()> {
+    let is_valid_char = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '-';
+    if name.is_empty() || name.starts_with('-') || !name.chars().all(is_valid_char) {
+        bail!("invalid feature name");
+    }
+    if name == "default" || reserved_names.contains(name) {
+        bail!("feature name is reserved");
+    }
+    Ok(())
+}
+
+impl<'a> CrateFeatures<'a> {
+    pub fn new(db: &'a DatabaseClient, hook: Option<&'a CrateFeatureHook>) -> Self {
+        Self { db, hook }
+    }
+
+    /// Returns the feature of the C++ item that the item `id` was generated from.
+    pub fn item_feature(&self, id: &ItemId) -> Result<Option<String>> {
+        let hook = if let Some(hook) = self.hook {
+            hook
+        } else {
+            return Ok(None);
+        };
+        if id.crate_name() != self.db.crate_name() {
+            return Ok(None);
+        }
+        match self.db.original_cpp_item(id)? {
+            Some(cpp_item) => hook(cpp_item.item),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns names of all features of the current crate.
+    ///
+    /// Fails if the hook returns a name that is not a valid Cargo feature name,
+    /// `default` or one of `reserved_names`.
+    pub fn all_features(&self, reserved_names: &BTreeSet<String>) -> Result<BTreeSet<String>> {
+        let mut features = BTreeSet::new();
+        if let Some(hook) = self.hook {
+            for item in self.db.cpp_items() {
+                if let Some(feature) = hook(item.item)? {
+                    if let Err(err) = check_feature_name(&feature, reserved_names) {
+                        bail!(
+                            "crate feature hook returned {:?} for {}: {}",
+                            feature,
+                            item.item.short_text(),
+                            err
+                        );
+                    }
+                    features.insert(feature);
+                }
+            }
+        }
+        Ok(features)
+    }
+
+    /// Returns the feature of the type `path`, or `None` if the type
+    /// is always available.
+    fn type_feature(&self, path: &RustPath) -> Result<Option<String>> {
+        if path.crate_name() != self.db.crate_name() {
+            return Ok(None);
+        }
+        let item = if let Some(item) = self.db.find_rust_item(path) {
+            item
+        } else {
+            return Ok(None);
+        };
+        match &item.item {
+            // sized types are always generated by the build script
+            RustItem::Struct(data) if data.kind.is_sized_type() => Ok(None),
+            RustItem::Struct(_) => self.item_feature(&item.id),
+            _ => Ok(None),
+        }
+    }
+
+    /// Returns all features that must be enabled to compile the Rust item:
+    /// the item's own feature and features of the types it uses.
+    pub fn rust_item_features(&self, item: &DbItem<&RustItem>) -> Result<BTreeSet<String>> {
+        let mut features = BTreeSet::new();
+        if self.hook.is_none() {
+            return Ok(features);
+        }
+        features.extend(self.item_feature(&item.id)?);
+
+        let mut paths = Vec::new();
+        match &item.item {
+            RustItem::Function(function) => collect_function_type_paths(function, &mut paths),
+            RustItem::Struct(rust_struct) => {
+                if let RustStructKind::ArgumentsBuilder(builder) = &rust_struct.kind {
+                    for field in builder
+                        .required_arguments
+                        .iter()
+                        .chain(&builder.optional_arguments)
+                    {
+                        collect_type_paths(&field.argument_type, &mut paths);
+                        collect_type_paths(&field.field_type, &mut paths);
+                    }
+                    collect_type_paths(&builder.return_type, &mut paths);
+                }
+            }
+            RustItem::TraitImpl(trait_impl) => {
+                collect_type_paths(&trait_impl.target_type, &mut paths);
+                if let Some(args) = &trait_impl.trait_type.generic_arguments {
+                    for arg in args {
+                        collect_type_paths(arg, &mut paths);
+                    }
+                }
+                for associated_type in &trait_impl.associated_types {
+                    collect_type_paths(&associated_type.value, &mut paths);
+                }
+                for function in &trait_impl.functions {
+                    collect_function_type_paths(function, &mut paths);
+                }
+            }
+            RustItem::ExtraImpl(extra_impl) => match &extra_impl.kind {
+                RustExtraImplKind::FlagEnum(data) => paths.push(data.enum_path.clone()),
+                RustExtraImplKind::QtReceiverImpl(data) => {
+                    paths.push(data.target_path.clone());
+                    collect_type_paths(&data.arguments, &mut paths);
+                    for arg in data.closure_arguments.iter().flatten() {
+                        collect_type_paths(arg, &mut paths);
+                    }
+                }
+                RustExtraImplKind::Hash(data) => paths.push(data.target_path.clone()),
+                RustExtraImplKind::Display(data) => paths.push(data.target_path.clone()),
+            },
+            _ => {}
+        }
+        for path in paths {
+            features.extend(self.type_feature(&path)?);
+        }
+        Ok(features)
+    }
+}
@@ -1,13 +1,12 @@
-use crate::config::{CrateDependencyKind, CrateDependencySource};
+use crate::config::{Config, CrateDependencyKind, CrateDependencySource};
 use crate::cpp_code_generator;
-use crate::cpp_code_generator::{
-    all_include_directives, generate_cpp_type_size_requester, write_include_directives,
-};
+use crate::cpp_code_generator::{all_include_directives, generate_cpp_type_size_requester};
+use crate::crate_features::CrateFeatures;
 use crate::database::CRATE_DB_FILE_NAME;
 use crate::processor::ProcessorData;
 use crate::rust_code_generator;
 use itertools::Itertools;
-use ritual_common::errors::Result;
+use ritual_common::errors::{bail, Result};
 use ritual_common::file_utils::{
     copy_file, copy_recursively, crate_version, create_dir, create_dir_all, create_file,
     diff_paths, path_to_str, read_dir, remove_dir_all, repo_dir_path, save_json, save_toml_table,
@@ -15,8 +14,9 @@ use ritual_common::file_utils::{
 use ritual_common::toml;
 use ritual_common::utils::run_command;
 use ritual_common::BuildScriptData;
+use std::collections::{BTreeMap, BTreeSet};
 use std::io::Write;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use std::process::Command;
 
 /// Merges `a` and `b` recursively. `b` take precedence over `a`.
@@ -56,10 +56,66 @@ fn toml_table_with_single_item(key: &str, value: impl Into<toml::Value>) -> toml
     toml::Value::Table(table)
 }
 
+/// Features that are always added to the generated crate.
+const RITUAL_FEATURES: &[&str] = &["ritual_rustdoc", "ritual_rustdoc_nightly"];
+
+/// Returns names that can't be used for features returned by the crate feature hook:
+/// features added by ritual and names of dependencies of the crate.
+pub fn reserved_feature_names(config: &Config) -> BTreeSet<String> {
+    let properties = config.crate_properties();
+    let mut names = RITUAL_FEATURES
+        .iter()
+        .map(|name| name.to_string())
+        .collect::<BTreeSet<_>>();
+    if !properties.should_remove_default_dependencies() {
+        names.insert("cpp_core".into());
+    }
+    names.extend(
+        properties
+            .dependencies()
+            .iter()
+            .map(|dep| dep.name().to_string()),
+    );
+    names
+}
+
+/// Returns content of the `[features]` section of `Cargo.toml`.
+/// `crate_features` are added as optional features enabled by default.
+pub fn features_table(config: &Config, crate_features: &BTreeSet<String>) -> toml::value::Table {
+    let mut features = toml::value::Table::new();
+    for &feature in RITUAL_FEATURES {
+        let dep_features = config
+            .crate_properties()
+            .dependencies()
+            .iter()
+            .filter(|dep| dep.kind() == CrateDependencyKind::Ritual)
+            .map(|dep| toml::value::Value::String(format!("{}/{}", dep.name(), feature)))
+            .collect_vec();
+
+        features.insert(feature.into(), dep_features.into());
+    }
+    if !crate_features.is_empty() {
+        for feature in crate_features {
+            features.insert(feature.clone(), toml::Value::Array(Vec::new()));
+        }
+        let default_features = crate_features
+            .iter()
+            .map(|feature| toml::Value::String(feature.clone()))
+            .collect_vec();
+        features.insert("default".into(), default_features.into());
+    }
+    features
+}
+
 /// Generates `Cargo.toml` file and skeleton of the crate.
 /// If a crate template was supplied, files from it are
 /// copied to the output location.
-fn generate_crate_template(data: &mut ProcessorData<'_>, output_path: &Path) -> Result<()> {
+/// `crate_features` are added as optional features enabled by default.
+fn generate_crate_template(
+    data: &mut ProcessorData<'_>,
+    output_path: &Path,
+    crate_features: &BTreeSet<String>,
+) -> Result<()> {
     let template_build_rs_path =
         data.config
             .crate_template_path()
@@ -110,7 +166,8 @@ fn generate_crate_template(data: &mut ProcessorData<'_>, output_path: &Path) ->
 
     let add_dependency = |table: &mut toml::value::Table,
                           name: &str,
-                          source: &CrateDependencySource|
+                          source: &CrateDependencySource,
+                          base_path: &Path|
      -> Result<()> {
         let (version, local_path) = match source {
             CrateDependencySource::CratesIo { version } => (version.to_string(), None),
@@ -128,7 +185,7 @@ fn generate_crate_template(data: &mut ProcessorData<'_>, output_path: &Path) ->
         let value = if local_path.is_none() || !data.config.write_dependencies_local_paths() {
             toml::Value::String(version)
         } else {
-            let path = diff_paths(&local_path.expect("checked above"), &output_path)?;
+            let path = diff_paths(&local_path.expect("checked above"), base_path)?;
             let mut value = toml::value::Table::new();
             value.insert("version".into(), toml::Value::String(version));
             value.insert(
@@ -141,53 +198,49 @@ fn generate_crate_template(data: &mut ProcessorData<'_>, output_path: &Path) ->
         Ok(())
     };
 
-    let mut dependencies = toml::value::Table::new();
-    if !data
-        .config
-        .crate_properties()
-        .should_remove_default_dependencies()
-    {
-        add_dependency(
-            &mut dependencies,
-            "cpp_core",
-            &CrateDependencySource::Local {
-                path: repo_dir_path("cpp_core")?,
-            },
-        )?;
-    }
-    for dep in data.config.crate_properties().dependencies() {
-        add_dependency(&mut dependencies, dep.name(), dep.source())?;
-    }
-    let mut build_dependencies = toml::value::Table::new();
-    if !data
-        .config
-        .crate_properties()
-        .should_remove_default_build_dependencies()
-    {
-        add_dependency(
-            &mut build_dependencies,
-            "ritual_build",
-            &CrateDependencySource::Local {
-                path: repo_dir_path("ritual_build")?,
-            },
-        )?;
-    }
-    for dep in data.config.crate_properties().build_dependencies() {
-        add_dependency(&mut build_dependencies, dep.name(), dep.source())?;
-    }
-    let mut features = toml::value::Table::new();
-    for &feature in &["ritual_rustdoc", "ritual_rustdoc_nightly"] {
-        let dep_features = data
+    // Returns dependencies and build dependencies of a crate located at `base_path`.
+    let dependency_tables = |base_path: &Path| -> Result<(toml::value::Table, toml::value::Table)> {
+        let mut dependencies = toml::value::Table::new();
+        if !data
             .config
             .crate_properties()
-            .dependencies()
-            .iter()
-            .filter(|dep| dep.kind() == CrateDependencyKind::Ritual)
-            .map(|dep| toml::value::Value::String(format!("{}/{}", dep.name(), feature)))
-            .collect_vec();
+            .should_remove_default_dependencies()
+        {
+            add_dependency(
+                &mut dependencies,
+                "cpp_core",
+                &CrateDependencySource::Local {
+                    path: repo_dir_path("cpp_core")?,
+                },
+                base_path,
+            )?;
+        }
+        for dep in data.config.crate_properties().dependencies() {
+            add_dependency(&mut dependencies, dep.name(), dep.source(), base_path)?;
+        }
+        let mut build_dependencies = toml::value::Table::new();
+        if !data
+            .config
+            .crate_properties()
+            .should_remove_default_build_dependencies()
+        {
+            add_dependency(
+                &mut build_dependencies,
+                "ritual_build",
+                &CrateDependencySource::Local {
+                    path: repo_dir_path("ritual_build")?,
+                },
+                base_path,
+            )?;
+        }
+        for dep in data.config.crate_properties().build_dependencies() {
+            add_dependency(&mut build_dependencies, dep.name(), dep.source(), base_path)?;
+        }
+        Ok((dependencies, build_dependencies))
+    };
 
-        features.insert(feature.into(), dep_features.into());
-    }
+    let (dependencies, build_dependencies) = dependency_tables(output_path)?;
+    let features = features_table(data.config, crate_features);
 
     let mut table = toml::value::Table::new();
     table.insert("package".into(), package.into());
@@ -208,18 +261,67 @@ fn generate_crate_template(data: &mut ProcessorData<'_>, output_path: &Path) ->
             copy_recursively(&item.path(), &target)?;
         }
     }
+    for test_crate in data.config.test_crates() {
+        let test_crate_path = output_path.join(test_crate);
+        if !test_crate_path.exists() {
+            bail!(
+                "test crate directory not found in the crate template: {}",
+                test_crate
+            );
+        }
+        let (dependencies, build_dependencies) = dependency_tables(&test_crate_path)?;
+        generate_test_crate_cargo_toml(
+            data.config.crate_properties().name(),
+            test_crate,
+            &test_crate_path,
+            dependencies,
+            build_dependencies,
+        )?;
+    }
     if !output_path.join("src").exists() {
         create_dir_all(output_path.join("src"))?;
     }
     Ok(())
 }
 
+/// Generates `Cargo.toml` of the test crate `test_crate` of the crate `crate_name`.
+/// The test crate is located in a subdirectory of the crate, so it's declared as
+/// a separate workspace.
+fn generate_test_crate_cargo_toml(
+    crate_name: &str,
+    test_crate: &str,
+    test_crate_path: &Path,
+    mut dependencies: toml::value::Table,
+    build_dependencies: toml::value::Table,
+) -> Result<()> {
+    let mut package = toml::value::Table::new();
+    package.insert(
+        "name".into(),
+        toml::Value::String(format!("{}_{}", crate_name, test_crate)),
+    );
+    package.insert("version".into(), toml::Value::String("0.0.0".into()));
+    package.insert("edition".into(), toml::Value::String("2018".into()));
+    package.insert("publish".into(), toml::Value::Boolean(false));
+
+    dependencies.insert(crate_name.into(), toml_table_with_single_item("path", ".."));
+
+    let mut table = toml::value::Table::new();
+    table.insert("package".into(), package.into());
+    table.insert("dependencies".into(), dependencies.into());
+    table.insert("build-dependencies".into(), build_dependencies.into());
+    table.insert("workspace".into(), toml::value::Table::new().into());
+    save_toml_table(
+        test_crate_path.join("Cargo.toml"),
+        &toml::Value::Table(table),
+    )?;
+    Ok(())
+}
+
 /// Generates main files and directories of the library.
-fn generate_c_lib_template(
+pub fn generate_c_lib_template(
     lib_name: &str,
     lib_path: &Path,
     global_header_name: &str,
-    include_directives: &[PathBuf],
 ) -> Result<()> {
     let name_upper = lib_name.to_uppercase();
     let cmakelists_path = lib_path.join("CMakeLists.txt");
@@ -239,7 +341,6 @@ fn generate_c_lib_template(
         "{}",
         include_str!("../templates/c_lib/global.h"),
     )?;
-    write_include_directives(&mut global_header_file, include_directives)?;
     Ok(())
 }
 
@@ -252,7 +353,9 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
     }
 
     create_dir(&output_path)?;
-    generate_crate_template(data, &output_path)?;
+    let crate_features = CrateFeatures::new(&data.db, data.config.crate_feature_hook())
+        .all_features(&reserved_feature_names(data.config))?;
+    generate_crate_template(data, &output_path, &crate_features)?;
     data.workspace.update_cargo_toml()?;
 
     let c_lib_path = output_path.join("c_lib");
@@ -261,24 +364,42 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
     }
     let c_lib_name = format!("{}_c", data.config.crate_properties().name());
     let global_header_name = format!("{}_global.h", c_lib_name);
-    generate_c_lib_template(
-        &c_lib_name,
-        &c_lib_path,
-        &global_header_name,
-        &all_include_directives(data.config)?,
-    )?;
+    generate_c_lib_template(&c_lib_name, &c_lib_path, &global_header_name)?;
+    let include_directives = all_include_directives(data.config)?;
 
-    cpp_code_generator::generate_cpp_file(
+    let features = CrateFeatures::new(&data.db, data.config.crate_feature_hook());
+    let items_per_file = data.config.cpp_wrapper_items_per_file();
+    cpp_code_generator::generate_cpp_files(
         &data.db,
-        &c_lib_path.join("file1.cpp"),
+        &c_lib_path,
+        "file",
         &global_header_name,
+        &include_directives,
+        &features,
+        None,
+        items_per_file,
     )?;
+    let mut feature_sources = BTreeMap::new();
+    for feature in &crate_features {
+        let file_names = cpp_code_generator::generate_cpp_files(
+            &data.db,
+            &c_lib_path,
+            &format!("file_{}_", feature),
+            &global_header_name,
+            &include_directives,
+            &features,
+            Some(feature),
+            items_per_file,
+        )?;
+        feature_sources.insert(feature.clone(), file_names);
+    }
 
     let file = create_file(c_lib_path.join("sized_types.cxx"))?;
     generate_cpp_type_size_requester(data.db, data.config.include_directives(), file)?;
 
     rust_code_generator::generate(
         &data.db,
+        &features,
         &output_path.join("src"),
         data.config.crate_template_path().map(|s| s.join("src")),
     )?;
@@ -303,6 +424,7 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
             cpp_build_config: data.config.cpp_build_config().clone(),
             cpp_wrapper_lib_name: c_lib_name,
             known_targets: data.db.environments().to_vec(),
+            feature_sources,
         },
         None,
     )?;
@@ -170,6 +170,14 @@ pub struct DocItem {
     pub cross_references: Vec<String>,
 }
 
+/// Header file containing declaration of a C++ item
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
+pub struct CppHeaderItem {
+    /// Path of the header relative to the target include path
+    /// it was found in, suitable for an `#include` directive
+    pub include_path: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum DatabaseItemData {
@@ -178,6 +186,7 @@ pub enum DatabaseItemData {
     CppChecksItem(CppChecksItem),
     RustItem(RustItem),
     DocItem(DocItem),
+    CppHeaderItem(CppHeaderItem),
 }
 
 impl DatabaseItemData {
@@ -279,6 +288,20 @@ impl DatabaseItemData {
             None
         }
     }
+    pub fn is_cpp_header_item(&self) -> bool {
+        if let DatabaseItemData::CppHeaderItem(_) = self {
+            true
+        } else {
+            false
+        }
+    }
+    pub fn as_cpp_header_item(&self) -> Option<&CppHeaderItem> {
+        if let DatabaseItemData::CppHeaderItem(data) = self {
+            Some(data)
+        } else {
+            None
+        }
+    }
 
     pub fn short_text(&self) -> String {
         match self {
@@ -287,6 +310,7 @@ impl DatabaseItemData {
             DatabaseItemData::RustItem(item) => item.short_text(),
             DatabaseItemData::CppChecksItem(_) => "CppChecksItem".into(),
             DatabaseItemData::DocItem(_) => "DocItem".into(),
+            DatabaseItemData::CppHeaderItem(_) => "CppHeaderItem".into(),
         }
     }
 }
@@ -681,6 +705,11 @@ impl DatabaseClient {
         self.current_database.find_rust_item(path)
     }
 
+    /// Finds a Rust item in the current crate or one of its dependencies.
+    pub fn find_rust_item_in_all_crates(&self, path: &RustPath) -> Option<DbItem<&RustItem>> {
+        self.database(path.crate_name()).ok()?.find_rust_item(path)
+    }
+
     pub fn rust_children<'a>(
         &'a self,
         path: &'a RustPath,
@@ -832,6 +861,39 @@ impl DatabaseClient {
         Some(id)
     }
 
+    pub fn add_cpp_header_item(
+        &mut self,
+        source_id: ItemId,
+        item: CppHeaderItem,
+    ) -> Option<ItemId> {
+        if self
+            .current_database
+            .filter_by_source(&Some(source_id.clone()))
+            .any(|other| other.item.is_cpp_header_item())
+        {
+            self.counters.items_ignored += 1;
+            return None;
+        }
+
+        let id = self.new_id();
+
+        self.current_database.push(DbItem {
+            id: id.clone(),
+            source_id: Some(source_id),
+            item: DatabaseItemData::CppHeaderItem(item),
+        });
+        self.counters.items_added += 1;
+        Some(id)
+    }
+
+    /// Returns the header declaring the C++ item `source_id`, if it's known.
+    pub fn cpp_header(&self, source_id: &ItemId) -> Result<Option<&CppHeaderItem>> {
+        Ok(self
+            .database(&source_id.crate_name)?
+            .filter_by_source(&Some(source_id.clone()))
+            .find_map(|item| item.item.as_cpp_header_item()))
+    }
+
     pub fn cpp_checks(&self, source_id: &ItemId) -> Result<CppChecks> {
         let items = self
             .database(&source_id.crate_name)?
@@ -95,7 +95,12 @@ fn first_phrase(html: &str) -> &str {
 pub fn struct_doc(type1: DbItem<&RustStruct>, database: &DatabaseClient) -> Result<String> {
     let mut output = String::new();
 
-    let doc_item = database.find_doc_for(&type1.id)?;
+    // C++ documentation of the function is attached to the builder's constructor instead
+    let doc_item = if let RustStructKind::ArgumentsBuilder(_) = &type1.item.kind {
+        None
+    } else {
+        database.find_doc_for(&type1.id)?
+    };
     if let Some(doc_item) = &doc_item {
         if !doc_item.item.html.is_empty() {
             writeln!(output, "{}\n", first_phrase(&doc_item.item.html))?;
@@ -220,6 +225,38 @@ pub fn struct_doc(type1: DbItem<&RustStruct>, database: &DatabaseClient) -> Resu
         }
         // private struct, no doc needed
         RustStructKind::SizedType(_) => {}
+        RustStructKind::NativeEnum(data) => {
+            let cpp_item = database
+                .source_cpp_item(&type1.id)?
+                .ok_or_else(|| err_msg("source cpp item not found"))?;
+            let cpp_type_code = cpp_item
+                .item
+                .path()
+                .ok_or_else(|| err_msg("cpp item expected to have path"))?
+                .to_cpp_pseudo_code();
+            writeln!(
+                output,
+                "Native Rust enum for C++ enum: {}.\n\n\
+                 Use `From` and `TryFrom` to convert between this enum and `{}`.\n",
+                wrap_inline_cpp_code(&cpp_type_code),
+                data.wrapper_path.last()
+            )?;
+        }
+        RustStructKind::ArgumentsBuilder(_) => {
+            let cpp_item = database
+                .source_cpp_item(&type1.id)?
+                .ok_or_else(|| err_msg("source cpp item not found"))?
+                .item
+                .as_function_ref()
+                .ok_or_else(|| err_msg("invalid source cpp item type"))?;
+            writeln!(
+                output,
+                "Arguments builder for C++ function: {}.\n\n\
+                 Optional arguments can be set using methods of the builder. \
+                 Call `build()` to call the function.\n",
+                wrap_inline_cpp_code(&cpp_item.short_text())
+            )?;
+        }
     };
 
     if let Some(doc_item) = doc_item {
@@ -416,6 +453,12 @@ pub fn function_doc(function: DbItem<&RustFunction>, database: &DatabaseClient)
                         }
                     };
                 }
+                CppFfiFunctionKind::StreamToString => {
+                    bail!("string conversion functions are not exposed as Rust functions");
+                }
+                CppFfiFunctionKind::StdHash => {
+                    bail!("hash functions are not exposed as Rust functions");
+                }
             }
         }
         RustFunctionKind::SignalOrSlotGetter(_) => {
@@ -440,6 +483,19 @@ pub fn function_doc(function: DbItem<&RustFunction>, database: &DatabaseClient)
                 cpp_path = cpp_item.path.to_cpp_pseudo_code()
             )?;
         }
+        RustFunctionKind::ArgumentsBuilderConstructor(data) => {
+            let cpp_item = cpp_item
+                .item
+                .as_function_ref()
+                .ok_or_else(|| err_msg("invalid source cpp item type"))?;
+            write!(
+                output,
+                "Returns a builder that calls C++ function: {}.\n\n\
+                 Optional arguments can be set using methods of `{}`.\n\n",
+                wrap_inline_cpp_code(&cpp_item.short_text()),
+                data.builder_path.last()
+            )?;
+        }
         // FFI functions are private
         RustFunctionKind::FfiFunction => {}
     }
@@ -449,6 +505,19 @@ pub fn function_doc(function: DbItem<&RustFunction>, database: &DatabaseClient)
     Ok(output)
 }
 
+pub fn arguments_builder_setter_doc(argument_name: &str) -> String {
+    format!(
+        "Sets value of the `{}` argument.\n\n\
+         Optional arguments can only be set in the order they appear \
+         in the C++ function.",
+        argument_name
+    )
+}
+
+pub fn arguments_builder_build_doc() -> String {
+    "Calls the C++ function with the arguments set in this builder.".to_string()
+}
+
 // TODO: add docs for slot wrapper functions
 /*
     for method in methods {
@@ -26,6 +26,7 @@ mod cpp_operator;
 pub mod cpp_parser;
 pub mod cpp_template_instantiator;
 pub mod cpp_type;
+mod crate_features;
 mod crate_writer;
 pub mod database;
 mod doc_formatter;
@@ -34,6 +35,7 @@ pub mod processor;
 mod rust_code_generator;
 mod rust_generator;
 pub mod rust_info;
+mod rust_rename_map;
 pub mod rust_type;
 mod type_allocation_places;
 pub mod workspace;
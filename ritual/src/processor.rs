@@ -216,8 +216,13 @@ fn build_crate(data: &mut ProcessorData<'_>) -> Result<()> {
             command.env_remove("CARGO_TARGET_DIR");
         }
 
-        if cargo_cmd == &"doc" {
-            command.arg("--features").arg("ritual_rustdoc");
+        let features = match *cargo_cmd {
+            "doc" => vec!["ritual_rustdoc".to_string()],
+            "test" => data.config.test_features().to_vec(),
+            _ => Vec::new(),
+        };
+        if !features.is_empty() {
+            command.arg("--features").arg(features.join(" "));
             // --features can't be used in workspace:
             // https://github.com/rust-lang/cargo/issues/5015
             command.current_dir(
@@ -229,6 +234,18 @@ fn build_crate(data: &mut ProcessorData<'_>) -> Result<()> {
         }
         run_command(&mut command)?;
     }
+
+    for test_crate in data.config.test_crates() {
+        let mut command = Command::new("cargo");
+        command.arg("test");
+        if let Ok(dir) = env::var(WORKSPACE_TARGET_DIR) {
+            command.env("CARGO_TARGET_DIR", dir);
+        } else {
+            command.env_remove("CARGO_TARGET_DIR");
+        }
+        command.current_dir(data.workspace.crate_path(crate_name).join(test_crate));
+        run_command(&mut command)?;
+    }
     Ok(())
 }
 
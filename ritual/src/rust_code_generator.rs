@@ -3,12 +3,14 @@
 use crate::cpp_checks::Condition;
 use crate::cpp_ffi_data::{CppFfiArgumentMeaning, CppFfiItem};
 use crate::cpp_function::CppFunction;
+use crate::crate_features::CrateFeatures;
 use crate::database::{DatabaseClient, DbItem, ItemId};
 use crate::doc_formatter;
 use crate::rust_generator::qt_core_path;
 use crate::rust_info::{
-    RustEnumValue, RustExtraImpl, RustExtraImplKind, RustFfiWrapperData, RustFunction,
-    RustFunctionArgument, RustFunctionKind, RustItem, RustModule, RustModuleKind,
+    RustArgumentsBuilder, RustArgumentsBuilderField, RustEnumValue, RustExtraImpl,
+    RustExtraImplKind, RustFfiWrapperData, RustFunction, RustFunctionArgument, RustFunctionKind,
+    RustItem, RustModule, RustModuleKind, RustNativeEnum, RustNativeEnumVariant,
     RustQtReceiverType, RustSpecialModuleKind, RustStruct, RustStructKind, RustTraitImpl,
     RustWrapperTypeKind,
 };
@@ -19,8 +21,11 @@ use crate::rust_type::{
 use itertools::Itertools;
 use ritual_common::errors::{bail, err_msg, format_err, Result};
 use ritual_common::file_utils::{create_dir_all, create_file, file_to_string, File};
-use ritual_common::string_utils::trim_slice;
+use ritual_common::string_utils::{trim_slice, CaseOperations};
+use ritual_common::target::{Arch, Endian, Env, Family, PointerWidth, OS};
 use ritual_common::utils::MapIfOk;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fmt::Write as _;
 use std::fs;
 use std::io::{self, BufWriter, Write};
@@ -36,13 +41,22 @@ fn wrap_unsafe(in_unsafe_context: bool, content: &str) -> String {
 }
 
 pub fn rust_common_type_to_code(rust_type: &RustCommonType, current_crate: Option<&str>) -> String {
-    let mut code = rust_type.path.full_name(current_crate);
+    common_type_to_code(rust_type, &|path| path.full_name(current_crate))
+}
+
+/// Generates Rust code representing type `rust_type`, using `path_to_code`
+/// to print paths of the used items.
+fn common_type_to_code(
+    rust_type: &RustCommonType,
+    path_to_code: &dyn Fn(&RustPath) -> String,
+) -> String {
+    let mut code = path_to_code(&rust_type.path);
     if let Some(args) = &rust_type.generic_arguments {
         write!(
             code,
             "<{}>",
             args.iter()
-                .map(|x| rust_type_to_code(x, current_crate))
+                .map(|x| type_to_code(x, path_to_code))
                 .join(", ",)
         )
         .unwrap();
@@ -53,11 +67,17 @@ pub fn rust_common_type_to_code(rust_type: &RustCommonType, current_crate: Optio
 /// Generates Rust code representing type `rust_type` inside crate `crate_name`.
 /// Same as `RustCodeGenerator::rust_type_to_code`, but accessible by other modules.
 pub fn rust_type_to_code(rust_type: &RustType, current_crate: Option<&str>) -> String {
+    type_to_code(rust_type, &|path| path.full_name(current_crate))
+}
+
+/// Generates Rust code representing type `rust_type`, using `path_to_code`
+/// to print paths of the used items.
+fn type_to_code(rust_type: &RustType, path_to_code: &dyn Fn(&RustPath) -> String) -> String {
     match rust_type {
         RustType::Tuple(types) => {
             let types_text = types
                 .iter()
-                .map(|t| rust_type_to_code(t, current_crate) + ",")
+                .map(|t| type_to_code(t, path_to_code) + ",")
                 .join("");
             format!("({})", types_text)
         }
@@ -67,7 +87,7 @@ pub fn rust_type_to_code(rust_type: &RustType, current_crate: Option<&str>) -> S
             target,
             is_const,
         } => {
-            let target_code = rust_type_to_code(&*target, current_crate);
+            let target_code = type_to_code(&*target, path_to_code);
             match kind {
                 RustPointerLikeTypeKind::Pointer => {
                     if *is_const {
@@ -89,39 +109,115 @@ pub fn rust_type_to_code(rust_type: &RustType, current_crate: Option<&str>) -> S
                 }
             }
         }
-        RustType::Common(common) => rust_common_type_to_code(common, current_crate),
+        RustType::Common(common) => common_type_to_code(common, path_to_code),
         RustType::FunctionPointer(function) => format!(
             "extern \"C\" fn({}){}",
             function
                 .arguments
                 .iter()
-                .map(|arg| rust_type_to_code(arg, current_crate))
+                .map(|arg| type_to_code(arg, path_to_code))
                 .join(", "),
             if function.return_type.is_unit() {
                 String::new()
             } else {
-                format!(
-                    " -> {}",
-                    rust_type_to_code(&function.return_type, current_crate)
-                )
+                format!(" -> {}", type_to_code(&function.return_type, path_to_code))
             }
         ),
-        RustType::ImplTrait(trait_type) => format!(
-            "impl {}",
-            rust_common_type_to_code(trait_type, current_crate)
-        ),
+        RustType::ImplTrait(trait_type) => {
+            format!("impl {}", common_type_to_code(trait_type, path_to_code))
+        }
     }
 }
 
+/// Names available without `use` declarations that must not be shadowed by imports.
+const PRELUDE_NAMES: &[&str] = &[
+    "AsMut",
+    "AsRef",
+    "Box",
+    "Clone",
+    "Copy",
+    "Default",
+    "DoubleEndedIterator",
+    "Drop",
+    "Eq",
+    "Err",
+    "ExactSizeIterator",
+    "Extend",
+    "Fn",
+    "FnMut",
+    "FnOnce",
+    "From",
+    "Into",
+    "IntoIterator",
+    "Iterator",
+    "None",
+    "Ok",
+    "Option",
+    "Ord",
+    "PartialEq",
+    "PartialOrd",
+    "Result",
+    "Send",
+    "Sized",
+    "Some",
+    "String",
+    "Sync",
+    "ToOwned",
+    "ToString",
+    "Unpin",
+    "Vec",
+    "bool",
+    "char",
+    "f32",
+    "f64",
+    "i128",
+    "i16",
+    "i32",
+    "i64",
+    "i8",
+    "isize",
+    "str",
+    "u128",
+    "u16",
+    "u32",
+    "u64",
+    "u8",
+    "usize",
+];
+
+/// Items imported into a module that is currently being generated.
+struct ModuleScope {
+    path: RustPath,
+    /// Number of open files when the module was entered. The scope is only
+    /// active while no other files are open on top of it.
+    file_index: usize,
+    /// Names that can't be imported because they are used by the module itself.
+    reserved_names: HashSet<String>,
+    /// Imported paths by the name they are available as.
+    imports: BTreeMap<String, RustPath>,
+    /// Generated content of the module. `use` declarations are written
+    /// before it when the module is finished.
+    content: Vec<u8>,
+}
+
 struct Generator<'a> {
     output_src_path: PathBuf,
     crate_template_src_path: Option<PathBuf>,
     destination: Vec<File<BufWriter<fs::File>>>,
+    scopes: RefCell<Vec<ModuleScope>>,
     current_database: &'a DatabaseClient,
+    features: &'a CrateFeatures<'a>,
 }
 
 impl Write for Generator<'_> {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let file_count = self.destination.len();
+        if let Some(scope) = self.scopes.get_mut().last_mut() {
+            if scope.file_index == file_count {
+                scope.content.extend_from_slice(buf);
+                return Ok(buf.len());
+            }
+        }
         io::Write::write(
             self.destination
                 .last_mut()
@@ -179,12 +275,63 @@ fn format_doc_extended(doc: &str, is_outer: bool) -> String {
 fn condition_expression(condition: &Condition) -> String {
     match condition {
         Condition::CppLibraryVersion(version) => format!("cpp_lib_version={:?}", version),
-        Condition::Arch(_) => unimplemented!(),
-        Condition::OS(_) => unimplemented!(),
-        Condition::Family(_) => unimplemented!(),
-        Condition::Env(_) => unimplemented!(),
-        Condition::PointerWidth(_) => unimplemented!(),
-        Condition::Endian(_) => unimplemented!(),
+        Condition::Arch(arch) => {
+            let value = match arch {
+                Arch::X86 => "x86",
+                Arch::X86_64 => "x86_64",
+                Arch::Mips => "mips",
+                Arch::PowerPC => "powerpc",
+                Arch::PowerPC64 => "powerpc64",
+                Arch::Arm => "arm",
+                Arch::AArch64 => "aarch64",
+            };
+            format!("target_arch={:?}", value)
+        }
+        Condition::OS(os) => {
+            let value = match os {
+                OS::Windows => "windows",
+                OS::MacOS => "macos",
+                OS::IOS => "ios",
+                OS::Linux => "linux",
+                OS::Android => "android",
+                OS::FreeBSD => "freebsd",
+                OS::DragonFly => "dragonfly",
+                OS::Bitrig => "bitrig",
+                OS::OpenBSD => "openbsd",
+                OS::NetBSD => "netbsd",
+            };
+            format!("target_os={:?}", value)
+        }
+        Condition::Family(family) => {
+            let value = match family {
+                Family::Windows => "windows",
+                Family::Unix => "unix",
+            };
+            format!("target_family={:?}", value)
+        }
+        Condition::Env(env) => {
+            let value = match env {
+                Env::Gnu => "gnu",
+                Env::Msvc => "msvc",
+                Env::Musl => "musl",
+                Env::None => "",
+            };
+            format!("target_env={:?}", value)
+        }
+        Condition::PointerWidth(pointer_width) => {
+            let value = match pointer_width {
+                PointerWidth::P64 => "64",
+                PointerWidth::P32 => "32",
+            };
+            format!("target_pointer_width={:?}", value)
+        }
+        Condition::Endian(endian) => {
+            let value = match endian {
+                Endian::Little => "little",
+                Endian::Big => "big",
+            };
+            format!("target_endian={:?}", value)
+        }
         Condition::And(conditions) => {
             let list = conditions.iter().map(condition_expression).join(", ");
             format!("all({})", list)
@@ -199,6 +346,19 @@ fn condition_expression(condition: &Condition) -> String {
     }
 }
 
+/// Returns `cfg` expression that is true if all `features` are enabled.
+fn features_expression(features: &BTreeSet<String>) -> String {
+    let expressions = features
+        .iter()
+        .map(|feature| format!("feature = {:?}", feature))
+        .collect_vec();
+    if expressions.len() == 1 {
+        expressions[0].clone()
+    } else {
+        format!("all({})", expressions.join(", "))
+    }
+}
+
 impl Generator<'_> {
     fn module_path(&self, rust_path: &RustPath, root_path: &Path) -> Result<PathBuf> {
         let parts = &rust_path.parts;
@@ -281,31 +441,34 @@ impl Generator<'_> {
             }
         }
 
-        let ffi_item = self
-            .current_database
-            .source_ffi_item(&item_for_condition.id)?;
-
         let mut condition_texts = ConditionTexts::default();
 
-        if let Some(ffi_item) = ffi_item {
-            let condition = self
-                .current_database
-                .cpp_checks(&ffi_item.id)?
-                .condition(self.current_database.environments());
-            if condition != Condition::True {
-                let expression = condition_expression(&condition);
-                condition_texts.attribute = format!(
-                    "#[cfg_attr(feature = \"ritual_rustdoc_nightly\", doc(cfg({})))]\n\
-                    #[cfg(any({}, feature = \"ritual_rustdoc\"))]\n",
-                    expression, expression
-                );
-                // condition_texts.doc_text =
-                // format!("\n\nThis item is available if `{}`.", expression);
-            }
+        let condition = self.item_condition(&item_for_condition.id)?;
+        if condition != Condition::True {
+            let expression = condition_expression(&condition);
+            condition_texts.attribute = format!(
+                "#[cfg_attr(feature = \"ritual_rustdoc_nightly\", doc(cfg({})))]\n\
+                #[cfg(any({}, feature = \"ritual_rustdoc\"))]\n",
+                expression, expression
+            );
+            // condition_texts.doc_text =
+            // format!("\n\nThis item is available if `{}`.", expression);
+        }
+
+        let features = self.features.rust_item_features(&item)?;
+        if !features.is_empty() {
+            let expression = features_expression(&features);
+            condition_texts.attribute += &format!(
+                "#[cfg_attr(feature = \"ritual_rustdoc_nightly\", doc(cfg({})))]\n\
+                 #[cfg({})]\n",
+                expression, expression
+            );
         }
 
         match &item.item {
-            RustItem::Module(_) => self.generate_module(item.map(|i| i.as_module_ref().unwrap())),
+            RustItem::Module(_) => {
+                self.generate_module(item.map(|i| i.as_module_ref().unwrap()), &condition_texts)
+            }
             RustItem::Struct(_) => {
                 self.generate_struct(item.map(|i| i.as_struct_ref().unwrap()), &condition_texts)
             }
@@ -330,7 +493,9 @@ impl Generator<'_> {
                 writeln!(
                     self,
                     "pub use {} as {};",
-                    self.rust_path_to_string(&reexport.target),
+                    reexport
+                        .target
+                        .full_name(Some(&self.current_database.crate_name())),
                     reexport.path.last()
                 )?;
                 Ok(())
@@ -338,16 +503,92 @@ impl Generator<'_> {
         }
     }
 
+    /// Returns the condition under which the FFI item
+    /// the Rust item `item_id` was generated from compiles.
+    fn item_condition(&self, item_id: &ItemId) -> Result<Condition> {
+        let ffi_item = self.current_database.source_ffi_item(item_id)?;
+        if let Some(ffi_item) = ffi_item {
+            Ok(self
+                .current_database
+                .cpp_checks(&ffi_item.id)?
+                .condition(self.current_database.environments()))
+        } else {
+            Ok(Condition::True)
+        }
+    }
+
+    /// Returns `cfg` expressions that are all true if the `Display` (and `Debug`)
+    /// implementation based on a C++ output stream operator is available for the type
+    /// at `type_path`, or `None` if there is no such implementation.
+    fn display_impl_cfg(&self, type_path: &RustPath) -> Result<Option<Vec<String>>> {
+        let item = self.current_database.rust_items().find(|item| {
+            item.item.as_extra_impl_ref().map_or(false, |data| {
+                if let RustExtraImplKind::Display(data) = &data.kind {
+                    &data.target_path == type_path
+                } else {
+                    false
+                }
+            })
+        });
+        let item = if let Some(item) = item {
+            item
+        } else {
+            return Ok(None);
+        };
+        let mut expressions = Vec::new();
+        let condition = self.item_condition(&item.id)?;
+        if condition != Condition::True {
+            expressions.push(format!(
+                "any({}, feature = \"ritual_rustdoc\")",
+                condition_expression(&condition)
+            ));
+        }
+        let features = self.features.rust_item_features(&item)?;
+        if !features.is_empty() {
+            expressions.push(features_expression(&features));
+        }
+        Ok(Some(expressions))
+    }
+
+    /// Generates a `Debug` implementation that prints the type name and the address
+    /// for a class wrapper `rust_struct` if the C++ class doesn't have
+    /// an output stream operator in some environments.
+    fn generate_fallback_debug_impl(
+        &mut self,
+        rust_struct: &RustStruct,
+        condition_texts: &ConditionTexts,
+    ) -> Result<()> {
+        let mut condition_attribute = condition_texts.attribute.clone();
+        if let Some(expressions) = self.display_impl_cfg(&rust_struct.path)? {
+            if expressions.is_empty() {
+                return Ok(());
+            }
+            condition_attribute += &format!("#[cfg(not(all({})))]\n", expressions.join(", "));
+        }
+        writeln!(
+            self,
+            include_str!("../templates/crate/debug_impl.rs.in"),
+            type_path = self.rust_path_to_string(&rust_struct.path),
+            name = rust_struct.path.last(),
+            condition_attribute = condition_attribute,
+        )?;
+        Ok(())
+    }
+
     fn rust_type_to_code(&self, rust_type: &RustType) -> String {
-        rust_type_to_code(rust_type, Some(&self.current_database.crate_name()))
+        type_to_code(rust_type, &|path| self.rust_path_to_string(path))
     }
 
     fn rust_common_type_to_code(&self, rust_type: &RustCommonType) -> String {
-        rust_common_type_to_code(rust_type, Some(&self.current_database.crate_name()))
+        common_type_to_code(rust_type, &|path| self.rust_path_to_string(path))
     }
 
     #[allow(clippy::collapsible_if)]
-    fn generate_module(&mut self, module: DbItem<&RustModule>) -> Result<()> {
+    fn generate_module(
+        &mut self,
+        module: DbItem<&RustModule>,
+        condition_texts: &ConditionTexts,
+    ) -> Result<()> {
         if self
             .current_database
             .rust_children(&module.item.path)
@@ -363,7 +604,13 @@ impl Generator<'_> {
         let mut content_from_template = None;
         if module.item.kind.is_in_separate_file() {
             if module.item.kind != RustModuleKind::Special(RustSpecialModuleKind::CrateRoot) {
-                writeln!(self, "{}mod {};", vis, module.item.path.last())?;
+                writeln!(
+                    self,
+                    "{}{}mod {};",
+                    condition_texts.attribute,
+                    vis,
+                    module.item.path.last()
+                )?;
             }
             let path = self.module_path(&module.item.path, &self.output_src_path)?;
             self.push_file(&path)?;
@@ -385,7 +632,13 @@ impl Generator<'_> {
                 module.item.kind,
                 RustModuleKind::Special(RustSpecialModuleKind::CrateRoot)
             );
-            writeln!(self, "{}mod {} {{", vis, module.item.path.last())?;
+            writeln!(
+                self,
+                "{}{}mod {} {{",
+                condition_texts.attribute,
+                vis,
+                module.item.path.last()
+            )?;
         }
 
         write!(
@@ -397,7 +650,7 @@ impl Generator<'_> {
             )
         )?;
 
-        if let Some(content) = content_from_template {
+        if let Some(content) = &content_from_template {
             writeln!(self, "{}", content)?;
         }
 
@@ -415,7 +668,9 @@ impl Generator<'_> {
             | RustModuleKind::Special(RustSpecialModuleKind::Ops)
             | RustModuleKind::CppNamespace { .. }
             | RustModuleKind::CppNestedTypes { .. } => {
+                self.push_module_scope(&module.item.path, content_from_template.as_deref());
                 self.generate_children(&module.item.path, None)?;
+                self.pop_module_scope()?;
             }
         }
 
@@ -472,10 +727,12 @@ impl Generator<'_> {
                         self,
                         include_str!("../templates/crate/enum_wrapper.rs.in"),
                         vis = visibility,
-                        name = rust_struct.item.path.last()
+                        name = rust_struct.item.path.last(),
+                        condition_attribute = condition_texts.attribute,
                     )?;
                 }
                 RustWrapperTypeKind::ImmovableClassWrapper => {
+                    write!(self, "{}", condition_texts.attribute)?;
                     writeln!(self, "#[repr(C)]")?;
                     writeln!(
                         self,
@@ -483,8 +740,10 @@ impl Generator<'_> {
                         visibility,
                         rust_struct.item.path.last()
                     )?;
+                    self.generate_fallback_debug_impl(rust_struct.item, condition_texts)?;
                 }
                 RustWrapperTypeKind::MovableClassWrapper { sized_type_path } => {
+                    write!(self, "{}", condition_texts.attribute)?;
                     writeln!(self, "#[repr(transparent)]")?;
                     writeln!(
                         self,
@@ -494,6 +753,7 @@ impl Generator<'_> {
                         self.rust_path_to_string(sized_type_path),
                     )?;
                     writeln!(self)?;
+                    self.generate_fallback_debug_impl(rust_struct.item, condition_texts)?;
                 }
             },
             RustStructKind::QtSlotWrapper(_) => {
@@ -502,6 +762,22 @@ impl Generator<'_> {
             RustStructKind::SizedType(_) => {
                 bail!("sized struct can't be generated with rust code generator")
             }
+            RustStructKind::ArgumentsBuilder(builder) => {
+                self.generate_arguments_builder(
+                    &rust_struct.item.path,
+                    visibility,
+                    builder,
+                    condition_texts,
+                )?;
+            }
+            RustStructKind::NativeEnum(native_enum) => {
+                self.generate_native_enum(
+                    &rust_struct.item.path,
+                    visibility,
+                    native_enum,
+                    condition_texts,
+                )?;
+            }
         }
 
         if self
@@ -515,7 +791,12 @@ impl Generator<'_> {
                 generic_arguments: None,
             });
 
-            writeln!(self, "impl {} {{", rust_struct.item.path.last())?;
+            writeln!(
+                self,
+                "{}impl {} {{",
+                condition_texts.attribute,
+                rust_struct.item.path.last()
+            )?;
             self.generate_children(&rust_struct.item.path, Some(&struct_type))?;
             writeln!(self, "}}")?;
             writeln!(self)?;
@@ -524,6 +805,184 @@ impl Generator<'_> {
         Ok(())
     }
 
+    /// Returns code that converts the argument of a builder's constructor
+    /// or setter to the type of the builder's field.
+    fn arguments_builder_field_value(&self, field: &RustArgumentsBuilderField) -> String {
+        if field.argument_type == field.field_type {
+            field.name.clone()
+        } else {
+            format!(
+                "::cpp_core::CastInto::<{}>::cast_into({})",
+                self.rust_type_to_code(&field.field_type),
+                field.name
+            )
+        }
+    }
+
+    /// Generates a builder struct. Its type parameter holds values of the optional
+    /// arguments set so far, so that the setters can only be called in order
+    /// and each state of the builder calls the matching function in `build()`.
+    fn generate_arguments_builder(
+        &mut self,
+        path: &RustPath,
+        visibility: &str,
+        builder: &RustArgumentsBuilder,
+        condition_texts: &ConditionTexts,
+    ) -> Result<()> {
+        let name = path.last();
+        let optional_types = builder
+            .optional_arguments
+            .iter()
+            .map(|arg| self.rust_type_to_code(&arg.field_type))
+            .collect_vec();
+        let state_type = |count: usize| {
+            if count == 0 {
+                "()".to_string()
+            } else {
+                format!("({},)", optional_types[..count].join(", "))
+            }
+        };
+
+        let fields = builder
+            .required_arguments
+            .iter()
+            .map(|arg| format!("{}: {},", arg.name, self.rust_type_to_code(&arg.field_type)))
+            .join("\n");
+        writeln!(
+            self,
+            "{}{}struct {}<T = ()> {{\n{}\noptional_arguments: T,\n}}\n",
+            condition_texts.attribute, visibility, name, fields
+        )?;
+
+        let required_values = builder
+            .required_arguments
+            .iter()
+            .map(|arg| format!("{name}: self.{name},", name = arg.name))
+            .join("\n");
+        let return_type = if builder.return_type.is_unit() {
+            String::new()
+        } else {
+            format!(" -> {}", self.rust_type_to_code(&builder.return_type))
+        };
+        for (index, function) in builder.functions.iter().enumerate() {
+            writeln!(
+                self,
+                "{}impl {}<{}> {{",
+                condition_texts.attribute,
+                name,
+                state_type(index)
+            )?;
+            if let Some(arg) = builder.optional_arguments.get(index) {
+                let optional_values = (0..index)
+                    .map(|i| format!("self.optional_arguments.{}, ", i))
+                    .join("");
+                writeln!(
+                    self,
+                    "{doc}pub unsafe fn {arg_name}(self, {arg_name}: {type_}) -> {name}<{state}> {{\n\
+                     {name} {{\n\
+                     {required_values}\n\
+                     optional_arguments: ({optional_values}{value},),\n\
+                     }}\n\
+                     }}\n",
+                    doc = format_doc(&doc_formatter::arguments_builder_setter_doc(&arg.name)),
+                    arg_name = arg.name,
+                    type_ = self.rust_type_to_code(&arg.argument_type),
+                    name = name,
+                    state = state_type(index + 1),
+                    required_values = required_values,
+                    optional_values = optional_values,
+                    value = self.arguments_builder_field_value(arg),
+                )?;
+            }
+            let args = builder
+                .required_arguments
+                .iter()
+                .map(|arg| format!("self.{}", arg.name))
+                .chain((0..index).map(|i| format!("self.optional_arguments.{}", i)))
+                .join(", ");
+            writeln!(
+                self,
+                "{doc}pub unsafe fn build(self){return_type} {{\n\
+                 <{name}>::{function}({args})\n\
+                 }}\n\
+                 }}\n",
+                doc = format_doc(&doc_formatter::arguments_builder_build_doc()),
+                return_type = return_type,
+                name = name,
+                function = function.last(),
+                args = args,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn generate_native_enum(
+        &mut self,
+        path: &RustPath,
+        visibility: &str,
+        native_enum: &RustNativeEnum,
+        condition_texts: &ConditionTexts,
+    ) -> Result<()> {
+        let name = path.last();
+        // Rust enum can't have multiple variants with the same value,
+        // so the first name is used for each value.
+        let mut unique_variants = Vec::new();
+        for variant in &native_enum.variants {
+            if !unique_variants
+                .iter()
+                .any(|v: &&RustNativeEnumVariant| v.value == variant.value)
+            {
+                unique_variants.push(variant);
+            }
+        }
+        let canonical_name = |value: i64| {
+            unique_variants
+                .iter()
+                .find(|v| v.value == value)
+                .map(|v| v.name.as_str())
+                .expect("every value has a variant")
+        };
+
+        writeln!(
+            self,
+            include_str!("../templates/crate/native_enum.rs.in"),
+            vis = visibility,
+            name = name,
+            wrapper = self.rust_path_to_string(&native_enum.wrapper_path),
+            condition_attribute = condition_texts.attribute,
+            variants = unique_variants
+                .iter()
+                .map(|v| format!("    {},", v.name))
+                .join("\n"),
+            from_arms = unique_variants
+                .iter()
+                .map(|v| format!(
+                    "            {}::{} => Self::from({} as ::std::os::raw::c_int),",
+                    name, v.name, v.value
+                ))
+                .join("\n"),
+            try_from_arms = unique_variants
+                .iter()
+                .map(|v| format!("            {} => Ok({}::{}),", v.value, name, v.name))
+                .join("\n"),
+            display_arms = unique_variants
+                .iter()
+                .map(|v| format!("            {}::{} => \"{}\",", name, v.name, v.name))
+                .join("\n"),
+            from_str_arms = native_enum
+                .variants
+                .iter()
+                .map(|v| format!(
+                    "            \"{}\" => Ok({}::{}),",
+                    v.name,
+                    name,
+                    canonical_name(v.value)
+                ))
+                .join("\n"),
+        )?;
+        Ok(())
+    }
+
     fn generate_enum_value(&mut self, value: DbItem<&RustEnumValue>) -> Result<()> {
         write!(
             self,
@@ -551,9 +1010,131 @@ impl Generator<'_> {
         Ok(())
     }
 
-    // TODO: generate relative paths for better readability
+    /// Returns code referring to the item at `path` from the module
+    /// that is currently being generated.
     fn rust_path_to_string(&self, path: &RustPath) -> String {
-        path.full_name(Some(&self.current_database.crate_name()))
+        self.import_path(path)
+            .unwrap_or_else(|| path.full_name(Some(&self.current_database.crate_name())))
+    }
+
+    /// Starts collecting imports for the module at `path`. Until the matching
+    /// `pop_module_scope` call, generated code is buffered so that `use` declarations
+    /// can be placed before it.
+    fn push_module_scope(&mut self, path: &RustPath, template_content: Option<&str>) {
+        let mut reserved_names: HashSet<String> = self
+            .current_database
+            .rust_children(path)
+            .filter_map(|item| item.item.path().map(|path| path.last().to_string()))
+            .collect();
+        if let Some(content) = template_content {
+            reserved_names.extend(
+                content
+                    .split(|c: char| !c.is_alphanumeric() && c != '_')
+                    .filter(|word| !word.is_empty())
+                    .map(str::to_string),
+            );
+        }
+        let scope = ModuleScope {
+            path: path.clone(),
+            file_index: self.destination.len(),
+            reserved_names,
+            imports: BTreeMap::new(),
+            content: Vec::new(),
+        };
+        self.scopes.get_mut().push(scope);
+    }
+
+    /// Writes `use` declarations and buffered content of the current module.
+    fn pop_module_scope(&mut self) -> Result<()> {
+        let scope = self
+            .scopes
+            .get_mut()
+            .pop()
+            .expect("generator: too much pop_module_scope");
+        let current_database = self.current_database;
+        let crate_name = current_database.crate_name();
+        for (name, path) in &scope.imports {
+            if name == path.last() {
+                writeln!(self, "use {};", path.full_name(Some(crate_name)))?;
+            } else {
+                writeln!(
+                    self,
+                    "use {} as {};",
+                    path.full_name(Some(crate_name)),
+                    name
+                )?;
+            }
+        }
+        if !scope.imports.is_empty() {
+            writeln!(self)?;
+        }
+        self.write_all(&scope.content)?;
+        Ok(())
+    }
+
+    /// Returns true if the item at `path` can be imported with a `use` declaration.
+    /// Only types declared directly in modules and not restricted by
+    /// `cfg` conditions are imported.
+    fn is_importable(&self, path: &RustPath) -> bool {
+        if path.parts.len() < 2 {
+            return false;
+        }
+        if path.crate_name() == "std" || path.crate_name() == "cpp_core" {
+            return true;
+        }
+        let item = match self.current_database.find_rust_item_in_all_crates(path) {
+            Some(item) => item,
+            None => return false,
+        };
+        if item.item.as_struct_ref().is_none() {
+            return false;
+        }
+        let has_module_parent = path.parent().ok().map_or(false, |parent| {
+            self.current_database
+                .find_rust_item_in_all_crates(&parent)
+                .map_or(false, |parent| parent.item.as_module_ref().is_some())
+        });
+        let has_condition = self
+            .current_database
+            .source_ffi_item(&item.id)
+            .map_or(true, |ffi_item| ffi_item.is_some());
+        let has_feature = self
+            .features
+            .item_feature(&item.id)
+            .map_or(true, |feature| feature.is_some());
+        has_module_parent && !has_condition && !has_feature
+    }
+
+    /// Returns a short name for `path` in the current module, adding
+    /// a `use` declaration if necessary. Returns `None` if the full path
+    /// must be used instead.
+    fn import_path(&self, path: &RustPath) -> Option<String> {
+        let file_count = self.destination.len();
+        let mut scopes = self.scopes.borrow_mut();
+        let scope = scopes
+            .last_mut()
+            .filter(|scope| scope.file_index == file_count)?;
+
+        let parent = path.parent().ok()?;
+        let name = path.last();
+        if parent == scope.path {
+            return Some(name.to_string());
+        }
+        if let Some((alias, _)) = scope.imports.iter().find(|(_, p)| *p == path) {
+            return Some(alias.clone());
+        }
+        if PRELUDE_NAMES.contains(&name) || !self.is_importable(path) {
+            return None;
+        }
+        let candidates = vec![
+            name.to_string(),
+            format!("{}{}", parent.last().to_class_case(), name),
+        ];
+        let alias = candidates.into_iter().find(|alias| {
+            !scope.reserved_names.contains(alias) && !scope.imports.contains_key(alias)
+        })?;
+        scope.imports.insert(alias.clone(), path.clone());
+        Some(alias)
     }
 
     /// Wraps `expression` of type `type1.rust_ffi_type` to convert
@@ -608,6 +1189,14 @@ impl Generator<'_> {
                 );
                 wrap_unsafe(in_unsafe_context, &code)
             }
+            RustToFfiTypeConversion::SharedPtrToPtr => {
+                let code = format!(
+                    "::cpp_core::SharedPtr::from_raw({}).expect(\"attempted to \
+                     construct a null SharedPtr\")",
+                    source_expr
+                );
+                wrap_unsafe(in_unsafe_context, &code)
+            }
             RustToFfiTypeConversion::QBoxToPtr => {
                 let code = format!("{}::QBox::from_raw({})", self.qt_core_prefix(), source_expr);
                 wrap_unsafe(in_unsafe_context, &code)
@@ -725,9 +1314,9 @@ impl Generator<'_> {
                     self.rust_type_to_code(type1.ffi_type())
                 )
             }
-            RustToFfiTypeConversion::CppBoxToPtr | RustToFfiTypeConversion::QBoxToPtr => {
-                format!("{}.into_raw_ptr()", expr)
-            }
+            RustToFfiTypeConversion::CppBoxToPtr
+            | RustToFfiTypeConversion::QBoxToPtr
+            | RustToFfiTypeConversion::SharedPtrToPtr => format!("{}.into_raw_ptr()", expr),
             RustToFfiTypeConversion::UtilsPtrToPtr { .. }
             | RustToFfiTypeConversion::UtilsRefToPtr { .. }
             | RustToFfiTypeConversion::QPtrToPtr { .. } => {
@@ -1062,6 +1651,31 @@ impl Generator<'_> {
                 Some(wrap_unsafe(func.item.is_unsafe, &call))
             }
             RustFunctionKind::FfiFunction => None,
+            RustFunctionKind::ArgumentsBuilderConstructor(data) => {
+                let builder = self
+                    .current_database
+                    .find_rust_item(&data.builder_path)
+                    .and_then(|item| item.item.as_struct_ref())
+                    .ok_or_else(|| err_msg("arguments builder not found"))?;
+                let builder_data = if let RustStructKind::ArgumentsBuilder(data) = &builder.kind {
+                    data
+                } else {
+                    bail!("invalid arguments builder item kind");
+                };
+                let required_fields = builder_data
+                    .required_arguments
+                    .iter()
+                    .map(|arg| {
+                        format!("{}: {},", arg.name, self.arguments_builder_field_value(arg))
+                    })
+                    .join("\n");
+                let code = format!(
+                    "{} {{\n{}\noptional_arguments: (),\n}}",
+                    self.rust_path_to_string(&data.builder_path),
+                    required_fields
+                );
+                Some(wrap_unsafe(func.item.is_unsafe, &code))
+            }
         };
 
         let maybe_body = match &body {
@@ -1184,7 +1798,8 @@ impl Generator<'_> {
                     self,
                     include_str!("../templates/crate/flag_enum_impl.rs.in"),
                     e = enum_path,
-                    qflags = qflags
+                    qflags = qflags,
+                    condition_attribute = condition_texts.attribute,
                 )?;
             }
             RustExtraImplKind::QtReceiverImpl(data) => {
@@ -1219,6 +1834,21 @@ impl Generator<'_> {
                 )?;
                 // TODO: use condition_texts.doc_text
 
+                if let Some(closure_arguments) = &data.closure_arguments {
+                    writeln!(
+                        self,
+                        include_str!("../templates/crate/impl_closure_slot.rs"),
+                        qt_core = self.qt_core_prefix(),
+                        type_path = self.rust_path_to_string(&data.target_path),
+                        args = self.rust_type_to_code(&data.arguments),
+                        closure_args = closure_arguments
+                            .iter()
+                            .map(|arg| self.rust_type_to_code(arg))
+                            .join(", "),
+                        condition_attribute = condition_texts.attribute,
+                    )?;
+                }
+
                 if data.receiver_type == RustQtReceiverType::Signal {
                     writeln!(
                         self,
@@ -1230,21 +1860,76 @@ impl Generator<'_> {
                     )?;
                 }
             }
+            RustExtraImplKind::Hash(data) => {
+                let type_path = self.rust_path_to_string(&data.target_path);
+                writeln!(
+                    self,
+                    include_str!("../templates/crate/hash_impl.rs.in"),
+                    type_path = type_path,
+                    ffi_function = self.rust_path_to_string(&data.ffi_function_path),
+                    condition_attribute = condition_texts.attribute,
+                )?;
+
+                // `HashMap` keys must be comparable to themselves, so `CppBox<T>`
+                // needs `T: PartialEq<CppBox<T>>` in addition to the C++ `operator==`.
+                if self.has_ref_equality(&data.target_path) {
+                    writeln!(
+                        self,
+                        include_str!("../templates/crate/box_eq_impl.rs.in"),
+                        type_path = type_path,
+                        condition_attribute = condition_texts.attribute,
+                    )?;
+                }
+            }
+            RustExtraImplKind::Display(data) => {
+                writeln!(
+                    self,
+                    include_str!("../templates/crate/display_impl.rs.in"),
+                    type_path = self.rust_path_to_string(&data.target_path),
+                    ffi_function = self.rust_path_to_string(&data.ffi_function_path),
+                    condition_attribute = condition_texts.attribute,
+                )?;
+            }
         }
         Ok(())
     }
+
+    /// Returns true if `PartialEq<Ref<T>>` is implemented for the type `T`
+    /// at `type_path`.
+    fn has_ref_equality(&self, type_path: &RustPath) -> bool {
+        let target_type = RustType::Common(RustCommonType {
+            path: type_path.clone(),
+            generic_arguments: None,
+        });
+        let ref_type = RustType::Common(RustCommonType {
+            path: RustPath::from_good_str("cpp_core::Ref"),
+            generic_arguments: Some(vec![target_type.clone()]),
+        });
+        self.current_database
+            .rust_items()
+            .filter_map(|item| item.item.as_trait_impl_ref())
+            .any(|trait_impl| {
+                trait_impl.target_type == target_type
+                    && trait_impl.trait_type.path == RustPath::from_good_str("std::cmp::PartialEq")
+                    && trait_impl.trait_type.generic_arguments.as_ref()
+                        == Some(&vec![ref_type.clone()])
+            })
+    }
 }
 
 pub fn generate(
     current_database: &DatabaseClient,
+    features: &CrateFeatures<'_>,
     output_src_path: impl Into<PathBuf>,
     crate_template_src_path: Option<impl Into<PathBuf>>,
 ) -> Result<()> {
     let mut generator = Generator {
         destination: Vec::new(),
+        scopes: RefCell::new(Vec::new()),
         output_src_path: output_src_path.into(),
         crate_template_src_path: crate_template_src_path.map(Into::into),
         current_database,
+        features,
     };
 
     let crate_root = generator
@@ -1256,6 +1941,6 @@ pub fn generate(
         })
         .ok_or_else(|| err_msg("crate root not found"))?;
 
-    generator.generate_module(crate_root)?;
+    generator.generate_module(crate_root, &ConditionTexts::default())?;
     Ok(())
 }
@@ -5,37 +5,42 @@ use crate::cpp_data::{
 };
 use crate::cpp_ffi_data::{
     CppCast, CppFfiArgumentMeaning, CppFfiFunction, CppFfiFunctionKind, CppFfiItem, CppFfiType,
-    CppFieldAccessorType, CppToFfiTypeConversion,
+    CppFieldAccessorType, CppToFfiTypeConversion, QtSlotWrapper,
 };
 use crate::cpp_ffi_generator::ffi_type;
 use crate::cpp_function::{CppFunction, CppOperator, ReturnValueAllocationPlace};
 use crate::cpp_type::{
-    is_qflags, CppBuiltInNumericType, CppFunctionPointerType, CppPointerLikeTypeKind,
-    CppSpecificNumericType, CppSpecificNumericTypeKind, CppType, CppTypeRole,
+    is_qflags, is_shared_ptr, CppBuiltInNumericType, CppFunctionPointerType,
+    CppPointerLikeTypeKind, CppSpecificNumericType, CppSpecificNumericTypeKind, CppType,
+    CppTypeRole,
 };
 use crate::database::{DbItem, ItemId, ItemWithSource};
 use crate::processor::ProcessorData;
 use crate::rust_info::{
-    NameType, RustEnumValue, RustExtraImpl, RustExtraImplKind, RustFfiWrapperData,
+    NameType, RustArgumentsBuilder, RustArgumentsBuilderConstructor, RustArgumentsBuilderField,
+    RustDisplayImpl, RustEnumValue, RustExtraImpl, RustExtraImplKind, RustFfiWrapperData,
     RustFlagEnumImpl, RustFunction, RustFunctionArgument, RustFunctionCaptionStrategy,
-    RustFunctionKind, RustFunctionSelfArgKind, RustItem, RustModule, RustModuleKind, RustPathScope,
-    RustQtReceiverData, RustQtReceiverImpl, RustQtReceiverType, RustReexport, RustReexportSource,
-    RustSignalOrSlotGetter, RustSizedType, RustSpecialModuleKind, RustStruct, RustStructKind,
-    RustTraitAssociatedType, RustTraitImpl, RustTraitImplExtraKind, RustTypeCaptionStrategy,
-    RustWrapperTypeKind, UnnamedRustFunction,
+    RustFunctionKind, RustFunctionSelfArgKind, RustHashImpl, RustItem, RustModule, RustModuleKind,
+    RustNativeEnum, RustNativeEnumVariant, RustPathScope, RustQtReceiverData, RustQtReceiverImpl,
+    RustQtReceiverType, RustReexport, RustReexportSource, RustSignalOrSlotGetter, RustSizedType,
+    RustSpecialModuleKind, RustStruct, RustStructKind, RustTraitAssociatedType, RustTraitImpl,
+    RustTraitImplExtraKind, RustTypeCaptionStrategy, RustWrapperTypeKind, UnnamedRustFunction,
 };
+use crate::rust_rename_map::RustRenameMap;
 use crate::rust_type::{
     RustClosureToCallbackConversion, RustCommonType, RustFinalType, RustFunctionPointerType,
     RustPath, RustPointerLikeTypeKind, RustToFfiTypeConversion, RustType,
 };
 use itertools::Itertools;
-use log::{debug, trace};
+use log::{debug, trace, warn};
 use ritual_common::errors::{bail, err_msg, format_err, print_trace, Result};
 use ritual_common::string_utils::CaseOperations;
 use ritual_common::utils::MapIfOk;
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::convert::TryFrom;
 use std::iter::Iterator;
 use std::ops::Deref;
+use std::os::raw::c_int;
 
 pub fn qt_core_path(crate_name: &str) -> RustPath {
     if crate_name.starts_with("moqt_") {
@@ -76,6 +81,9 @@ fn sanitize_rust_identifier_test() {
 struct FunctionWithDesiredPath {
     function: UnnamedRustFunction,
     desired_path: RustPath,
+    /// True if `desired_path` was returned by the Rust path hook.
+    /// Signature renames from the rename map don't apply to such functions.
+    is_from_hook: bool,
 }
 
 enum ProcessedFfiItem {
@@ -87,7 +95,6 @@ enum ProcessedFfiItem {
 enum ReturnTypeConstraint {
     Bool,
     Usize,
-    #[allow(dead_code)]
     Unit,
     Any,
 }
@@ -101,6 +108,7 @@ struct TraitImplInfo {
     has_output_associated_type: bool,
     trait_arg_is_second_arg_type: bool,
     second_arg_is_reference: bool,
+    second_arg_by_value: bool,
     return_type_constraint: ReturnTypeConstraint,
     self_arg_kind: RustFunctionSelfArgKind,
     target_is_reference: bool,
@@ -118,6 +126,7 @@ impl TraitImplInfo {
                 has_output_associated_type: true,
                 trait_arg_is_second_arg_type: true,
                 second_arg_is_reference: false,
+                second_arg_by_value: false,
                 return_type_constraint: ReturnTypeConstraint::Any,
                 target_is_reference: true,
             },
@@ -130,6 +139,7 @@ impl TraitImplInfo {
                 has_output_associated_type: true,
                 trait_arg_is_second_arg_type: true,
                 second_arg_is_reference: false,
+                second_arg_by_value: false,
                 return_type_constraint: ReturnTypeConstraint::Any,
                 target_is_reference: true,
             },
@@ -142,6 +152,7 @@ impl TraitImplInfo {
                 has_output_associated_type: true,
                 trait_arg_is_second_arg_type: true,
                 second_arg_is_reference: false,
+                second_arg_by_value: false,
                 return_type_constraint: ReturnTypeConstraint::Any,
                 target_is_reference: true,
             },
@@ -154,6 +165,7 @@ impl TraitImplInfo {
                 has_output_associated_type: true,
                 trait_arg_is_second_arg_type: true,
                 second_arg_is_reference: false,
+                second_arg_by_value: false,
                 return_type_constraint: ReturnTypeConstraint::Any,
                 target_is_reference: true,
             },
@@ -166,6 +178,7 @@ impl TraitImplInfo {
                 has_output_associated_type: true,
                 trait_arg_is_second_arg_type: true,
                 second_arg_is_reference: false,
+                second_arg_by_value: false,
                 return_type_constraint: ReturnTypeConstraint::Any,
                 target_is_reference: true,
             },
@@ -178,6 +191,7 @@ impl TraitImplInfo {
                 has_output_associated_type: true,
                 trait_arg_is_second_arg_type: true,
                 second_arg_is_reference: false,
+                second_arg_by_value: false,
                 return_type_constraint: ReturnTypeConstraint::Any,
                 target_is_reference: true,
             },
@@ -190,6 +204,7 @@ impl TraitImplInfo {
                 has_output_associated_type: true,
                 trait_arg_is_second_arg_type: true,
                 second_arg_is_reference: false,
+                second_arg_by_value: false,
                 return_type_constraint: ReturnTypeConstraint::Any,
                 target_is_reference: true,
             },
@@ -202,6 +217,7 @@ impl TraitImplInfo {
                 has_output_associated_type: true,
                 trait_arg_is_second_arg_type: true,
                 second_arg_is_reference: false,
+                second_arg_by_value: false,
                 return_type_constraint: ReturnTypeConstraint::Any,
                 target_is_reference: true,
             },
@@ -214,6 +230,7 @@ impl TraitImplInfo {
                 has_output_associated_type: true,
                 trait_arg_is_second_arg_type: true,
                 second_arg_is_reference: false,
+                second_arg_by_value: false,
                 return_type_constraint: ReturnTypeConstraint::Any,
                 target_is_reference: true,
             },
@@ -226,6 +243,7 @@ impl TraitImplInfo {
                 has_output_associated_type: true,
                 trait_arg_is_second_arg_type: true,
                 second_arg_is_reference: false,
+                second_arg_by_value: false,
                 return_type_constraint: ReturnTypeConstraint::Any,
                 target_is_reference: true,
             },
@@ -238,6 +256,7 @@ impl TraitImplInfo {
                 has_output_associated_type: false,
                 trait_arg_is_second_arg_type: true,
                 second_arg_is_reference: true,
+                second_arg_by_value: false,
                 return_type_constraint: ReturnTypeConstraint::Bool,
                 target_is_reference: false,
             },
@@ -248,6 +267,7 @@ impl TraitImplInfo {
                 is_inherent: false,
                 self_arg_kind: RustFunctionSelfArgKind::ConstRef,
                 second_arg_is_reference: true,
+                second_arg_by_value: false,
                 return_type_constraint: ReturnTypeConstraint::Bool,
                 has_output_associated_type: false,
                 trait_arg_is_second_arg_type: true,
@@ -260,6 +280,7 @@ impl TraitImplInfo {
                 is_inherent: false,
                 self_arg_kind: RustFunctionSelfArgKind::ConstRef,
                 second_arg_is_reference: true,
+                second_arg_by_value: false,
                 return_type_constraint: ReturnTypeConstraint::Bool,
                 has_output_associated_type: false,
                 trait_arg_is_second_arg_type: true,
@@ -272,6 +293,7 @@ impl TraitImplInfo {
                 is_inherent: false,
                 self_arg_kind: RustFunctionSelfArgKind::ConstRef,
                 second_arg_is_reference: true,
+                second_arg_by_value: false,
                 return_type_constraint: ReturnTypeConstraint::Bool,
                 has_output_associated_type: false,
                 trait_arg_is_second_arg_type: true,
@@ -284,6 +306,7 @@ impl TraitImplInfo {
                 is_inherent: false,
                 self_arg_kind: RustFunctionSelfArgKind::ConstRef,
                 second_arg_is_reference: true,
+                second_arg_by_value: false,
                 return_type_constraint: ReturnTypeConstraint::Bool,
                 has_output_associated_type: false,
                 trait_arg_is_second_arg_type: true,
@@ -296,6 +319,7 @@ impl TraitImplInfo {
                 is_inherent: false,
                 self_arg_kind: RustFunctionSelfArgKind::Value,
                 second_arg_is_reference: false,
+                second_arg_by_value: false,
                 return_type_constraint: ReturnTypeConstraint::Any,
                 has_output_associated_type: true,
                 trait_arg_is_second_arg_type: false,
@@ -310,6 +334,7 @@ impl TraitImplInfo {
                 has_output_associated_type: true,
                 trait_arg_is_second_arg_type: true,
                 second_arg_is_reference: false,
+                second_arg_by_value: false,
                 return_type_constraint: ReturnTypeConstraint::Any,
                 target_is_reference: true,
             },
@@ -322,6 +347,7 @@ impl TraitImplInfo {
                 has_output_associated_type: true,
                 trait_arg_is_second_arg_type: false,
                 second_arg_is_reference: false,
+                second_arg_by_value: false,
                 return_type_constraint: ReturnTypeConstraint::Any,
                 target_is_reference: false,
             },
@@ -334,6 +360,7 @@ impl TraitImplInfo {
                 has_output_associated_type: true,
                 trait_arg_is_second_arg_type: false,
                 second_arg_is_reference: false,
+                second_arg_by_value: false,
                 return_type_constraint: ReturnTypeConstraint::Any,
                 target_is_reference: false,
             },
@@ -346,6 +373,7 @@ impl TraitImplInfo {
                 has_output_associated_type: true,
                 trait_arg_is_second_arg_type: false,
                 second_arg_is_reference: false,
+                second_arg_by_value: false,
                 return_type_constraint: ReturnTypeConstraint::Any,
                 target_is_reference: false,
             },
@@ -381,7 +409,51 @@ impl TraitImplInfo {
         })
     }
 
-    fn new(function: &CppFunction) -> Option<TraitImplInfo> {
+    /// Returns the trait implemented by `function`. `iterator_types` are classes
+    /// returned by `begin()` and `end()` functions of containers.
+    fn new(function: &CppFunction, iterator_types: &HashSet<CppPath>) -> Option<TraitImplInfo> {
+        if function.is_indexed_access() {
+            return Some(TraitImplInfo {
+                trait_path: "cpp_core::vector_ops::At",
+                function_name: "at",
+                is_unsafe: true,
+                is_inherent: true,
+                self_arg_kind: RustFunctionSelfArgKind::ConstRef,
+                has_output_associated_type: true,
+                trait_arg_is_second_arg_type: true,
+                second_arg_is_reference: false,
+                second_arg_by_value: false,
+                return_type_constraint: ReturnTypeConstraint::Any,
+                target_is_reference: false,
+            });
+        }
+        if function.is_push_function() {
+            // `append(const int&)` is exposed as `Push<i32>` instead of `Push<*const i32>`
+            let second_arg_by_value = match &function.arguments[0].argument_type {
+                CppType::PointerLike {
+                    kind: CppPointerLikeTypeKind::Reference,
+                    is_const: true,
+                    target,
+                } => match &**target {
+                    CppType::BuiltInNumeric(_) | CppType::SpecificNumeric(_) => true,
+                    _ => false,
+                },
+                _ => false,
+            };
+            return Some(TraitImplInfo {
+                trait_path: "cpp_core::vector_ops::Push",
+                function_name: "push",
+                is_unsafe: true,
+                is_inherent: true,
+                self_arg_kind: RustFunctionSelfArgKind::ConstRef,
+                has_output_associated_type: false,
+                trait_arg_is_second_arg_type: true,
+                second_arg_is_reference: false,
+                second_arg_by_value,
+                return_type_constraint: ReturnTypeConstraint::Unit,
+                target_is_reference: false,
+            });
+        }
         if let Some(operator) = &function.operator {
             return Self::from_operator(operator);
         }
@@ -390,6 +462,24 @@ impl TraitImplInfo {
                 && function.arguments.is_empty()
                 && function.path.last().template_arguments.is_none()
             {
+                if function.is_shared_ptr_getter() {
+                    return Some(TraitImplInfo {
+                        trait_path: "cpp_core::ops::Get",
+                        function_name: "get",
+                        is_unsafe: true,
+                        is_inherent: true,
+                        self_arg_kind: RustFunctionSelfArgKind::ConstRef,
+                        has_output_associated_type: true,
+                        trait_arg_is_second_arg_type: false,
+                        second_arg_is_reference: false,
+                        second_arg_by_value: false,
+                        return_type_constraint: ReturnTypeConstraint::Any,
+                        target_is_reference: false,
+                    });
+                }
+                let is_iterator_member = function
+                    .class_path()
+                    .map_or(false, |path| iterator_types.contains(&path));
                 match function.path.last().name.as_str() {
                     "begin" => {
                         let info = if member.is_const {
@@ -402,6 +492,7 @@ impl TraitImplInfo {
                                 has_output_associated_type: true,
                                 trait_arg_is_second_arg_type: false,
                                 second_arg_is_reference: false,
+                                second_arg_by_value: false,
                                 return_type_constraint: ReturnTypeConstraint::Any,
                                 target_is_reference: false,
                             }
@@ -415,6 +506,7 @@ impl TraitImplInfo {
                                 has_output_associated_type: true,
                                 trait_arg_is_second_arg_type: false,
                                 second_arg_is_reference: false,
+                                second_arg_by_value: false,
                                 return_type_constraint: ReturnTypeConstraint::Any,
                                 target_is_reference: false,
                             }
@@ -432,6 +524,7 @@ impl TraitImplInfo {
                                 has_output_associated_type: true,
                                 trait_arg_is_second_arg_type: false,
                                 second_arg_is_reference: false,
+                                second_arg_by_value: false,
                                 return_type_constraint: ReturnTypeConstraint::Any,
                                 target_is_reference: false,
                             }
@@ -445,6 +538,7 @@ impl TraitImplInfo {
                                 has_output_associated_type: true,
                                 trait_arg_is_second_arg_type: false,
                                 second_arg_is_reference: false,
+                                second_arg_by_value: false,
                                 return_type_constraint: ReturnTypeConstraint::Any,
                                 target_is_reference: false,
                             }
@@ -463,6 +557,7 @@ impl TraitImplInfo {
                                     has_output_associated_type: true,
                                     trait_arg_is_second_arg_type: false,
                                     second_arg_is_reference: false,
+                                    second_arg_by_value: false,
                                     return_type_constraint: ReturnTypeConstraint::Any,
                                     target_is_reference: false,
                                 }
@@ -476,6 +571,7 @@ impl TraitImplInfo {
                                     has_output_associated_type: true,
                                     trait_arg_is_second_arg_type: false,
                                     second_arg_is_reference: false,
+                                    second_arg_by_value: false,
                                     return_type_constraint: ReturnTypeConstraint::Any,
                                     target_is_reference: false,
                                 }
@@ -483,6 +579,26 @@ impl TraitImplInfo {
                             return Some(info);
                         }
                     }
+                    "key" | "value" if member.is_const && is_iterator_member => {
+                        let (trait_path, function_name) = if function.path.last().name == "key" {
+                            ("cpp_core::ops::Key", "key")
+                        } else {
+                            ("cpp_core::ops::Value", "value")
+                        };
+                        return Some(TraitImplInfo {
+                            trait_path,
+                            function_name,
+                            is_unsafe: true,
+                            is_inherent: true,
+                            self_arg_kind: RustFunctionSelfArgKind::ConstRef,
+                            has_output_associated_type: true,
+                            trait_arg_is_second_arg_type: false,
+                            second_arg_is_reference: false,
+                            second_arg_by_value: false,
+                            return_type_constraint: ReturnTypeConstraint::Any,
+                            target_is_reference: false,
+                        });
+                    }
                     "size" => {
                         return Some(TraitImplInfo {
                             trait_path: "cpp_core::vector_ops::Size",
@@ -493,6 +609,7 @@ impl TraitImplInfo {
                             has_output_associated_type: false,
                             trait_arg_is_second_arg_type: false,
                             second_arg_is_reference: false,
+                            second_arg_by_value: false,
                             return_type_constraint: ReturnTypeConstraint::Usize,
                             target_is_reference: false,
                         });
@@ -547,6 +664,9 @@ fn check_trait_impl_uniqueness(
 struct State<'b, 'a> {
     data: &'b mut ProcessorData<'a>,
     special_module_paths: HashMap<RustSpecialModuleKind, RustPath>,
+    rename_map: RustRenameMap,
+    /// Classes returned by `begin()` and `end()` functions of containers.
+    iterator_types: HashSet<CppPath>,
 }
 
 impl State<'_, '_> {
@@ -768,7 +888,21 @@ impl State<'_, '_> {
                 false
             };
 
-            if let CppToFfiTypeConversion::ValueToPointer { .. } = cpp_ffi_type.conversion() {
+            if cpp_ffi_type.conversion() == &CppToFfiTypeConversion::UniquePtrToPointer {
+                // the ownership is transferred through `unique_ptr` in both directions,
+                // so `CppBox` (or `QBox`) must be able to delete the object
+                if let Some(checks) = checks {
+                    if !self.is_type_deletable(cpp_ffi_type.ffi_type(), checks)? {
+                        bail!("unique_ptr target type is not deletable");
+                    }
+                }
+                api_to_ffi_conversion = if inherits_qobject {
+                    RustToFfiTypeConversion::QBoxToPtr
+                } else {
+                    RustToFfiTypeConversion::CppBoxToPtr
+                };
+            } else if let CppToFfiTypeConversion::ValueToPointer { .. } = cpp_ffi_type.conversion()
+            {
                 if argument_meaning == &CppFfiArgumentMeaning::ReturnValue {
                     match allocation_place {
                         ReturnValueAllocationPlace::Stack => {
@@ -980,6 +1114,14 @@ impl State<'_, '_> {
                 other_type1 =
                     RustFinalType::new(other_type1.ffi_type().clone(), (**conversion).clone())?;
             }
+            if operator_info.second_arg_by_value
+                && other_type1.conversion() == &RustToFfiTypeConversion::None
+            {
+                other_type1 = RustFinalType::new(
+                    other_type1.ffi_type().clone(),
+                    RustToFfiTypeConversion::ValueToPtr,
+                )?;
+            }
             trait_args = Some(vec![other_type1.api_type().clone()]);
             other_type = Some(other_type1);
         } else {
@@ -1070,6 +1212,73 @@ impl State<'_, '_> {
         })
     }
 
+    /// Generates `From<&T> for U` implementation for an implicit
+    /// conversion operator of class `T` if `U` is a primitive type
+    /// or a wrapped class returned by value.
+    fn process_conversion_as_from_impl(
+        unnamed_function: UnnamedRustFunction,
+        crate_name: &str,
+        trait_types: &[TraitTypes],
+    ) -> Result<RustTraitImpl> {
+        if unnamed_function.arguments.len() != 1 {
+            bail!("conversion operator must have one argument");
+        }
+        let self_type = unnamed_function.arguments[0]
+            .argument_type
+            .ffi_type()
+            .clone();
+        let self_value_type = self_type.pointer_like_to_target()?;
+
+        let target_type = unnamed_function.return_type.api_type().clone();
+        let is_supported_target = match &target_type {
+            RustType::Primitive(_) => true,
+            RustType::Common(RustCommonType {
+                path,
+                generic_arguments,
+            }) => {
+                path == &RustPath::from_good_str("cpp_core::CppBox")
+                    || (path.crate_name() == "std" && generic_arguments.is_none())
+            }
+            _ => false,
+        };
+        if !is_supported_target {
+            bail!("unsupported conversion target type: {:?}", target_type);
+        }
+
+        let parent_path = if let RustType::Common(RustCommonType { path, .. }) = &self_value_type {
+            if path.crate_name() != crate_name {
+                bail!("self type is outside current crate");
+            }
+            path.parent()?
+        } else {
+            bail!("self type is not Common");
+        };
+
+        let trait_path = RustPath::from_good_str("std::convert::From");
+        let trait_type = RustCommonType {
+            path: trait_path.clone(),
+            generic_arguments: Some(vec![RustType::new_reference(true, self_value_type)]),
+        };
+        check_trait_impl_uniqueness(trait_types, &target_type, &trait_type)?;
+
+        let mut function = unnamed_function.with_path(trait_path.join("from"));
+        function.is_unsafe = false;
+        function.arguments[0].argument_type = RustFinalType::new(
+            self_type,
+            RustToFfiTypeConversion::RefToPtr { lifetime: None },
+        )?;
+        function.arguments[0].name = "value".to_string();
+
+        Ok(RustTraitImpl {
+            target_type,
+            parent_path,
+            trait_type,
+            associated_types: Vec::new(),
+            functions: vec![function],
+            extra_kind: RustTraitImplExtraKind::Normal,
+        })
+    }
+
     fn process_destructor(
         unnamed_function: UnnamedRustFunction,
         allocation_place: ReturnValueAllocationPlace,
@@ -1123,6 +1332,67 @@ impl State<'_, '_> {
         })
     }
 
+    /// Generates `CppClone` implementation for a copy constructor
+    /// or `CppDefault` implementation for a constructor without arguments.
+    fn process_constructor_as_trait_impl(
+        mut unnamed_function: UnnamedRustFunction,
+        is_copy_constructor: bool,
+        crate_name: &str,
+        trait_types: &[TraitTypes],
+    ) -> Result<RustTraitImpl> {
+        let target_type = match unnamed_function.return_type.api_type() {
+            RustType::Common(RustCommonType {
+                path,
+                generic_arguments: Some(args),
+            }) if path == &RustPath::from_good_str("cpp_core::CppBox") && args.len() == 1 => {
+                args[0].clone()
+            }
+            _ => bail!("constructor must return CppBox"),
+        };
+        let parent_path = if let RustType::Common(RustCommonType { path, .. }) = &target_type {
+            if path.crate_name() != crate_name {
+                bail!("target type is outside current crate");
+            }
+            path.parent()?
+        } else {
+            bail!("target type is not Common");
+        };
+
+        let (trait_path, function_name) = if is_copy_constructor {
+            let arg = match unnamed_function.arguments.as_mut_slice() {
+                [arg] => arg,
+                _ => bail!("copy constructor must have one argument"),
+            };
+            arg.name = "self".to_string();
+            arg.argument_type = RustFinalType::new(
+                arg.argument_type.ffi_type().clone(),
+                RustToFfiTypeConversion::RefToPtr { lifetime: None },
+            )?;
+            ("cpp_core::CppClone", "cpp_clone")
+        } else {
+            if !unnamed_function.arguments.is_empty() {
+                bail!("default constructor must not have arguments");
+            }
+            ("cpp_core::CppDefault", "cpp_default")
+        };
+        let trait_path = RustPath::from_good_str(trait_path);
+        let trait_type = RustCommonType {
+            path: trait_path.clone(),
+            generic_arguments: None,
+        };
+        check_trait_impl_uniqueness(trait_types, &target_type, &trait_type)?;
+
+        let function = unnamed_function.with_path(trait_path.join(function_name));
+        Ok(RustTraitImpl {
+            target_type,
+            parent_path,
+            trait_type,
+            associated_types: Vec::new(),
+            functions: vec![function],
+            extra_kind: RustTraitImplExtraKind::Normal,
+        })
+    }
+
     fn process_cast(
         mut unnamed_function: UnnamedRustFunction,
         cast: &CppCast,
@@ -1235,6 +1505,24 @@ impl State<'_, '_> {
         Ok(results)
     }
 
+    /// Returns types of the arguments of the closure accepted by the slot wrapper.
+    fn closure_argument_types(
+        &self,
+        wrapper: &QtSlotWrapper,
+        checks: &CppChecks,
+    ) -> Result<Vec<RustFinalType>> {
+        wrapper.arguments.iter().map_if_ok(|arg| {
+            self.rust_final_type(
+                arg,
+                // closure argument should be handled in the same way
+                // as return type (value is produced behind FFI)
+                &CppFfiArgumentMeaning::ReturnValue,
+                ReturnValueAllocationPlace::NotApplicable,
+                Some(checks),
+            )
+        })
+    }
+
     fn convert_callbacks_to_closure(
         &self,
         id: &ItemId,
@@ -1254,16 +1542,7 @@ impl State<'_, '_> {
             .as_slot_wrapper_ref()
             .ok_or_else(|| err_msg("invalid source ffi item type"))?;
 
-        let closure_arguments = wrapper.arguments.iter().map_if_ok(|arg| {
-            self.rust_final_type(
-                arg,
-                // closure argument should be handled in the same way
-                // as return type (value is produced behind FFI)
-                &CppFfiArgumentMeaning::ReturnValue,
-                ReturnValueAllocationPlace::NotApplicable,
-                Some(&checks),
-            )
-        })?;
+        let closure_arguments = self.closure_argument_types(wrapper, checks)?;
         let closure_return_type = self.rust_final_type(
             &CppFfiType::void(),
             // TODO: not sure about the meaning.
@@ -1288,6 +1567,74 @@ impl State<'_, '_> {
         Ok(())
     }
 
+    /// Returns path of the class type pointed to by `ffi_type`
+    /// if this type belongs to the current crate.
+    fn extra_impl_target_path(&self, ffi_type: &RustType) -> Result<RustPath> {
+        if let RustType::Common(RustCommonType { path, .. }) = ffi_type.pointer_like_to_target()? {
+            if path.crate_name() != self.data.db.crate_name() {
+                bail!("target type is outside current crate");
+            }
+            Ok(path)
+        } else {
+            bail!("target type is not Common");
+        }
+    }
+
+    /// Generates `Hash` implementation for the type pointed to by `value_ffi_type`
+    /// that uses the FFI function `ffi_function_path` returning the hash of the value.
+    fn hash_impl(
+        &self,
+        ffi_function_path: &RustPath,
+        value_ffi_type: &RustType,
+    ) -> Result<RustItem> {
+        let target_path = self.extra_impl_target_path(value_ffi_type)?;
+        let is_duplicate = self.data.db.rust_items().any(|item| {
+            item.item.as_extra_impl_ref().map_or(false, |data| {
+                if let RustExtraImplKind::Hash(data) = &data.kind {
+                    data.target_path == target_path
+                } else {
+                    false
+                }
+            })
+        });
+        if is_duplicate {
+            bail!(
+                "Hash is already implemented for {}",
+                target_path.full_name(None)
+            );
+        }
+        Ok(RustItem::ExtraImpl(RustExtraImpl {
+            parent_path: target_path.parent()?,
+            kind: RustExtraImplKind::Hash(RustHashImpl {
+                target_path,
+                ffi_function_path: ffi_function_path.clone(),
+            }),
+        }))
+    }
+
+    /// Generates `Hash` implementation if `function` is a `qHash` function
+    /// that receives only the hashed value.
+    fn process_hash_function(
+        &self,
+        cpp_function: &CppFunction,
+        function: &CppFfiFunction,
+        ffi_function_path: &RustPath,
+        value_ffi_type: &RustType,
+    ) -> Result<RustItem> {
+        if cpp_function.member.is_some() || cpp_function.path.last().name != "qHash" {
+            bail!("not a hash function");
+        }
+        let returns_integer = match function.return_type.ffi_type() {
+            CppType::BuiltInNumeric(t) => t.is_signed_integer() || t.is_unsigned_integer(),
+            CppType::SpecificNumeric(t) => t.kind != CppSpecificNumericTypeKind::FloatingPoint,
+            _ => false,
+        };
+        if function.arguments.len() != 1 || !returns_integer {
+            bail!("unsupported hash function signature");
+        }
+        self.hash_impl(ffi_function_path, value_ffi_type)
+    }
+
     /// Converts one function to a `RustSingleMethod`.
     fn process_rust_function(
         &self,
@@ -1298,6 +1645,40 @@ impl State<'_, '_> {
         let function = item.item;
         let rust_ffi_function = self.generate_ffi_function(&function)?;
         let ffi_function_path = rust_ffi_function.path.clone();
+        if let CppFfiFunctionKind::StreamToString = &function.kind {
+            let value_ffi_type = rust_ffi_function
+                .arguments
+                .get(0)
+                .ok_or_else(|| err_msg("no arguments"))?
+                .argument_type
+                .ffi_type();
+            let target_path = self.extra_impl_target_path(value_ffi_type)?;
+            let display_impl = RustItem::ExtraImpl(RustExtraImpl {
+                parent_path: target_path.parent()?,
+                kind: RustExtraImplKind::Display(RustDisplayImpl {
+                    target_path,
+                    ffi_function_path,
+                }),
+            });
+            return Ok(vec![
+                ProcessedFfiItem::Item(RustItem::Function(rust_ffi_function)),
+                ProcessedFfiItem::Item(display_impl),
+            ]);
+        }
+        if let CppFfiFunctionKind::StdHash = &function.kind {
+            let value_ffi_type = rust_ffi_function
+                .arguments
+                .get(0)
+                .ok_or_else(|| err_msg("no arguments"))?
+                .argument_type
+                .ffi_type()
+                .clone();
+            let hash_impl = self.hash_impl(&ffi_function_path, &value_ffi_type)?;
+            return Ok(vec![
+                ProcessedFfiItem::Item(RustItem::Function(rust_ffi_function)),
+                ProcessedFfiItem::Item(hash_impl),
+            ]);
+        }
         let mut results = vec![ProcessedFfiItem::Item(RustItem::Function(
             rust_ffi_function,
         ))];
@@ -1386,7 +1767,9 @@ impl State<'_, '_> {
             is_public: true,
             arguments,
             return_type,
-            kind: RustFunctionKind::FfiWrapper(RustFfiWrapperData { ffi_function_path }),
+            kind: RustFunctionKind::FfiWrapper(RustFfiWrapperData {
+                ffi_function_path: ffi_function_path.clone(),
+            }),
             is_unsafe: true,
         };
         self.convert_callbacks_to_closure(&item.id, &mut unnamed_function, checks)?;
@@ -1398,6 +1781,37 @@ impl State<'_, '_> {
             .ok_or_else(|| err_msg("source cpp item not found"))?
             .item;
 
+        // shared pointers returned by value are wrapped in `cpp_core::SharedPtr`,
+        // but constructors still return `CppBox` to allow implementing `CppClone`
+        if let CppType::Class(path) = function.return_type.original_type() {
+            let is_constructor = cpp_item
+                .as_function_ref()
+                .map_or(false, |f| f.is_constructor());
+            if is_shared_ptr(path)
+                && !is_constructor
+                && unnamed_function.return_type.conversion()
+                    == &RustToFfiTypeConversion::CppBoxToPtr
+            {
+                unnamed_function.return_type = RustFinalType::new(
+                    unnamed_function.return_type.ffi_type().clone(),
+                    RustToFfiTypeConversion::SharedPtrToPtr,
+                )?;
+            }
+        }
+
+        // `SharedPtr` derefs to the managed object through `cpp_core::ops::Get`,
+        // so the getter returns `Ptr` even if the managed object is a `QObject`.
+        if cpp_item
+            .as_function_ref()
+            .map_or(false, |f| f.is_shared_ptr_getter())
+            && unnamed_function.return_type.conversion() == &RustToFfiTypeConversion::QPtrToPtr
+        {
+            unnamed_function.return_type = RustFinalType::new(
+                unnamed_function.return_type.ffi_type().clone(),
+                RustToFfiTypeConversion::UtilsPtrToPtr {},
+            )?;
+        }
+
         if let CppFfiFunctionKind::Function = &function.kind {
             let cpp_function = cpp_item
                 .as_function_ref()
@@ -1417,10 +1831,37 @@ impl State<'_, '_> {
                 );
                 return Ok(results);
             }
+            if cpp_function.is_copy_constructor()
+                || (cpp_function.is_constructor() && unnamed_function.arguments.is_empty())
+            {
+                match State::process_constructor_as_trait_impl(
+                    unnamed_function.clone(),
+                    cpp_function.is_copy_constructor(),
+                    self.data.db.crate_name(),
+                    trait_types,
+                ) {
+                    Ok(item) => {
+                        results.push(ProcessedFfiItem::Item(RustItem::TraitImpl(item)));
+                    }
+                    Err(err) => {
+                        debug!("not implementing CppClone or CppDefault: {}", err);
+                    }
+                }
+            }
+            if let Some(arg) = unnamed_function.arguments.get(0) {
+                if let Ok(hash_impl) = self.process_hash_function(
+                    cpp_function,
+                    function,
+                    &ffi_function_path,
+                    arg.argument_type.ffi_type(),
+                ) {
+                    results.push(ProcessedFfiItem::Item(hash_impl));
+                }
+            }
             if cpp_function.operator.as_ref() == Some(&CppOperator::NotEqualTo) {
                 bail!("NotEqualTo is not needed in public API because PartialEq is used");
             }
-            if let Some(operator_info) = TraitImplInfo::new(cpp_function) {
+            if let Some(operator_info) = TraitImplInfo::new(cpp_function, &self.iterator_types) {
                 match State::process_operator_as_trait_impl(
                     unnamed_function.clone(),
                     operator_info,
@@ -1440,6 +1881,23 @@ impl State<'_, '_> {
                     }
                 }
             }
+            // explicit conversions are only available as methods
+            if let Some(CppOperator::Conversion(_)) = &cpp_function.operator {
+                if !cpp_function.is_explicit() {
+                    match State::process_conversion_as_from_impl(
+                        unnamed_function.clone(),
+                        self.data.db.crate_name(),
+                        trait_types,
+                    ) {
+                        Ok(item) => {
+                            results.push(ProcessedFfiItem::Item(RustItem::TraitImpl(item)));
+                        }
+                        Err(err) => {
+                            debug!("not implementing From for conversion operator: {}", err);
+                        }
+                    }
+                }
+            }
         }
 
         let cpp_path = cpp_item
@@ -1473,6 +1931,7 @@ impl State<'_, '_> {
                                 results.push(ProcessedFfiItem::Function(FunctionWithDesiredPath {
                                     function: unnamed_function,
                                     desired_path: type1.path.join(name),
+                                    is_from_hook: false,
                                 }));
                                 return Ok(results);
                             }
@@ -1482,10 +1941,17 @@ impl State<'_, '_> {
             }
         }
 
-        let desired_path = self.generate_rust_path(cpp_path, NameType::ApiFunction(item))?;
+        let name_type = NameType::ApiFunction(item);
+        let (desired_path, is_from_hook) =
+            if let Some(path) = self.rust_path_from_hook(cpp_path, name_type.clone())? {
+                (path, true)
+            } else {
+                (self.generate_default_rust_path(cpp_path, name_type)?, false)
+            };
         results.push(ProcessedFfiItem::Function(FunctionWithDesiredPath {
             function: unnamed_function,
             desired_path,
+            is_from_hook,
         }));
         Ok(results)
     }
@@ -1653,16 +2119,19 @@ impl State<'_, '_> {
                     .as_field_ref()
                     .ok_or_else(|| err_msg("invalid source cpp item type"))?;
 
-                let name = &field.path.last().name;
+                let name = self
+                    .data
+                    .config
+                    .rust_naming_policy()
+                    .to_snake_case(&field.path.last().name);
                 let function_name = match accessor_type {
-                    CppFieldAccessorType::CopyGetter | CppFieldAccessorType::ConstRefGetter => {
-                        name.to_string()
-                    }
+                    CppFieldAccessorType::CopyGetter | CppFieldAccessorType::ConstRefGetter => name,
                     CppFieldAccessorType::MutRefGetter => format!("{}_mut", name),
                     CppFieldAccessorType::Setter => format!("set_{}", name),
                 };
                 Some(function_name)
             }
+            CppFfiFunctionKind::StreamToString | CppFfiFunctionKind::StdHash => None,
         };
 
         Ok(r)
@@ -1686,12 +2155,30 @@ impl State<'_, '_> {
         }
     }
 
-    fn generate_rust_path(&self, cpp_path: &CppPath, name_type: NameType<'_>) -> Result<RustPath> {
+    fn rust_path_from_hook(
+        &self,
+        cpp_path: &CppPath,
+        name_type: NameType<'_>,
+    ) -> Result<Option<RustPath>> {
         if let Some(hook) = self.data.config.rust_path_hook() {
-            if let Some(path) = hook(cpp_path, name_type.clone(), &self.data)? {
-                return Ok(path);
-            }
+            hook(cpp_path, name_type, &self.data)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn generate_rust_path(&self, cpp_path: &CppPath, name_type: NameType<'_>) -> Result<RustPath> {
+        if let Some(path) = self.rust_path_from_hook(cpp_path, name_type.clone())? {
+            return Ok(path);
         }
+        self.generate_default_rust_path(cpp_path, name_type)
+    }
+
+    fn generate_default_rust_path(
+        &self,
+        cpp_path: &CppPath,
+        name_type: NameType<'_>,
+    ) -> Result<RustPath> {
         let scope = match &name_type {
             NameType::FfiFunction => RustPathScope {
                 path: self.special_module_paths[&RustSpecialModuleKind::Ffi].clone(),
@@ -1738,70 +2225,106 @@ impl State<'_, '_> {
             }
         };
 
-        let full_last_name = match &name_type {
-            NameType::SizedItem => cpp_path
-                .items()
-                .iter()
-                .map_if_ok(|item| self.cpp_path_item_to_name(item, &scope.path, &name_type))?
-                .join("_"),
-            NameType::ApiFunction(function) => {
-                let s = if let Some(last_name_override) =
-                    self.special_function_rust_name(function.clone(), &scope.path)?
-                {
-                    last_name_override
+        let policy = self.data.config.rust_naming_policy();
+        let mut is_from_rename_map = false;
+        let renamed = match &name_type {
+            NameType::Type { .. }
+            | NameType::EnumValue
+            | NameType::Module { .. }
+            | NameType::ApiFunction(_) => {
+                if let Some(name) = self.rename_map.find_by_path(cpp_path) {
+                    is_from_rename_map = true;
+                    Some(name)
                 } else {
-                    self.cpp_path_item_to_name(cpp_path.last(), &scope.path, &name_type)?
-                };
-                s.to_snake_case()
+                    policy.rename(cpp_path)
+                }
             }
-            NameType::ReceiverFunction {
-                receiver_type,
-                is_wrapped_signal,
-            } => {
-                if *is_wrapped_signal {
-                    "signal".to_string()
-                } else {
-                    let name = self
-                        .cpp_path_item_to_name(cpp_path.last(), &scope.path, &name_type)?
-                        .to_snake_case();
-                    match receiver_type {
-                        RustQtReceiverType::Signal => name,
-                        RustQtReceiverType::Slot => format!("slot_{}", name),
+            _ => None,
+        };
+
+        let full_last_name = if let Some(name) = renamed {
+            if name_type.is_module() {
+                name.to_snake_case()
+            } else {
+                name.to_string()
+            }
+        } else {
+            match &name_type {
+                NameType::SizedItem => cpp_path
+                    .items()
+                    .iter()
+                    .map_if_ok(|item| self.cpp_path_item_to_name(item, &scope.path, &name_type))?
+                    .join("_"),
+                NameType::ApiFunction(function) => {
+                    if let Some(last_name_override) =
+                        self.special_function_rust_name(function.clone(), &scope.path)?
+                    {
+                        last_name_override.to_snake_case()
+                    } else {
+                        let name =
+                            self.cpp_path_item_to_name(cpp_path.last(), &scope.path, &name_type)?;
+                        policy.to_snake_case(&name)
                     }
                 }
-            }
-            NameType::Type { .. } | NameType::EnumValue => {
-                if cpp_path.to_templateless_string() == "std::vector" {
-                    // remove allocator template argument
-                    let mut path_item = cpp_path.last().clone();
-                    if let Some(args) = &mut path_item.template_arguments {
-                        args.pop();
+                NameType::ReceiverFunction {
+                    receiver_type,
+                    is_wrapped_signal,
+                } => {
+                    if *is_wrapped_signal {
+                        "signal".to_string()
+                    } else {
+                        let name = policy.to_snake_case(&self.cpp_path_item_to_name(
+                            cpp_path.last(),
+                            &scope.path,
+                            &name_type,
+                        )?);
+                        match receiver_type {
+                            RustQtReceiverType::Signal => name,
+                            RustQtReceiverType::Slot => format!("slot_{}", name),
+                        }
                     }
-                    self.cpp_path_item_to_name(&path_item, &scope.path, &name_type)?
-                        .to_class_case()
-                } else {
-                    self.cpp_path_item_to_name(&cpp_path.last(), &scope.path, &name_type)?
-                        .to_class_case()
                 }
-            }
-            NameType::Module { .. } => self
-                .cpp_path_item_to_name(&cpp_path.last(), &scope.path, &name_type)?
-                .to_snake_case(),
-            NameType::FfiFunction => cpp_path.last().name.clone(),
-            NameType::QtSlotWrapper { signal_arguments } => {
-                if signal_arguments.is_empty() {
-                    "SlotNoArgs".to_string()
-                } else {
-                    let captions = self.type_list_caption(signal_arguments, &scope.path)?;
-                    format!("SlotOf_{}", captions).to_class_case()
+                NameType::Type { .. } | NameType::EnumValue => {
+                    if cpp_path.to_templateless_string() == "std::vector" {
+                        // remove allocator template argument
+                        let mut path_item = cpp_path.last().clone();
+                        if let Some(args) = &mut path_item.template_arguments {
+                            args.pop();
+                        }
+                        policy.to_class_case(&self.cpp_path_item_to_name(
+                            &path_item,
+                            &scope.path,
+                            &name_type,
+                        )?)
+                    } else {
+                        policy.to_class_case(&self.cpp_path_item_to_name(
+                            &cpp_path.last(),
+                            &scope.path,
+                            &name_type,
+                        )?)
+                    }
                 }
-            }
-            NameType::QtSignalWrapper { signal_arguments } => {
-                if signal_arguments.is_empty() {
-                    "SignalNoArgs".to_string()
-                } else {
-                    let captions = self.type_list_caption(signal_arguments, &scope.path)?;
-                    format!("SignalOf_{}", captions).to_class_case()
+                NameType::Module { .. } => policy.to_snake_case(&self.cpp_path_item_to_name(
+                    &cpp_path.last(),
+                    &scope.path,
+                    &name_type,
+                )?),
+                NameType::FfiFunction => cpp_path.last().name.clone(),
+                NameType::QtSlotWrapper { signal_arguments } => {
+                    if signal_arguments.is_empty() {
+                        "SlotNoArgs".to_string()
+                    } else {
+                        let captions = self.type_list_caption(signal_arguments, &scope.path)?;
+                        format!("SlotOf_{}", captions).to_class_case()
+                    }
+                }
+                NameType::QtSignalWrapper { signal_arguments } => {
+                    if signal_arguments.is_empty() {
+                        "SignalNoArgs".to_string()
+                    } else {
+                        let captions = self.type_list_caption(signal_arguments, &scope.path)?;
+                        format!("SignalOf_{}", captions).to_class_case()
+                    }
                 }
             }
         };
@@ -1819,6 +2342,15 @@ impl State<'_, '_> {
 
         if name_type.is_api_function() {
             Ok(rust_path)
+        } else if is_from_rename_map {
+            if self.data.db.find_rust_item(&rust_path).is_some() {
+                bail!(
+                    "name from rename map for {} conflicts with existing item: {:?}",
+                    cpp_path.to_cpp_pseudo_code(),
+                    rust_path
+                );
+            }
+            Ok(rust_path)
         } else {
             Ok(self.data.db.make_unique_rust_path(&rust_path))
         }
@@ -1958,6 +2490,12 @@ impl State<'_, '_> {
                 .arguments
                 .iter()
                 .map_if_ok(|t| self.ffi_type_to_rust_ffi_type(t.ffi_type()))?;
+            let checks = self.data.db.cpp_checks(&wrapper.id)?;
+            let closure_arguments = self
+                .closure_argument_types(wrapper.item, &checks)?
+                .into_iter()
+                .map(|t| t.api_type().clone())
+                .collect();
 
             let impl_item = RustItem::ExtraImpl(RustExtraImpl {
                 parent_path: public_path.parent()?,
@@ -1965,6 +2503,7 @@ impl State<'_, '_> {
                     target_path: public_path.clone(),
                     arguments: RustType::Tuple(arg_types.clone()),
                     receiver_type: RustQtReceiverType::Slot,
+                    closure_arguments: Some(closure_arguments),
                 }),
             });
             rust_items.push(impl_item);
@@ -1987,6 +2526,7 @@ impl State<'_, '_> {
                     target_path: public_path.clone(),
                     arguments: RustType::Tuple(arg_types.clone()),
                     receiver_type: RustQtReceiverType::Signal,
+                    closure_arguments: None,
                 }),
             });
             rust_items.push(impl_item);
@@ -2035,7 +2575,7 @@ impl State<'_, '_> {
                 CppTypeDeclarationKind::Class { .. } => {
                     self.process_cpp_class(cpp_item.map(|v| v.as_type_ref().unwrap()))
                 }
-                CppTypeDeclarationKind::Enum => {
+                CppTypeDeclarationKind::Enum { .. } => {
                     let rust_path = self.generate_rust_path(
                         &data.path,
                         NameType::Type {
@@ -2277,6 +2817,83 @@ impl State<'_, '_> {
         Ok(())
     }
 
+    /// Adds native Rust enums for wrappers of closed C++ enums.
+    fn generate_native_enums(&mut self) -> Result<()> {
+        let mut native_enums = Vec::new();
+        for item in self.data.db.rust_items() {
+            let wrapper = match item.item.as_struct_ref() {
+                Some(data)
+                    if data.kind
+                        == RustStructKind::WrapperType(RustWrapperTypeKind::EnumWrapper) =>
+                {
+                    data
+                }
+                _ => continue,
+            };
+            let cpp_item = self
+                .data
+                .db
+                .source_cpp_item(&item.id)?
+                .ok_or_else(|| err_msg("source cpp item not found"))?;
+            let is_closed = match cpp_item.item.as_type_ref() {
+                Some(data) => match data.kind {
+                    CppTypeDeclarationKind::Enum { is_scoped } => {
+                        is_scoped || self.data.config.closed_enums().contains(&data.path)
+                    }
+                    CppTypeDeclarationKind::Class => false,
+                },
+                None => false,
+            };
+            if !is_closed {
+                continue;
+            }
+
+            let variants = self
+                .data
+                .db
+                .rust_children(&wrapper.path)
+                .filter_map(|child| child.item.as_enum_value_ref())
+                .map(|value| RustNativeEnumVariant {
+                    name: value.path.last().to_string(),
+                    value: value.value,
+                })
+                .collect_vec();
+            if variants.is_empty() {
+                continue;
+            }
+            // The wrapper is converted to and from `c_int`, so values
+            // that don't fit into it can't be represented.
+            if variants
+                .iter()
+                .any(|variant| c_int::try_from(variant.value).is_err())
+            {
+                debug!(
+                    "skipping native enum for {}: values don't fit into c_int",
+                    wrapper.path.full_name(None)
+                );
+                continue;
+            }
+            native_enums.push((cpp_item.id, wrapper.path.clone(), variants));
+        }
+
+        for (source_id, wrapper_path, variants) in native_enums {
+            let path = wrapper_path
+                .parent()?
+                .join(format!("{}Enum", wrapper_path.last()));
+            let rust_item = RustItem::Struct(RustStruct {
+                path: self.data.db.make_unique_rust_path(&path),
+                kind: RustStructKind::NativeEnum(RustNativeEnum {
+                    wrapper_path,
+                    variants,
+                }),
+                is_public: true,
+                qt_receiver_data: None,
+            });
+            self.add_rust_item(Some(source_id), rust_item)?;
+        }
+        Ok(())
+    }
+
     fn process_ffi_items(
         &mut self,
     ) -> Result<BTreeMap<RustPath, Vec<ItemWithSource<FunctionWithDesiredPath>>>> {
@@ -2354,13 +2971,326 @@ impl State<'_, '_> {
         Ok(())
     }
 
+    /// Returns id of the C++ function with all default arguments present
+    /// if the FFI function `ffi_item_id` corresponds to this function
+    /// or to a copy of it with some default arguments omitted.
+    fn function_with_default_arguments(&self, ffi_item_id: &ItemId) -> Result<Option<ItemId>> {
+        let cpp_item = match self.data.db.source_cpp_item(ffi_item_id)? {
+            Some(item) => item,
+            None => return Ok(None),
+        };
+        let function = match cpp_item.item.as_function_ref() {
+            Some(function) => function,
+            None => return Ok(None),
+        };
+        if let Some(source_id) = &cpp_item.source_id {
+            let source_item = self.data.db.cpp_item(source_id).ok();
+            if let Some(source_function) = source_item.and_then(|item| item.item.as_function_ref())
+            {
+                if source_function.path == function.path
+                    && source_function.arguments.len() > function.arguments.len()
+                    && source_function.arguments[function.arguments.len()..]
+                        .iter()
+                        .all(|arg| arg.has_default_value)
+                {
+                    return Ok(Some(source_id.clone()));
+                }
+            }
+        }
+        if function
+            .arguments
+            .last()
+            .map_or(false, |arg| arg.has_default_value)
+        {
+            Ok(Some(cpp_item.id))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Checks whether `variants` (sorted by number of arguments) cover all
+    /// possible numbers of omitted default arguments of `cpp_function_id`
+    /// and can be called from an arguments builder.
+    fn can_use_arguments_builder(
+        &self,
+        cpp_function_id: &ItemId,
+        variants: &[&ItemWithSource<FunctionWithDesiredPath>],
+    ) -> Result<bool> {
+        let cpp_function = self
+            .data
+            .db
+            .cpp_item(cpp_function_id)?
+            .item
+            .as_function_ref()
+            .ok_or_else(|| err_msg("invalid source cpp item type"))?;
+        let optional_count = cpp_function
+            .arguments
+            .iter()
+            .rev()
+            .take_while(|arg| arg.has_default_value)
+            .count();
+        if variants.len() != optional_count + 1 {
+            return Ok(false);
+        }
+
+        let environments = self.data.db.environments();
+        let condition = self
+            .data
+            .db
+            .cpp_checks(&variants[0].source_id)?
+            .condition(environments);
+        let required_count = variants[0].item.function.arguments.len();
+        for (index, variant) in variants.iter().enumerate() {
+            let function = &variant.item.function;
+            if function.arguments.len() != required_count + index {
+                return Ok(false);
+            }
+            if !function.kind.is_ffi_wrapper()
+                || function.self_arg_kind()? != RustFunctionSelfArgKind::None
+                || function.return_type.api_type().is_ref()
+            {
+                return Ok(false);
+            }
+            for arg in &function.arguments {
+                if arg.name == "build"
+                    || arg.name == "optional_arguments"
+                    || arg.argument_type.conversion().as_callback_ref().is_some()
+                    || arguments_builder_field_type(&arg.argument_type)?.is_ref()
+                {
+                    return Ok(false);
+                }
+            }
+            // all variants must be available on the same platforms
+            // because `build()` may call any of them
+            let variant_condition = self
+                .data
+                .db
+                .cpp_checks(&variant.source_id)?
+                .condition(environments);
+            if variant_condition != condition {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Removes functions that should be called through arguments builders
+    /// from `functions`. Returns remaining functions and variants of each
+    /// function with default arguments, sorted by number of arguments.
+    #[allow(clippy::type_complexity)]
+    fn extract_arguments_builders(
+        &self,
+        functions: Vec<ItemWithSource<FunctionWithDesiredPath>>,
+    ) -> Result<(
+        Vec<ItemWithSource<FunctionWithDesiredPath>>,
+        Vec<Vec<ItemWithSource<FunctionWithDesiredPath>>>,
+    )> {
+        let mut groups = BTreeMap::<ItemId, Vec<usize>>::new();
+        for (index, function) in functions.iter().enumerate() {
+            if let Some(id) = self.function_with_default_arguments(&function.source_id)? {
+                groups.entry(id).or_default().push(index);
+            }
+        }
+
+        let mut slots = functions.into_iter().map(Some).collect_vec();
+        let mut builders = Vec::new();
+        for (cpp_function_id, mut indexes) in groups {
+            indexes
+                .sort_by_key(|&index| slots[index].as_ref().unwrap().item.function.arguments.len());
+            let variants = indexes
+                .iter()
+                .map(|&index| slots[index].as_ref().unwrap())
+                .collect_vec();
+            if self.can_use_arguments_builder(&cpp_function_id, &variants)? {
+                builders.push(
+                    indexes
+                        .iter()
+                        .map(|&index| slots[index].take().unwrap())
+                        .collect_vec(),
+                );
+            }
+        }
+        Ok((slots.into_iter().flatten().collect(), builders))
+    }
+
+    /// Adds a builder struct for a function with default arguments,
+    /// private functions for all its variants, and a public function
+    /// at `constructor_path` that creates the builder.
+    fn add_arguments_builder(
+        &mut self,
+        source_id: ItemId,
+        constructor_path: RustPath,
+        variants: Vec<ItemWithSource<FunctionWithDesiredPath>>,
+    ) -> Result<()> {
+        let builder_parent = {
+            let cpp_function = self
+                .data
+                .db
+                .source_cpp_item(&source_id)?
+                .ok_or_else(|| err_msg("source cpp item not found"))?
+                .item
+                .as_function_ref()
+                .ok_or_else(|| err_msg("invalid source cpp item type"))?;
+            if cpp_function.member.is_some() {
+                let class_path = cpp_function.class_path()?;
+                self.data
+                    .db
+                    .find_rust_items_for_cpp_path(&class_path, false)?
+                    .filter_map(|item| item.item.as_module_ref())
+                    .find(|module| module.kind.is_cpp_nested_types())
+                    .map(|module| module.path.clone())
+                    .ok_or_else(|| {
+                        format_err!(
+                            "nested types module not found for {}",
+                            class_path.to_cpp_pseudo_code()
+                        )
+                    })?
+            } else {
+                constructor_path.parent()?
+            }
+        };
+        let builder_name = format!("{}Builder", constructor_path.last().to_class_case());
+        let builder_path = self
+            .data
+            .db
+            .make_unique_rust_path(&builder_parent.join(builder_name));
+
+        let full_function = &variants
+            .last()
+            .ok_or_else(|| err_msg("no variants"))?
+            .item
+            .function;
+        let required_count = variants[0].item.function.arguments.len();
+        let mut required_arguments =
+            full_function
+                .arguments
+                .iter()
+                .map_if_ok(|arg| -> Result<_> {
+                    Ok(RustArgumentsBuilderField {
+                        name: arg.name.clone(),
+                        argument_type: arg.argument_type.api_type().clone(),
+                        field_type: arguments_builder_field_type(&arg.argument_type)?,
+                    })
+                })?;
+        let optional_arguments = required_arguments.split_off(required_count);
+        let return_type = full_function.return_type.clone();
+
+        let function_paths = variants
+            .iter()
+            .map(|variant| {
+                builder_path.join(format!("call_{}a", variant.item.function.arguments.len()))
+            })
+            .collect_vec();
+
+        let builder = RustItem::Struct(RustStruct {
+            path: builder_path.clone(),
+            kind: RustStructKind::ArgumentsBuilder(RustArgumentsBuilder {
+                required_arguments,
+                optional_arguments,
+                functions: function_paths.clone(),
+                return_type: return_type.api_type().clone(),
+            }),
+            is_public: true,
+            qt_receiver_data: None,
+        });
+        let constructor = RustItem::Function(RustFunction {
+            is_public: true,
+            is_unsafe: true,
+            path: constructor_path,
+            kind: RustFunctionKind::ArgumentsBuilderConstructor(RustArgumentsBuilderConstructor {
+                builder_path: builder_path.clone(),
+            }),
+            arguments: variants[0].item.function.arguments.clone(),
+            return_type: RustFinalType::new(
+                RustType::Common(RustCommonType {
+                    path: builder_path,
+                    generic_arguments: None,
+                }),
+                RustToFfiTypeConversion::None,
+            )?,
+        });
+        self.add_rust_item(Some(source_id.clone()), builder)?;
+        self.add_rust_item(Some(source_id), constructor)?;
+
+        for (variant, path) in variants.into_iter().zip(function_paths) {
+            let mut function = variant.item.function;
+            function.is_public = false;
+            self.add_rust_item(
+                Some(variant.source_id),
+                RustItem::Function(function.with_path(path)),
+            )?;
+        }
+        Ok(())
+    }
+
     fn finalize_functions(
         &mut self,
         grouped_functions: BTreeMap<RustPath, Vec<ItemWithSource<FunctionWithDesiredPath>>>,
     ) -> Result<()> {
         let all_strategies = RustFunctionCaptionStrategy::all();
 
+        // Names from the rename map are applied to all groups first,
+        // so that they can't be silently taken by automatically named functions.
+        let mut renamed_paths = HashSet::new();
+        let mut remaining_groups = Vec::new();
         for (_group_path, functions) in grouped_functions {
+            let mut functions_without_renames = Vec::new();
+            for function in functions {
+                if function.item.is_from_hook {
+                    functions_without_renames.push(function);
+                    continue;
+                }
+                let cpp_item = self
+                    .data
+                    .db
+                    .source_cpp_item(&function.source_id)?
+                    .ok_or_else(|| err_msg("source cpp item not found"))?
+                    .item;
+                let name = cpp_item
+                    .as_function_ref()
+                    .and_then(|f| self.rename_map.find_by_signature(f));
+                if let Some(name) = name {
+                    let path = function.item.desired_path.parent()?.join(name);
+                    if self.data.db.find_rust_item(&path).is_some() {
+                        bail!(
+                            "name from rename map for {} conflicts with existing item: {:?}",
+                            cpp_item.short_text(),
+                            path
+                        );
+                    }
+                    renamed_paths.insert(path.clone());
+                    let item = RustItem::Function(function.item.function.with_path(path));
+                    self.add_rust_item(Some(function.source_id), item)?;
+                } else {
+                    functions_without_renames.push(function);
+                }
+            }
+            remaining_groups.push(functions_without_renames);
+        }
+
+        for functions_without_renames in remaining_groups {
+            let (mut functions, builders) = if self.data.config.use_arguments_builders() {
+                self.extract_arguments_builders(functions_without_renames)?
+            } else {
+                (functions_without_renames, Vec::new())
+            };
+
+            // The builder's constructor is named as if it were
+            // the variant without optional arguments.
+            let mut builder_variants = HashMap::new();
+            for variants in builders {
+                let function = ItemWithSource::new(
+                    &variants.last().unwrap().source_id,
+                    FunctionWithDesiredPath {
+                        function: variants[0].item.function.clone(),
+                        desired_path: variants[0].item.desired_path.clone(),
+                        is_from_hook: variants[0].item.is_from_hook,
+                    },
+                );
+                builder_variants.insert(functions.len(), variants);
+                functions.push(function);
+            }
+
             let mut chosen_strategy = None;
             if functions.len() > 1 {
                 trace!("choosing caption strategy for:");
@@ -2391,15 +3321,26 @@ impl State<'_, '_> {
                 }
             }
 
-            for function in functions {
+            for (index, function) in functions.into_iter().enumerate() {
                 let path = if let Some(strategy) = &chosen_strategy {
                     function.item.apply_strategy(strategy).unwrap()
                 } else {
-                    function.item.desired_path
+                    function.item.desired_path.clone()
                 };
+                if renamed_paths.contains(&path) {
+                    bail!(
+                        "name from rename map conflicts with {}: {:?}",
+                        function.item.function.kind.short_text(),
+                        path
+                    );
+                }
                 let final_path = self.data.db.make_unique_rust_path(&path);
-                let item = RustItem::Function(function.item.function.with_path(final_path));
-                self.add_rust_item(Some(function.source_id), item)?;
+                if let Some(variants) = builder_variants.remove(&index) {
+                    self.add_arguments_builder(function.source_id, final_path, variants)?;
+                } else {
+                    let item = RustItem::Function(function.item.function.with_path(final_path));
+                    self.add_rust_item(Some(function.source_id), item)?;
+                }
             }
         }
         Ok(())
@@ -2407,9 +3348,23 @@ impl State<'_, '_> {
 }
 
 pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
+    let rename_map = if let Some(path) = data.config.rust_rename_map_path() {
+        RustRenameMap::load(path)?
+    } else {
+        RustRenameMap::default()
+    };
+    let iterator_types = data
+        .db
+        .all_cpp_items()
+        .filter_map(|item| item.item.as_function_ref())
+        .filter_map(|function| function.container_iterator_type())
+        .cloned()
+        .collect();
     let mut state = State {
         data,
         special_module_paths: HashMap::new(),
+        rename_map,
+        iterator_types,
     };
     for &module in &[
         RustSpecialModuleKind::CrateRoot,
@@ -2433,12 +3388,30 @@ pub fn run(data: &mut ProcessorData<'_>) -> Result<()> {
     }
 
     state.process_cpp_items()?;
+    state.generate_native_enums()?;
     let grouped_functions = state.process_ffi_items()?;
     state.finalize_functions(grouped_functions)?;
 
+    let cpp_items = state.data.db.cpp_items().map(|item| item.item);
+    for key in state.rename_map.stale_keys(cpp_items) {
+        warn!("rename map entry doesn't match any C++ item: {}", key);
+    }
+
     Ok(())
 }
 
+/// Returns type of the value stored in an arguments builder for
+/// an argument of type `argument_type`.
+fn arguments_builder_field_type(argument_type: &RustFinalType) -> Result<RustType> {
+    if let RustToFfiTypeConversion::ImplCastInto(conversion) = argument_type.conversion() {
+        let intermediate =
+            RustFinalType::new(argument_type.ffi_type().clone(), (**conversion).clone())?;
+        Ok(intermediate.api_type().clone())
+    } else {
+        Ok(argument_type.api_type().clone())
+    }
+}
+
 fn detect_callback_function(function: &UnnamedRustFunction) -> Option<&RustFunctionPointerType> {
     if function.arguments.len() < 3 {
         return None;
@@ -2628,92 +3601,3 @@ fn operator_function_name(operator: &CppOperator) -> Result<&'static str> {
         DeleteArray => "delete_array",
     })
 }
-
-#[allow(dead_code)]
-mod ported {
-    use itertools::Itertools;
-    use ritual_common::string_utils::CaseOperations;
-    use ritual_common::string_utils::WordIterator;
-
-    /// Mode of case conversion
-    #[derive(Clone, Copy)]
-    enum Case {
-        /// Class case: "OneTwo"
-        Class,
-        /// Snake case: "one_two"
-        Snake,
-    }
-
-    // TODO: implement removal of arbitrary prefixes (#25)
-
-    /// If `remove_qt_prefix` is true, removes "Q" or "Qt"
-    /// if it is first word of the string and not the only one word.
-    /// Also converts case of the words.
-    #[allow(clippy::collapsible_if)]
-    fn remove_qt_prefix_and_convert_case(s: &str, case: Case, remove_qt_prefix: bool) -> String {
-        let mut parts = WordIterator::new(s).collect_vec();
-        if remove_qt_prefix && parts.len() > 1 {
-            if (parts[0] == "Q" || parts[0] == "q" || parts[0] == "Qt")
-                && !parts[1].starts_with(|c: char| c.is_digit(10))
-            {
-                parts.remove(0);
-            }
-        }
-        match case {
-            Case::Snake => parts.to_snake_case(),
-            Case::Class => parts.to_class_case(),
-        }
-    }
-
-    #[test]
-    fn remove_qt_prefix_and_convert_case_test() {
-        assert_eq!(
-            remove_qt_prefix_and_convert_case(&"OneTwo".to_string(), Case::Class, false),
-            "OneTwo"
-        );
-        assert_eq!(
-            remove_qt_prefix_and_convert_case(&"OneTwo".to_string(), Case::Snake, false),
-            "one_two"
-        );
-        assert_eq!(
-            remove_qt_prefix_and_convert_case(&"OneTwo".to_string(), Case::Class, true),
-            "OneTwo"
-        );
-        assert_eq!(
-            remove_qt_prefix_and_convert_case(&"OneTwo".to_string(), Case::Snake, true),
-            "one_two"
-        );
-        assert_eq!(
-            remove_qt_prefix_and_convert_case(&"QDirIterator".to_string(), Case::Class, false),
-            "QDirIterator"
-        );
-        assert_eq!(
-            remove_qt_prefix_and_convert_case(&"QDirIterator".to_string(), Case::Snake, false),
-            "q_dir_iterator"
-        );
-        assert_eq!(
-            remove_qt_prefix_and_convert_case(&"QDirIterator".to_string(), Case::Class, true),
-            "DirIterator"
-        );
-        assert_eq!(
-            remove_qt_prefix_and_convert_case(&"QDirIterator".to_string(), Case::Snake, true),
-            "dir_iterator"
-        );
-        assert_eq!(
-            remove_qt_prefix_and_convert_case(&"Qt3DWindow".to_string(), Case::Class, false),
-            "Qt3DWindow"
-        );
-        assert_eq!(
-            remove_qt_prefix_and_convert_case(&"Qt3DWindow".to_string(), Case::Snake, false),
-            "qt_3d_window"
-        );
-        assert_eq!(
-            remove_qt_prefix_and_convert_case(&"Qt3DWindow".to_string(), Case::Class, true),
-            "Qt3DWindow"
-        );
-        assert_eq!(
-            remove_qt_prefix_and_convert_case(&"Qt3DWindow".to_string(), Case::Snake, true),
-            "qt_3d_window"
-        );
-    }
-}
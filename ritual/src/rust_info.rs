@@ -39,12 +39,60 @@ pub struct RustSizedType {
     pub cpp_path: CppPath,
 }
 
+/// One variant of a native Rust enum.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RustNativeEnumVariant {
+    pub name: String,
+    /// Corresponding value
+    pub value: i64,
+}
+
+/// Information about a native Rust enum generated in addition
+/// to the wrapper of a closed C++ enum.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RustNativeEnum {
+    /// Path to the wrapper struct.
+    pub wrapper_path: RustPath,
+    /// Values of the enum in declaration order. Values may be repeated
+    /// if the C++ enum has multiple names for the same value.
+    pub variants: Vec<RustNativeEnumVariant>,
+}
+
+/// Information about a field of an arguments builder.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RustArgumentsBuilderField {
+    /// Rust argument name.
+    pub name: String,
+    /// Type accepted by the builder's constructor or setter.
+    pub argument_type: RustType,
+    /// Type of the value stored in the builder.
+    /// Differs from `argument_type` if the argument is `impl CastInto<T>`.
+    pub field_type: RustType,
+}
+
+/// Information about a builder that collects arguments of a function
+/// with default arguments and calls the appropriate FFI wrapper.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RustArgumentsBuilder {
+    /// Arguments that must be passed to the builder's constructor.
+    pub required_arguments: Vec<RustArgumentsBuilderField>,
+    /// Arguments that can be set using builder methods.
+    pub optional_arguments: Vec<RustArgumentsBuilderField>,
+    /// Functions called by `build()`. The function at index `i`
+    /// accepts all required arguments and first `i` optional arguments.
+    pub functions: Vec<RustPath>,
+    /// Return type of `build()`.
+    pub return_type: RustType,
+}
+
 /// Information about a Rust type wrapper
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum RustStructKind {
     WrapperType(RustWrapperTypeKind),
     QtSlotWrapper(RustQtSlotWrapper), // TODO: remove
     SizedType(RustSizedType),
+    ArgumentsBuilder(RustArgumentsBuilder),
+    NativeEnum(RustNativeEnum),
 }
 
 impl RustStructKind {
@@ -86,6 +134,20 @@ impl RustStructKind {
                     false
                 }
             }
+            RustStructKind::ArgumentsBuilder(_) => {
+                if let RustStructKind::ArgumentsBuilder(_) = other {
+                    true
+                } else {
+                    false
+                }
+            }
+            RustStructKind::NativeEnum(_) => {
+                if let RustStructKind::NativeEnum(_) = other {
+                    true
+                } else {
+                    false
+                }
+            }
         }
     }
 }
@@ -145,12 +207,18 @@ pub struct RustFfiWrapperData {
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct RustSignalOrSlotGetter {}
 
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct RustArgumentsBuilderConstructor {
+    pub builder_path: RustPath,
+}
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum RustFunctionKind {
     FfiWrapper(RustFfiWrapperData),
     SignalOrSlotGetter(RustSignalOrSlotGetter),
     FfiFunction,
+    ArgumentsBuilderConstructor(RustArgumentsBuilderConstructor),
 }
 
 impl RustFunctionKind {
@@ -161,6 +229,17 @@ impl RustFunctionKind {
             }
             RustFunctionKind::SignalOrSlotGetter(_) => "SignalOrSlotGetter".to_string(),
             RustFunctionKind::FfiFunction => "FfiFunction".to_string(),
+            RustFunctionKind::ArgumentsBuilderConstructor(data) => {
+                format!("ArgumentsBuilderConstructor({})", data.builder_path.last())
+            }
+        }
+    }
+
+    pub fn is_ffi_wrapper(&self) -> bool {
+        if let RustFunctionKind::FfiWrapper(_) = self {
+            true
+        } else {
+            false
         }
     }
 
@@ -504,6 +583,9 @@ pub struct RustQtReceiverImpl {
     pub target_path: RustPath,
     pub arguments: RustType,
     pub receiver_type: RustQtReceiverType,
+    /// API types of the closure arguments accepted by a slot wrapper.
+    /// `None` for signal wrappers.
+    pub closure_arguments: Option<Vec<RustType>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -511,10 +593,28 @@ pub struct RustFlagEnumImpl {
     pub enum_path: RustPath,
 }
 
+/// `Hash` implementation based on a C++ `qHash` function.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RustHashImpl {
+    pub target_path: RustPath,
+    /// FFI function that returns the hash of a value.
+    pub ffi_function_path: RustPath,
+}
+
+/// `Display` and `Debug` implementations based on a C++ output stream operator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RustDisplayImpl {
+    pub target_path: RustPath,
+    /// FFI function that converts a value to a string.
+    pub ffi_function_path: RustPath,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum RustExtraImplKind {
     FlagEnum(RustFlagEnumImpl),
     QtReceiverImpl(RustQtReceiverImpl),
+    Hash(RustHashImpl),
+    Display(RustDisplayImpl),
 }
 
 impl RustExtraImplKind {
@@ -534,6 +634,20 @@ impl RustExtraImplKind {
                     false
                 }
             }
+            RustExtraImplKind::Hash(_) => {
+                if let RustExtraImplKind::Hash(_) = other {
+                    true
+                } else {
+                    false
+                }
+            }
+            RustExtraImplKind::Display(_) => {
+                if let RustExtraImplKind::Display(_) = other {
+                    true
+                } else {
+                    false
+                }
+            }
         }
     }
 }
@@ -727,6 +841,17 @@ impl RustItem {
                         false
                     }
                 }
+                RustFunctionKind::ArgumentsBuilderConstructor(_) => {
+                    if let RustItem::Function(other) = other {
+                        if let RustFunctionKind::ArgumentsBuilderConstructor(_) = &other.kind {
+                            true
+                        } else {
+                            false
+                        }
+                    } else {
+                        false
+                    }
+                }
             },
             RustItem::Reexport(data) => {
                 if let RustItem::Reexport(other) = other {
@@ -0,0 +1,100 @@
+//! User-provided overrides for names of generated Rust items.
+
+use crate::cpp_data::{CppItem, CppPath};
+use crate::cpp_function::CppFunction;
+use itertools::Itertools;
+use ritual_common::errors::{bail, Result};
+use ritual_common::file_utils::load_toml_table;
+use ritual_common::toml;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+
+/// Mapping of C++ items to the last parts of paths of the corresponding Rust items.
+///
+/// The map is loaded from a TOML file containing a single table.
+/// Each key is either a C++ path (e.g. `"QString::arg"`) or a signature
+/// of a C++ function (e.g. `"QPushButton::QPushButton(const QString&, QWidget*)"`).
+/// A path renames all items with that path (in case of overloaded functions,
+/// their names will still receive captions if necessary), and a signature renames
+/// a single function. Each value is the desired Rust name.
+#[derive(Debug, Default)]
+pub struct RustRenameMap {
+    entries: BTreeMap<String, String>,
+}
+
+impl RustRenameMap {
+    /// Loads the map from a TOML file.
+    pub fn load(path: &Path) -> Result<Self> {
+        let table = load_toml_table(path)?;
+        let mut entries = BTreeMap::new();
+        for (key, value) in table {
+            let name = match value {
+                toml::Value::String(name) => name,
+                _ => bail!("rename map value for {:?} is not a string", key),
+            };
+            if !is_valid_identifier(&name) {
+                bail!(
+                    "rename map value for {:?} is not an identifier: {:?}",
+                    key,
+                    name
+                );
+            }
+            entries.insert(key, name);
+        }
+        Ok(Self { entries })
+    }
+
+    /// Returns the name for items with the specified C++ path.
+    pub fn find_by_path(&self, cpp_path: &CppPath) -> Option<&str> {
+        self.entries
+            .get(&cpp_path.to_cpp_pseudo_code())
+            .map(String::as_str)
+    }
+
+    /// Returns the name for the function with the signature of `function`.
+    pub fn find_by_signature(&self, function: &CppFunction) -> Option<&str> {
+        self.entries
+            .get(&function_signature(function))
+            .map(String::as_str)
+    }
+
+    /// Returns keys that don't refer to any of `cpp_items`.
+    pub fn stale_keys<'a>(&self, cpp_items: impl Iterator<Item = &'a CppItem>) -> Vec<&str> {
+        let mut known_keys = HashSet::new();
+        for item in cpp_items {
+            if let Some(path) = item.path() {
+                known_keys.insert(path.to_cpp_pseudo_code());
+            }
+            if let Some(function) = item.as_function_ref() {
+                known_keys.insert(function_signature(function));
+            }
+        }
+        self.entries
+            .keys()
+            .filter(|key| !known_keys.contains(*key))
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+/// Returns signature of `function` as it should be written in a rename map.
+fn function_signature(function: &CppFunction) -> String {
+    let arguments = function
+        .arguments
+        .iter()
+        .map(|arg| arg.argument_type.to_cpp_pseudo_code())
+        .join(", ");
+    let is_const = function.member.as_ref().map_or(false, |info| info.is_const);
+    format!(
+        "{}({}){}",
+        function.path.to_cpp_pseudo_code(),
+        arguments,
+        if is_const { " const" } else { "" }
+    )
+}
+
+fn is_valid_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_digit(10))
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
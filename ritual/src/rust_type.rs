@@ -153,6 +153,8 @@ pub enum RustToFfiTypeConversion {
     CppBoxToPtr,
     /// `QBox<T>` to `*mut T`
     QBoxToPtr,
+    /// `cpp_core::SharedPtr<T>` to `*mut T` (`T` is a shared pointer type)
+    SharedPtrToPtr,
     /// `qt_core::flags::Flags<T>` to `c_int`
     QFlagsToUInt {
         api_type: RustType,
@@ -273,6 +275,13 @@ impl RustFinalType {
                     generic_arguments: Some(vec![target]),
                 })
             }
+            RustToFfiTypeConversion::SharedPtrToPtr => {
+                let target = ffi_type.pointer_like_to_target()?;
+                RustType::Common(RustCommonType {
+                    path: RustPath::from_good_str("cpp_core::SharedPtr"),
+                    generic_arguments: Some(vec![target]),
+                })
+            }
             RustToFfiTypeConversion::QBoxToPtr => {
                 let target = ffi_type.pointer_like_to_target()?;
                 RustType::Common(RustCommonType {
@@ -545,6 +554,7 @@ impl RustType {
                 if path == &RustPath::from_good_str("cpp_core::Ptr")
                     || path == &RustPath::from_good_str("cpp_core::Ref")
                     || path == &RustPath::from_good_str("cpp_core::CppBox")
+                    || path == &RustPath::from_good_str("cpp_core::SharedPtr")
                 {
                     let arg = &generic_arguments.as_ref().unwrap()[0];
                     return arg.caption(context, strategy);
@@ -0,0 +1,58 @@
+use crate::config::RustNamingPolicy;
+use crate::cpp_data::CppPath;
+
+#[test]
+fn naming_policy_default() {
+    let policy = RustNamingPolicy::default();
+    assert_eq!(policy.to_class_case("OneTwo"), "OneTwo");
+    assert_eq!(policy.to_snake_case("OneTwo"), "one_two");
+    assert_eq!(policy.to_class_case("QDirIterator"), "QDirIterator");
+    assert_eq!(policy.to_snake_case("QDirIterator"), "q_dir_iterator");
+    assert_eq!(policy.to_class_case("Qt3DWindow"), "Qt3DWindow");
+    assert_eq!(policy.to_snake_case("Qt3DWindow"), "qt_3d_window");
+}
+
+#[test]
+fn naming_policy_prefixes() {
+    let mut policy = RustNamingPolicy::default();
+    policy.add_prefix("Qt");
+    policy.add_prefix("Q");
+    policy.add_prefix("vtk");
+    assert_eq!(policy.to_class_case("OneTwo"), "OneTwo");
+    assert_eq!(policy.to_snake_case("OneTwo"), "one_two");
+    assert_eq!(policy.to_class_case("QDirIterator"), "DirIterator");
+    assert_eq!(policy.to_snake_case("QDirIterator"), "dir_iterator");
+    assert_eq!(policy.to_snake_case("qDebug"), "debug");
+    assert_eq!(policy.to_class_case("QtConcurrent"), "Concurrent");
+    assert_eq!(policy.to_class_case("Qt3DWindow"), "Qt3DWindow");
+    assert_eq!(policy.to_snake_case("Qt3DWindow"), "qt_3d_window");
+    assert_eq!(policy.to_class_case("Q"), "Q");
+    assert_eq!(policy.to_class_case("vtkRenderer"), "Renderer");
+    assert_eq!(policy.to_snake_case("vtkRenderer"), "renderer");
+}
+
+#[test]
+fn naming_policy_case_exceptions() {
+    let mut policy = RustNamingPolicy::default();
+    policy.add_prefix("Xy");
+    policy.add_case_exception("XML");
+    policy.add_case_exception("HTTP");
+    assert_eq!(policy.to_snake_case("XyXMLReader"), "xml_reader");
+    assert_eq!(policy.to_class_case("XyXMLReader"), "XmlReader");
+    assert_eq!(policy.to_snake_case("readXML"), "read_xml");
+    assert_eq!(policy.to_snake_case("XMLHTTPRequest"), "xml_http_request");
+}
+
+#[test]
+fn naming_policy_renames() {
+    let mut policy = RustNamingPolicy::default();
+    policy.add_rename(CppPath::from_good_str("Xy::Widget::setValue"), "set");
+    assert_eq!(
+        policy.rename(&CppPath::from_good_str("Xy::Widget::setValue")),
+        Some("set")
+    );
+    assert_eq!(
+        policy.rename(&CppPath::from_good_str("Xy::Widget::value")),
+        None
+    );
+}
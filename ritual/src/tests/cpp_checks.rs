@@ -0,0 +1,145 @@
+use crate::cpp_checks::*;
+use ritual_common::target::{Arch, Endian, Env, Family, LibraryTarget, PointerWidth, Target, OS};
+
+fn env(os: OS, family: Family, version: Option<&str>) -> LibraryTarget {
+    LibraryTarget {
+        target: Target {
+            arch: Arch::X86_64,
+            os,
+            family,
+            env: Env::Gnu,
+            pointer_width: PointerWidth::P64,
+            endian: Endian::Little,
+        },
+        cpp_library_version: version.map(ToString::to_string),
+    }
+}
+
+fn checks(items: &[(&LibraryTarget, bool)]) -> CppChecks {
+    CppChecks::new(items.iter().map(|(env, is_success)| CppChecksItem {
+        env: (*env).clone(),
+        is_success: *is_success,
+    }))
+}
+
+#[test]
+fn condition_trivial() {
+    let linux = env(OS::Linux, Family::Unix, None);
+    let windows = env(OS::Windows, Family::Windows, None);
+    let envs = vec![linux.clone(), windows.clone()];
+
+    let all = checks(&[(&linux, true), (&windows, true)]);
+    assert_eq!(all.condition(&envs), Condition::True);
+
+    let none = checks(&[(&linux, false), (&windows, false)]);
+    assert_eq!(none.condition(&envs), Condition::False);
+}
+
+#[test]
+fn condition_unchecked_env() {
+    let linux = env(OS::Linux, Family::Unix, None);
+    let windows = env(OS::Windows, Family::Windows, None);
+    let envs = vec![linux.clone(), windows.clone()];
+
+    // `windows` has no check result, so it's treated as failed.
+    let partial = checks(&[(&linux, true)]);
+    let condition = partial.condition(&envs);
+    assert_eq!(condition, Condition::Family(Family::Unix));
+    assert!(condition.eval(&linux));
+    assert!(!condition.eval(&windows));
+}
+
+#[test]
+fn condition_library_version() {
+    let v1 = env(OS::Linux, Family::Unix, Some("5.11"));
+    let v2 = env(OS::Linux, Family::Unix, Some("5.12"));
+    let v3 = env(OS::Linux, Family::Unix, Some("5.13"));
+    let envs = vec![v1.clone(), v2.clone(), v3.clone()];
+
+    let checks1 = checks(&[(&v1, false), (&v2, true), (&v3, false)]);
+    assert_eq!(
+        checks1.condition(&envs),
+        Condition::CppLibraryVersion("5.12".into())
+    );
+
+    let checks2 = checks(&[(&v1, false), (&v2, true), (&v3, true)]);
+    assert_eq!(
+        checks2.condition(&envs),
+        Condition::Or(vec![
+            Condition::CppLibraryVersion("5.12".into()),
+            Condition::CppLibraryVersion("5.13".into()),
+        ])
+    );
+}
+
+#[test]
+fn condition_platform() {
+    let linux = env(OS::Linux, Family::Unix, Some("5.12"));
+    let macos = env(OS::MacOS, Family::Unix, Some("5.12"));
+    let windows = env(OS::Windows, Family::Windows, Some("5.12"));
+    let envs = vec![linux.clone(), macos.clone(), windows.clone()];
+
+    let checks1 = checks(&[(&linux, true), (&macos, true), (&windows, false)]);
+    let condition = checks1.condition(&envs);
+    assert_eq!(condition, Condition::Family(Family::Unix));
+
+    let checks2 = checks(&[(&linux, true), (&macos, false), (&windows, true)]);
+    let condition = checks2.condition(&envs);
+    assert_eq!(
+        condition,
+        Condition::Or(vec![
+            Condition::OS(OS::Linux),
+            Condition::Family(Family::Windows),
+        ])
+    );
+    assert!(condition.eval(&linux));
+    assert!(!condition.eval(&macos));
+    assert!(condition.eval(&windows));
+}
+
+#[test]
+fn condition_mixed() {
+    let linux1 = env(OS::Linux, Family::Unix, Some("5.11"));
+    let linux2 = env(OS::Linux, Family::Unix, Some("5.12"));
+    let windows1 = env(OS::Windows, Family::Windows, Some("5.11"));
+    let windows2 = env(OS::Windows, Family::Windows, Some("5.12"));
+    let envs = vec![
+        linux1.clone(),
+        linux2.clone(),
+        windows1.clone(),
+        windows2.clone(),
+    ];
+
+    let checks = checks(&[
+        (&linux1, false),
+        (&linux2, true),
+        (&windows1, false),
+        (&windows2, false),
+    ]);
+    let condition = checks.condition(&envs);
+    assert_eq!(
+        condition,
+        Condition::And(vec![
+            Condition::Family(Family::Unix),
+            Condition::CppLibraryVersion("5.12".into()),
+        ])
+    );
+    for env in &envs {
+        assert_eq!(condition.eval(env), env == &linux2);
+    }
+}
+
+#[test]
+fn condition_indistinguishable() {
+    let linux = env(OS::Linux, Family::Unix, Some("5.12"));
+    let windows = env(OS::Windows, Family::Windows, Some("5.12"));
+    let envs = vec![linux.clone(), windows.clone()];
+
+    let checks1 = checks(&[(&linux, true), (&linux, false), (&windows, true)]);
+    let condition = checks1.condition(&envs);
+    assert_eq!(condition, Condition::Family(Family::Windows));
+    assert!(!condition.eval(&linux));
+
+    let checks2 = checks(&[(&linux, true), (&linux, false), (&windows, false)]);
+    assert_eq!(checks2.condition(&envs), Condition::False);
+}
@@ -0,0 +1,330 @@
+use crate::config::{Config, CrateProperties};
+use crate::cpp_code_generator::{all_include_directives, generate_cpp_files};
+use crate::cpp_data::*;
+use crate::cpp_ffi_data::{CppFfiItem, QtSignalWrapper};
+use crate::cpp_type::CppType;
+use crate::crate_features::CrateFeatures;
+use crate::crate_writer::generate_c_lib_template;
+use crate::tests::rust_generator::{
+    argument, class_items, free_function, with_generated_database_and_ffi_items,
+    with_generated_database_and_headers, with_generated_database_for_envs,
+};
+use ritual_common::errors::Result;
+use ritual_common::file_utils::{create_dir_all, create_file, file_to_string};
+use ritual_common::target::{current_target, LibraryTarget, OS};
+use std::path::PathBuf;
+
+fn classes(names: &[&str]) -> Vec<CppItem> {
+    names
+        .iter()
+        .flat_map(|name| class_items(name, CppVisibility::Public))
+        .collect()
+}
+
+fn signal_wrapper(crate_name: &str) -> CppFfiItem {
+    CppFfiItem::QtSignalWrapper(QtSignalWrapper {
+        signal_arguments: Vec::new(),
+        class_path: CppPath::from_good_str(&format!("ctr_{}_ffi_signal_wrapper", crate_name)),
+    })
+}
+
+/// Generates C++ wrapper files with `items_per_file` limit and
+/// returns names and contents of the files.
+fn generate(
+    crate_name: &str,
+    cpp_items: Vec<CppItem>,
+    ffi_items: Vec<CppFfiItem>,
+    items_per_file: usize,
+) -> Result<Vec<(String, String)>> {
+    generate_with_headers(crate_name, cpp_items, ffi_items, &|_| None, items_per_file)
+}
+
+/// Same as `generate`, but C++ items are declared in headers returned by `header`.
+fn generate_with_headers(
+    crate_name: &str,
+    cpp_items: Vec<CppItem>,
+    ffi_items: Vec<CppFfiItem>,
+    header: &dyn Fn(&CppItem) -> Option<String>,
+    items_per_file: usize,
+) -> Result<Vec<(String, String)>> {
+    let config = Config::new(CrateProperties::new(crate_name, "0.0.0"));
+    let env = LibraryTarget {
+        target: current_target(),
+        cpp_library_version: None,
+    };
+    with_generated_database_and_headers(
+        &config,
+        cpp_items,
+        ffi_items,
+        &[env],
+        &|_, _| true,
+        header,
+        |db, dir| {
+            let names = generate_cpp_files(
+                db,
+                dir,
+                "file",
+                "global.h",
+                &[PathBuf::from("lib1.h")],
+                &CrateFeatures::new(db, None),
+                None,
+                items_per_file,
+            )?;
+            names
+                .into_iter()
+                .map(|name| {
+                    let content = file_to_string(dir.join(&name))?;
+                    Ok((name, content))
+                })
+                .collect()
+        },
+    )
+}
+
+fn file_names(files: &[(String, String)]) -> Vec<&str> {
+    files.iter().map(|(name, _)| name.as_str()).collect()
+}
+
+/// Returns names of classes whose constructors are called in `content`.
+fn classes_in_file<'a>(content: &str, names: &[&'a str]) -> Vec<&'a str> {
+    names
+        .iter()
+        .cloned()
+        .filter(|name| content.contains(&format!("new {}(", name)))
+        .collect()
+}
+
+#[test]
+fn one_file_per_class() {
+    let names = ["A", "B", "C"];
+    let files = generate("crate1", classes(&names), Vec::new(), 3).unwrap();
+    assert_eq!(
+        file_names(&files),
+        vec!["file1.cpp", "file2.cpp", "file3.cpp"]
+    );
+    for (index, (_, content)) in files.iter().enumerate() {
+        assert!(content.starts_with("#include \"global.h\"\n"));
+        assert!(content.contains("extern \"C\" {"));
+        assert!(!content.contains(".moc\""));
+        assert_eq!(classes_in_file(content, &names), vec![names[index]]);
+    }
+}
+
+#[test]
+fn classes_are_not_split_between_files() {
+    let names = ["A", "B", "C"];
+    let files = generate("crate1", classes(&names), Vec::new(), 5).unwrap();
+    assert_eq!(file_names(&files), vec!["file1.cpp", "file2.cpp"]);
+    assert_eq!(classes_in_file(&files[0].1, &names), vec!["A", "B"]);
+    assert_eq!(classes_in_file(&files[1].1, &names), vec!["C"]);
+}
+
+#[test]
+fn zero_items_per_file() {
+    let names = ["A", "B"];
+    let files = generate("crate1", classes(&names), Vec::new(), 0).unwrap();
+    assert_eq!(file_names(&files), vec!["file1.cpp", "file2.cpp"]);
+}
+
+#[test]
+fn file_without_items() {
+    let files = generate("crate1", Vec::new(), Vec::new(), 3).unwrap();
+    assert_eq!(file_names(&files), vec!["file1.cpp"]);
+    assert!(files[0].1.starts_with("#include \"global.h\"\n"));
+}
+
+#[test]
+fn moc_include() {
+    let names = ["A", "B"];
+    let files = generate("crate1", classes(&names), vec![signal_wrapper("crate1")], 3).unwrap();
+    assert_eq!(file_names(&files), vec!["file1.cpp", "file2.cpp"]);
+    for (name, content) in &files {
+        assert!(content.starts_with("#include \"global.h\"\n"));
+        let moc_include = format!("#include \"{}.moc\"", name.trim_end_matches(".cpp"));
+        let has_wrapper = content.contains("class ctr_crate1_ffi_signal_wrapper");
+        assert_eq!(content.contains(&moc_include), has_wrapper);
+    }
+    assert!(files[0].1.contains("class ctr_crate1_ffi_signal_wrapper"));
+    assert_eq!(classes_in_file(&files[0].1, &names), vec!["A"]);
+}
+
+#[test]
+fn no_moc_include_for_moqt() {
+    let files = generate(
+        "moqt_crate1",
+        Vec::new(),
+        vec![signal_wrapper("moqt_crate1")],
+        3,
+    )
+    .unwrap();
+    assert_eq!(file_names(&files), vec!["file1.cpp"]);
+    assert!(files[0]
+        .1
+        .contains("class ctr_moqt_crate1_ffi_signal_wrapper"));
+    assert!(!files[0].1.contains(".moc\""));
+}
+
+#[test]
+fn target_headers_before_conditions() {
+    let dir = tempdir::TempDir::new("test_cpp_code_generator").unwrap();
+    generate_c_lib_template("lib1", dir.path(), "global.h").unwrap();
+    let global_header = file_to_string(dir.path().join("global.h")).unwrap();
+    let position = |text: &str| global_header.find(text).unwrap();
+    // These headers define macros used in conditions of the generated code,
+    // and the global header is included before the library headers.
+    assert!(position("#include <cstdlib>") > 0);
+    assert!(position("#ifdef __APPLE__") < position("#include <TargetConditionals.h>"));
+    assert!(!global_header.contains("lib1.h"));
+
+    let current_env = LibraryTarget {
+        target: current_target(),
+        cpp_library_version: None,
+    };
+    let mut other_env = current_env.clone();
+    other_env.target.os = if current_env.target.os == OS::Windows {
+        OS::Linux
+    } else {
+        OS::Windows
+    };
+    let envs = [current_env.clone(), other_env];
+    let config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    let content = with_generated_database_for_envs(
+        &config,
+        classes(&["A"]),
+        Vec::new(),
+        &envs,
+        &|_, env| env == &current_env,
+        |db, dir| {
+            let names = generate_cpp_files(
+                db,
+                dir,
+                "file",
+                "global.h",
+                &[PathBuf::from("lib1.h")],
+                &CrateFeatures::new(db, None),
+                None,
+                3,
+            )?;
+            file_to_string(dir.join(&names[0]))
+        },
+    )
+    .unwrap();
+    let first_condition = content.find("\n#if ").unwrap();
+    assert!(content.starts_with("#include \"global.h\"\n#include \"lib1.h\"\n"));
+    assert!(content.find("#include \"lib1.h\"").unwrap() < first_condition);
+    assert!(content[first_condition..].contains("new A("));
+}
+
+#[test]
+fn excluded_extra_files() {
+    let dir = tempdir::TempDir::new("test_cpp_code_generator").unwrap();
+    let extra_dir = dir.path().join("c_lib/extra");
+    create_dir_all(&extra_dir).unwrap();
+    create_file(extra_dir.join("a.h")).unwrap();
+    create_file(extra_dir.join("b.h")).unwrap();
+
+    let mut config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    config.set_crate_template_path(dir.path());
+    config.add_include_directive("lib1.h");
+    let mut directives = all_include_directives(&config).unwrap();
+    directives.sort();
+    assert_eq!(
+        directives,
+        vec![
+            PathBuf::from("extra/a.h"),
+            PathBuf::from("extra/b.h"),
+            PathBuf::from("lib1.h"),
+        ]
+    );
+
+    config.add_excluded_extra_file("a.h");
+    let directives = all_include_directives(&config).unwrap();
+    assert_eq!(
+        directives,
+        vec![PathBuf::from("lib1.h"), PathBuf::from("extra/b.h")]
+    );
+}
+
+#[test]
+fn std_hash_function() {
+    let mut config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    config.set_use_std_hash_specializations(true);
+    let content = with_generated_database_and_ffi_items(
+        &config,
+        classes(&["A"]),
+        Vec::new(),
+        &|_| true,
+        |db, dir| {
+            let names = generate_cpp_files(
+                db,
+                dir,
+                "file",
+                "global.h",
+                &[PathBuf::from("lib1.h")],
+                &CrateFeatures::new(db, None),
+                None,
+                3,
+            )?;
+            file_to_string(dir.join(&names[0]))
+        },
+    )
+    .unwrap();
+    assert!(content.contains("size_t ctr_crate1_ffi_A_std_hash(A const * value)"));
+    assert!(content.contains("return ritual::std_hash(*value);"));
+}
+
+/// Declares items of each class and free function in a header named after it.
+fn header_of_item(item: &CppItem) -> Option<String> {
+    let name = &item.path()?.items()[0].name;
+    Some(format!("{}.h", name.to_lowercase()))
+}
+
+#[test]
+fn only_required_headers() {
+    let mut items = classes(&["A", "B"]);
+    let value_arg = argument("value", CppType::Class(CppPath::from_good_str("B")), false);
+    items.push(CppItem::Function(free_function("f", vec![value_arg])));
+    let files = generate_with_headers("crate1", items, Vec::new(), &header_of_item, 1).unwrap();
+    assert_eq!(
+        file_names(&files),
+        vec!["file1.cpp", "file2.cpp", "file3.cpp"]
+    );
+    assert!(files[0]
+        .1
+        .starts_with("#include \"global.h\"\n#include \"a.h\"\nextern"));
+    assert!(files[1]
+        .1
+        .starts_with("#include \"global.h\"\n#include \"b.h\"\nextern"));
+    assert!(files[2]
+        .1
+        .starts_with("#include \"global.h\"\n#include \"b.h\"\n#include \"f.h\"\nextern"));
+    for (_, content) in &files {
+        assert!(!content.contains("lib1.h"));
+    }
+}
+
+#[test]
+fn unknown_header_uses_include_directives() {
+    let mut items = classes(&["A", "B"]);
+    let value_arg = argument("value", CppType::Class(CppPath::from_good_str("B")), false);
+    items.push(CppItem::Function(free_function("f", vec![value_arg])));
+    let header = |item: &CppItem| {
+        if item.path()?.items()[0].name == "B" {
+            None
+        } else {
+            header_of_item(item)
+        }
+    };
+    let files = generate_with_headers("crate1", items, Vec::new(), &header, 1).unwrap();
+    assert_eq!(file_names(&files).len(), 3);
+    assert!(files[0]
+        .1
+        .starts_with("#include \"global.h\"\n#include \"a.h\"\nextern"));
+    assert!(files[1]
+        .1
+        .starts_with("#include \"global.h\"\n#include \"lib1.h\"\nextern"));
+    // `f` is declared in a known header, but its argument type is not.
+    assert!(files[2]
+        .1
+        .starts_with("#include \"global.h\"\n#include \"lib1.h\"\nextern"));
+}
@@ -1,6 +1,8 @@
 use crate::cpp_data::*;
 use crate::cpp_ffi_data::*;
-use crate::cpp_ffi_generator::NewFfiFunctionKind;
+use crate::cpp_ffi_generator::{
+    generate_ffi_methods_for_method, FfiNameProvider, NewFfiFunctionKind,
+};
 use crate::cpp_function::*;
 use crate::cpp_type::*;
 use itertools::Itertools;
@@ -629,3 +631,78 @@ fn short_text1() {
         "protected int Class1::method1(int arg1, double arg2 = …) const"
     );
 }
+
+#[test]
+fn stream_output_operator() {
+    let stream_type = CppType::new_reference(
+        false,
+        CppType::Class(CppPath::from_good_str("std::ostream")),
+    );
+    let mut method = empty_regular_method();
+    method.path = CppPath::from_item(CppPathItem {
+        name: "operator<<".into(),
+        template_arguments: None,
+    });
+    method.operator = Some(CppOperator::BitwiseLeftShift);
+    method.return_type = stream_type.clone();
+    method.arguments = vec![
+        CppFunctionArgument {
+            argument_type: stream_type,
+            name: "stream".to_string(),
+            has_default_value: false,
+        },
+        CppFunctionArgument {
+            argument_type: CppType::new_reference(
+                true,
+                CppType::Class(CppPath::from_good_str("Class1")),
+            ),
+            name: "value".to_string(),
+            has_default_value: false,
+        },
+    ];
+    assert!(method.is_stream_output_operator());
+
+    let mut name_provider = FfiNameProvider::testing();
+    let ffi_items = generate_ffi_methods_for_method(&method, &[], &mut name_provider).unwrap();
+    assert_eq!(ffi_items.len(), 1);
+    let ffi_function = ffi_items[0].as_function_ref().unwrap();
+    assert_eq!(ffi_function.kind, CppFfiFunctionKind::StreamToString);
+    assert_eq!(ffi_function.arguments.len(), 1);
+    assert_eq!(
+        ffi_function.arguments[0].meaning,
+        CppFfiArgumentMeaning::Argument(1)
+    );
+    assert_eq!(
+        ffi_function.return_type.ffi_type(),
+        &CppType::new_pointer(true, CppType::BuiltInNumeric(CppBuiltInNumericType::Char))
+    );
+
+    method.arguments[1].argument_type = CppType::BuiltInNumeric(CppBuiltInNumericType::Int);
+    assert!(!method.is_stream_output_operator());
+}
+
+#[test]
+fn push_function() {
+    let mut method = empty_regular_method();
+    method.path = CppPath::from_good_str("QByteArray::append");
+    method.member = Some(empty_membership());
+    method.arguments = vec![CppFunctionArgument {
+        argument_type: CppType::new_reference(
+            true,
+            CppType::BuiltInNumeric(CppBuiltInNumericType::Int),
+        ),
+        name: "value".to_string(),
+        has_default_value: false,
+    }];
+    assert!(method.is_push_function());
+
+    method.path = CppPath::from_good_str("QByteArray::push_back");
+    assert!(method.is_push_function());
+
+    method.member.as_mut().unwrap().is_const = true;
+    assert!(!method.is_push_function());
+    method.member.as_mut().unwrap().is_const = false;
+
+    method.path = CppPath::from_good_str("QByteArray::insert");
+    assert!(!method.is_push_function());
+}
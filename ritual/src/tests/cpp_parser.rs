@@ -17,6 +17,7 @@ struct ParserCppData {
     methods: Vec<CppFunction>,
     enum_values: Vec<CppEnumValue>,
     namespaces: Vec<CppPath>,
+    headers: Vec<Option<String>>,
 }
 
 fn run_parser(code: &'static str) -> ParserCppData {
@@ -78,6 +79,15 @@ fn run_parser(code: &'static str) -> ParserCppData {
             .filter_map(|item| item.item.as_namespace_ref())
             .map(|ns| ns.path.clone())
             .collect(),
+        headers: database
+            .cpp_items()
+            .map(|item| {
+                database
+                    .cpp_header(&item.id)
+                    .unwrap()
+                    .map(|header| header.include_path.clone())
+            })
+            .collect(),
     }
 }
 
@@ -526,6 +536,7 @@ fn advanced_class_methods() {
         data.methods[6].return_type,
         CppType::BuiltInNumeric(CppBuiltInNumericType::Bool),
     );
+    assert!(!data.methods[6].is_explicit());
 
     let func6_item = CppPathItem {
         name: "func6".to_string(),
@@ -757,7 +768,10 @@ fn simple_enum() {
     );
     assert_eq!(data.types.len(), 1);
     assert_eq!(data.types[0].path, CppPath::from_good_str("Enum1"));
-    assert_eq!(data.types[0].kind, CppTypeDeclarationKind::Enum);
+    assert_eq!(
+        data.types[0].kind,
+        CppTypeDeclarationKind::Enum { is_scoped: false }
+    );
     assert_eq!(
         data.enum_values,
         vec![
@@ -788,7 +802,10 @@ fn simple_enum2() {
     );
     assert_eq!(data.types.len(), 1);
     assert_eq!(data.types[0].path, CppPath::from_good_str("ns1::Enum1"));
-    assert_eq!(data.types[0].kind, CppTypeDeclarationKind::Enum);
+    assert_eq!(
+        data.types[0].kind,
+        CppTypeDeclarationKind::Enum { is_scoped: false }
+    );
     assert_eq!(
         data.enum_values,
         vec![
@@ -809,6 +826,65 @@ fn simple_enum2() {
     assert_eq!(data.namespaces, vec![CppPath::from_good_str("ns1")]);
 }
 
+#[test]
+fn explicit_conversion_operator() {
+    let data = run_parser(
+        "
+        class MyClass {
+        public:
+            explicit operator bool() const;
+            operator double() const;
+        };
+        ",
+    );
+    assert_eq!(data.methods.len(), 2);
+    assert_eq!(
+        data.methods[0].operator,
+        Some(CppOperator::Conversion(CppType::BuiltInNumeric(
+            CppBuiltInNumericType::Bool
+        )))
+    );
+    assert!(data.methods[0].is_explicit());
+    assert_eq!(
+        data.methods[1].operator,
+        Some(CppOperator::Conversion(CppType::BuiltInNumeric(
+            CppBuiltInNumericType::Double
+        )))
+    );
+    assert!(!data.methods[1].is_explicit());
+}
+
+#[test]
+fn scoped_enum() {
+    let data = run_parser(
+        "
+        enum class Enum1 : int {
+            Good,
+            Bad
+        };
+        ",
+    );
+    assert_eq!(data.types.len(), 1);
+    assert_eq!(data.types[0].path, CppPath::from_good_str("Enum1"));
+    assert_eq!(
+        data.types[0].kind,
+        CppTypeDeclarationKind::Enum { is_scoped: true }
+    );
+    assert_eq!(
+        data.enum_values,
+        vec![
+            CppEnumValue {
+                value: 0,
+                path: CppPath::from_good_str("Enum1::Good"),
+            },
+            CppEnumValue {
+                value: 1,
+                path: CppPath::from_good_str("Enum1::Bad"),
+            },
+        ]
+    );
+}
+
 #[test]
 fn template_instantiation() {
     let data = run_parser(
@@ -1220,3 +1296,12 @@ fn elaborated_enum_type() {
         }
     );
 }
+
+#[test]
+fn headers() {
+    let data = run_parser("class Class1 { public: int f(); }; enum Enum1 { Value1 };");
+    assert_eq!(data.headers.len(), 4);
+    for header in data.headers {
+        assert_eq!(header.as_deref(), Some("myfakelib.h"));
+    }
+}
@@ -2,8 +2,8 @@ use crate::cpp_data::{CppPath, CppPathItem};
 use crate::cpp_ffi_data::CppToFfiTypeConversion;
 use crate::cpp_ffi_generator::ffi_type;
 use crate::cpp_type::{
-    CppBuiltInNumericType, CppFunctionPointerType, CppSpecificNumericType,
-    CppSpecificNumericTypeKind, CppTemplateParameter, CppType, CppTypeRole,
+    is_shared_ptr, is_unique_ptr, CppBuiltInNumericType, CppFunctionPointerType,
+    CppSpecificNumericType, CppSpecificNumericTypeKind, CppTemplateParameter, CppType, CppTypeRole,
 };
 
 fn assert_type_to_ffi_unchanged(t: &CppType) {
@@ -324,6 +324,63 @@ fn qflags() {
     }
 }
 
+#[test]
+fn unique_ptr() {
+    let target = CppType::Class(CppPath::from_good_str("QObject"));
+    let path = CppPath::from_items(vec![
+        CppPathItem::from_good_str("std"),
+        CppPathItem {
+            name: "unique_ptr".into(),
+            template_arguments: Some(vec![target.clone()]),
+        },
+    ]);
+    assert!(is_unique_ptr(&path));
+    assert!(!is_shared_ptr(&path));
+    let type1 = CppType::Class(path);
+
+    for role in &[CppTypeRole::NotReturnType, CppTypeRole::ReturnType] {
+        let ffi_type = ffi_type(&type1, *role).unwrap();
+        assert_eq!(ffi_type.original_type(), &type1);
+        assert_eq!(
+            ffi_type.ffi_type(),
+            &CppType::new_pointer(false, target.clone())
+        );
+        assert_eq!(ffi_type.ffi_type().to_cpp_code(None).unwrap(), "QObject *");
+        assert_eq!(
+            ffi_type.conversion(),
+            &CppToFfiTypeConversion::UniquePtrToPointer
+        );
+    }
+}
+
+#[test]
+fn shared_ptr() {
+    let args = Some(vec![CppType::Class(CppPath::from_good_str("QObject"))]);
+    let qt_path = CppPath::from_item(CppPathItem {
+        name: "QSharedPointer".into(),
+        template_arguments: args.clone(),
+    });
+    assert!(is_shared_ptr(&qt_path));
+    assert!(!is_unique_ptr(&qt_path));
+    let std_path = CppPath::from_items(vec![
+        CppPathItem::from_good_str("std"),
+        CppPathItem {
+            name: "shared_ptr".into(),
+            template_arguments: args,
+        },
+    ]);
+    assert!(is_shared_ptr(&std_path));
+    assert!(!is_shared_ptr(&CppPath::from_good_str("QSharedPointer")));
+
+    let ffi_type = ffi_type(&CppType::Class(std_path), CppTypeRole::ReturnType).unwrap();
+    assert_eq!(
+        ffi_type.conversion(),
+        &CppToFfiTypeConversion::ValueToPointer {
+            is_ffi_const: false
+        }
+    );
+}
+
 #[test]
 fn template_parameter() {
     let type1 = CppType::new_pointer(
@@ -0,0 +1,178 @@
+use crate::config::{Config, CrateDependencyKind, CrateDependencySource, CrateProperties};
+use crate::cpp_data::*;
+use crate::cpp_type::*;
+use crate::crate_features::CrateFeatures;
+use crate::crate_writer::{features_table, reserved_feature_names};
+use crate::rust_code_generator;
+use crate::rust_type::RustPath;
+use crate::tests::rust_generator::{argument, free_function, with_generated_database};
+use ritual_common::file_utils::file_to_string;
+use ritual_common::toml;
+use std::collections::BTreeSet;
+
+fn string_set(values: &[&str]) -> BTreeSet<String> {
+    values.iter().map(|s| s.to_string()).collect()
+}
+
+fn string_array(values: &[&str]) -> toml::Value {
+    toml::Value::Array(
+        values
+            .iter()
+            .map(|s| toml::Value::String(s.to_string()))
+            .collect(),
+    )
+}
+
+#[test]
+fn features_table_without_crate_features() {
+    let mut properties = CrateProperties::new("crate1", "0.0.0");
+    properties
+        .add_dependency(
+            "dep1",
+            CrateDependencyKind::Ritual,
+            CrateDependencySource::CratesIo {
+                version: "0.1.0".into(),
+            },
+        )
+        .unwrap();
+    let config = Config::new(properties);
+    let table = features_table(&config, &BTreeSet::new());
+    assert_eq!(table.len(), 2);
+    assert_eq!(
+        table["ritual_rustdoc"],
+        string_array(&["dep1/ritual_rustdoc"])
+    );
+    assert_eq!(
+        table["ritual_rustdoc_nightly"],
+        string_array(&["dep1/ritual_rustdoc_nightly"])
+    );
+}
+
+#[test]
+fn features_table_with_crate_features() {
+    let config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    let table = features_table(&config, &string_set(&["f1", "f2"]));
+    assert_eq!(table["f1"], string_array(&[]));
+    assert_eq!(table["f2"], string_array(&[]));
+    assert_eq!(table["default"], string_array(&["f1", "f2"]));
+}
+
+/// Returns items of two namespaces. Function `ns2::f` uses
+/// a type from `ns1`.
+fn namespace_items() -> Vec<CppItem> {
+    let mut cpp_items = Vec::new();
+    for name in &["ns1", "ns2"] {
+        cpp_items.push(CppItem::Namespace(CppNamespace {
+            path: CppPath::from_good_str(name),
+        }));
+    }
+    for name in &["ns1::A", "ns2::B"] {
+        cpp_items.push(CppItem::Type(CppTypeDeclaration {
+            path: CppPath::from_good_str(name),
+            kind: CppTypeDeclarationKind::Class,
+        }));
+    }
+    let class_ptr =
+        |name| CppType::new_pointer(false, CppType::Class(CppPath::from_good_str(name)));
+    cpp_items.push(CppItem::Function(free_function(
+        "ns2::f",
+        vec![argument("a", class_ptr("ns1::A"), false)],
+    )));
+    cpp_items
+}
+
+fn config_with_namespace_features() -> Config {
+    let mut config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    config.set_crate_feature_hook(|item| {
+        let path = if let Some(path) = item.path() {
+            path
+        } else {
+            return Ok(None);
+        };
+        let namespace = &path.items()[0].name;
+        if path.items().len() > 1 {
+            Ok(Some(namespace.clone()))
+        } else {
+            Ok(None)
+        }
+    });
+    config
+}
+
+#[test]
+fn features_from_hook() {
+    let config = config_with_namespace_features();
+    with_generated_database(&config, namespace_items(), &|_| true, |db, _| {
+        let features = CrateFeatures::new(db, config.crate_feature_hook());
+        assert_eq!(
+            features.all_features(&BTreeSet::new())?,
+            string_set(&["ns1", "ns2"])
+        );
+
+        let item_features = |path: &str| {
+            let item = db.find_rust_item(&RustPath::from_good_str(path)).unwrap();
+            features.rust_item_features(&item)
+        };
+        assert_eq!(item_features("crate1::ns1::A")?, string_set(&["ns1"]));
+        assert_eq!(item_features("crate1::ns2::B")?, string_set(&["ns2"]));
+        // `f` also requires the feature of its argument type.
+        assert_eq!(
+            item_features("crate1::ns2::f")?,
+            string_set(&["ns1", "ns2"])
+        );
+
+        let without_hook = CrateFeatures::new(db, None);
+        assert!(without_hook.all_features(&BTreeSet::new())?.is_empty());
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn features_in_generated_code() {
+    let config = config_with_namespace_features();
+    with_generated_database(&config, namespace_items(), &|_| true, |db, dir| {
+        let features = CrateFeatures::new(db, config.crate_feature_hook());
+        rust_code_generator::generate(db, &features, dir, None::<&str>)?;
+        let code = file_to_string(dir.join("ns2.rs"))?;
+        assert!(code.contains("#[cfg(feature = \"ns2\")]\n#[repr(C)]\npub struct B {"));
+        assert!(
+            code.contains("#[cfg(all(feature = \"ns1\", feature = \"ns2\"))]\npub unsafe  fn f(")
+        );
+        Ok(())
+    })
+    .unwrap();
+}
+
+#[test]
+fn invalid_feature_names() {
+    for &name in &[
+        "",
+        "default",
+        "ritual_rustdoc",
+        "cpp_core",
+        "-ns",
+        "ns 1",
+        "ns.1",
+    ] {
+        let mut config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+        config.set_crate_feature_hook(move |item| {
+            if item.path().map_or(false, |path| path.items().len() > 1) {
+                Ok(Some(name.to_string()))
+            } else {
+                Ok(None)
+            }
+        });
+        with_generated_database(&config, namespace_items(), &|_| true, |db, _| {
+            let features = CrateFeatures::new(db, config.crate_feature_hook());
+            let err = features
+                .all_features(&reserved_feature_names(&config))
+                .unwrap_err();
+            assert!(err
+                .to_string()
+                .contains(&format!("{:?} for type ns1::A", name)));
+            Ok(())
+        })
+        .unwrap();
+    }
+}
@@ -1,7 +1,13 @@
 #![allow(clippy::cognitive_complexity)]
 
+mod config;
+mod cpp_checks;
+mod cpp_code_generator;
 mod cpp_ffi_data;
 mod cpp_method;
 mod cpp_operator;
 mod cpp_parser;
 mod cpp_type;
+mod crate_features;
+mod rust_generator;
+mod rust_rename_map;
@@ -0,0 +1,1001 @@
+use crate::config::{Config, CrateProperties};
+use crate::cpp_checks::CppChecksItem;
+use crate::cpp_data::*;
+use crate::cpp_ffi_data::CppFfiItem;
+use crate::cpp_ffi_generator;
+use crate::cpp_function::*;
+use crate::cpp_omitting_arguments;
+use crate::cpp_type::*;
+use crate::crate_features::CrateFeatures;
+use crate::database::{CppHeaderItem, DatabaseClient};
+use crate::processor::ProcessorData;
+use crate::rust_code_generator;
+use crate::rust_generator;
+use crate::rust_info::*;
+use crate::rust_type::{RustCommonType, RustPath, RustType};
+use crate::tests::cpp_method::{empty_membership, empty_regular_method};
+use crate::workspace::Workspace;
+use ritual_common::errors::Result;
+use ritual_common::file_utils::{create_dir, create_file, file_to_string, os_str_to_str, read_dir};
+use ritual_common::target::{current_target, LibraryTarget, PointerWidth};
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub fn argument(
+    name: &str,
+    argument_type: CppType,
+    has_default_value: bool,
+) -> CppFunctionArgument {
+    CppFunctionArgument {
+        name: name.to_string(),
+        argument_type,
+        has_default_value,
+    }
+}
+
+pub fn int() -> CppType {
+    CppType::BuiltInNumeric(CppBuiltInNumericType::Int)
+}
+
+pub fn double() -> CppType {
+    CppType::BuiltInNumeric(CppBuiltInNumericType::Double)
+}
+
+pub fn free_function(path: &str, arguments: Vec<CppFunctionArgument>) -> CppFunction {
+    let mut function = empty_regular_method();
+    function.path = CppPath::from_good_str(path);
+    function.arguments = arguments;
+    function
+}
+
+/// Runs FFI and Rust generators on `cpp_items`, assuming that FFI items
+/// compile successfully if `is_success` returns true for their source C++ item,
+/// and calls `f` with the resulting database and a temporary directory.
+pub fn with_generated_database<T>(
+    config: &Config,
+    cpp_items: Vec<CppItem>,
+    is_success: &dyn Fn(&CppItem) -> bool,
+    f: impl FnOnce(&DatabaseClient, &Path) -> Result<T>,
+) -> Result<T> {
+    with_generated_database_and_ffi_items(config, cpp_items, Vec::new(), is_success, f)
+}
+
+/// Same as `with_generated_database`, but also adds `ffi_items` (e.g. Qt signal wrappers)
+/// to the database before running the Rust generator. These items always compile successfully.
+pub fn with_generated_database_and_ffi_items<T>(
+    config: &Config,
+    cpp_items: Vec<CppItem>,
+    ffi_items: Vec<CppFfiItem>,
+    is_success: &dyn Fn(&CppItem) -> bool,
+    f: impl FnOnce(&DatabaseClient, &Path) -> Result<T>,
+) -> Result<T> {
+    let env = LibraryTarget {
+        target: current_target(),
+        cpp_library_version: None,
+    };
+    with_generated_database_for_envs(
+        config,
+        cpp_items,
+        ffi_items,
+        &[env],
+        &|item, _| is_success(item),
+        f,
+    )
+}
+
+/// Same as `with_generated_database_and_ffi_items`, but checks FFI items
+/// in each of `envs`. Success of an item in an environment is determined by `is_success`.
+pub fn with_generated_database_for_envs<T>(
+    config: &Config,
+    cpp_items: Vec<CppItem>,
+    ffi_items: Vec<CppFfiItem>,
+    envs: &[LibraryTarget],
+    is_success: &dyn Fn(&CppItem, &LibraryTarget) -> bool,
+    f: impl FnOnce(&DatabaseClient, &Path) -> Result<T>,
+) -> Result<T> {
+    with_generated_database_and_headers(
+        config,
+        cpp_items,
+        ffi_items,
+        envs,
+        is_success,
+        &|_| None,
+        f,
+    )
+}
+
+/// Same as `with_generated_database_for_envs`, but also records the header
+/// returned by `header` for each of `cpp_items`.
+pub fn with_generated_database_and_headers<T>(
+    config: &Config,
+    cpp_items: Vec<CppItem>,
+    ffi_items: Vec<CppFfiItem>,
+    envs: &[LibraryTarget],
+    is_success: &dyn Fn(&CppItem, &LibraryTarget) -> bool,
+    header: &dyn Fn(&CppItem) -> Option<String>,
+    f: impl FnOnce(&DatabaseClient, &Path) -> Result<T>,
+) -> Result<T> {
+    let dir = tempdir::TempDir::new("test_rust_generator").unwrap();
+    let mut workspace = Workspace::new(dir.path().into())?;
+    let crate_name = config.crate_properties().name().to_string();
+    let mut db = workspace.get_database_client(&crate_name, &[], false, true)?;
+    for env in envs {
+        db.add_environment(env.clone());
+    }
+    for item in cpp_items {
+        let include_path = header(&item);
+        if let Some(id) = db.add_cpp_item_without_hook(None, item)? {
+            if let Some(include_path) = include_path {
+                db.add_cpp_header_item(id, CppHeaderItem { include_path });
+            }
+        }
+    }
+
+    let mut data = ProcessorData {
+        workspace: &mut workspace,
+        config,
+        db: &mut db,
+    };
+    cpp_omitting_arguments::run(&mut data)?;
+    cpp_ffi_generator::run(&mut data)?;
+    for item in ffi_items {
+        data.db.add_ffi_item(None, item)?;
+    }
+    let mut checks = Vec::new();
+    for ffi_item in data.db.ffi_items() {
+        let source_item = data.db.source_cpp_item(&ffi_item.id)?;
+        for env in envs {
+            let result = source_item
+                .as_ref()
+                .map_or(true, |item| is_success(item.item, env));
+            checks.push((ffi_item.id.clone(), env, result));
+        }
+    }
+    for (id, env, is_success) in checks {
+        data.db.add_cpp_checks_item(
+            id,
+            CppChecksItem {
+                env: env.clone(),
+                is_success,
+            },
+        );
+    }
+    rust_generator::run(&mut data)?;
+    f(&db, dir.path())
+}
+
+pub fn run_generator(config: &Config, cpp_items: Vec<CppItem>) -> Result<Vec<RustItem>> {
+    run_generator_with_checks(config, cpp_items, &|_| true)
+}
+
+pub fn run_generator_with_checks(
+    config: &Config,
+    cpp_items: Vec<CppItem>,
+    is_success: &dyn Fn(&CppItem) -> bool,
+) -> Result<Vec<RustItem>> {
+    with_generated_database(config, cpp_items, is_success, |db, _| {
+        Ok(db.rust_items().map(|item| item.item.clone()).collect())
+    })
+}
+
+/// Returns content of generated Rust files by file name.
+pub fn generate_files(
+    config: &Config,
+    cpp_items: Vec<CppItem>,
+) -> Result<BTreeMap<String, String>> {
+    let env = LibraryTarget {
+        target: current_target(),
+        cpp_library_version: None,
+    };
+    generate_files_for_envs(config, cpp_items, &[env], &|_, _| true)
+}
+
+/// Same as `generate_files`, but checks FFI items in each of `envs`
+/// like `with_generated_database_for_envs`.
+pub fn generate_files_for_envs(
+    config: &Config,
+    cpp_items: Vec<CppItem>,
+    envs: &[LibraryTarget],
+    is_success: &dyn Fn(&CppItem, &LibraryTarget) -> bool,
+) -> Result<BTreeMap<String, String>> {
+    with_generated_database_for_envs(
+        config,
+        cpp_items,
+        Vec::new(),
+        envs,
+        is_success,
+        |db, dir| {
+            let src_path = dir.join("src");
+            create_dir(&src_path)?;
+            let features = CrateFeatures::new(db, None);
+            rust_code_generator::generate(db, &features, &src_path, None::<PathBuf>)?;
+            let mut files = BTreeMap::new();
+            for entry in read_dir(&src_path)? {
+                let path = entry?.path();
+                let name = os_str_to_str(path.file_name().unwrap())?.to_string();
+                files.insert(name, file_to_string(&path)?);
+            }
+            Ok(files)
+        },
+    )
+}
+
+/// Returns Rust code generated for the crate root.
+pub fn generate_code(config: &Config, cpp_items: Vec<CppItem>) -> Result<String> {
+    Ok(generate_files(config, cpp_items)?.remove("lib.rs").unwrap())
+}
+
+pub fn config_with_rename_map(dir: &tempdir::TempDir, content: &str) -> Config {
+    let path = dir.path().join("renames.toml");
+    {
+        let mut file = create_file(&path).unwrap();
+        write!(file, "{}", content).unwrap();
+    }
+    let mut config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    config.set_rust_rename_map_path(path);
+    config
+}
+
+pub fn public_functions(items: &[RustItem]) -> Vec<&RustFunction> {
+    items
+        .iter()
+        .filter_map(|item| item.as_function_ref())
+        .filter(|function| match function.kind {
+            RustFunctionKind::FfiFunction => false,
+            _ => function.is_public,
+        })
+        .collect()
+}
+
+pub fn function_names(items: &[RustItem]) -> Vec<String> {
+    let mut names = public_functions(items)
+        .into_iter()
+        .map(|function| function.path.full_name(None))
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+fn overloads() -> Vec<CppItem> {
+    vec![
+        CppItem::Function(free_function("f", vec![argument("x", int(), false)])),
+        CppItem::Function(free_function("f", vec![argument("x", double(), false)])),
+    ]
+}
+
+#[test]
+fn rename_map_signature() {
+    let dir = tempdir::TempDir::new("test_rust_generator").unwrap();
+    let config = config_with_rename_map(&dir, "\"f(int)\" = \"from_int\"");
+    let items = run_generator(&config, overloads()).unwrap();
+    assert_eq!(
+        function_names(&items),
+        vec!["::crate1::f", "::crate1::from_int"]
+    );
+}
+
+#[test]
+fn rename_map_conflict_with_other_function() {
+    let dir = tempdir::TempDir::new("test_rust_generator").unwrap();
+    let config = config_with_rename_map(&dir, "\"f(int)\" = \"g\"");
+    let mut cpp_items = overloads();
+    cpp_items.push(CppItem::Function(free_function("g", vec![])));
+    let err = run_generator(&config, cpp_items).unwrap_err();
+    assert!(err.to_string().contains("conflicts"));
+}
+
+#[test]
+fn path_hook_takes_precedence_over_rename_map() {
+    let dir = tempdir::TempDir::new("test_rust_generator").unwrap();
+    let mut config = config_with_rename_map(&dir, "\"f(int)\" = \"from_int\"");
+    config.set_rust_path_hook(|path, name_type, _data| {
+        if let NameType::ApiFunction(_) = name_type {
+            if path == &CppPath::from_good_str("f") {
+                return Ok(Some(RustPath::from_good_str("crate1::hooked")));
+            }
+        }
+        Ok(None)
+    });
+    let items = run_generator(&config, overloads()).unwrap();
+    let names = function_names(&items);
+    assert!(!names.iter().any(|name| name.contains("from_int")));
+    assert!(names
+        .iter()
+        .all(|name| name.starts_with("::crate1::hooked")));
+}
+
+fn function_with_default_arguments() -> CppFunction {
+    free_function(
+        "f",
+        vec![
+            argument("x", int(), false),
+            argument("y", int(), true),
+            argument("z", double(), true),
+        ],
+    )
+}
+
+#[test]
+fn arguments_builder() {
+    let mut config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    config.set_use_arguments_builders(true);
+    let cpp_items = vec![CppItem::Function(function_with_default_arguments())];
+    let items = run_generator(&config, cpp_items.clone()).unwrap();
+    assert_eq!(function_names(&items), vec!["::crate1::f"]);
+    let builder = items
+        .iter()
+        .filter_map(|item| item.as_struct_ref())
+        .find(|item| item.path == RustPath::from_good_str("crate1::FBuilder"))
+        .unwrap();
+    let builder = if let RustStructKind::ArgumentsBuilder(builder) = &builder.kind {
+        builder
+    } else {
+        panic!("invalid struct kind: {:?}", builder.kind);
+    };
+    let names = |fields: &[RustArgumentsBuilderField]| {
+        fields.iter().map(|f| f.name.clone()).collect::<Vec<_>>()
+    };
+    assert_eq!(names(&builder.required_arguments), vec!["x"]);
+    assert_eq!(names(&builder.optional_arguments), vec!["y", "z"]);
+    assert_eq!(
+        builder.functions,
+        vec![
+            RustPath::from_good_str("crate1::FBuilder::call_1a"),
+            RustPath::from_good_str("crate1::FBuilder::call_2a"),
+            RustPath::from_good_str("crate1::FBuilder::call_3a"),
+        ]
+    );
+
+    let code = generate_code(&config, cpp_items).unwrap();
+    assert!(code.contains("pub struct FBuilder<T = ()> {"));
+    assert!(code.contains("fn f(x: c_int) -> FBuilder {"));
+    assert!(code.contains("impl FBuilder<()> {"));
+    assert!(code.contains("pub unsafe fn y(self, y: c_int) -> FBuilder<(c_int,)> {"));
+    assert!(code.contains("<FBuilder>::call_1a(self.x)"));
+    assert!(code.contains("impl FBuilder<(c_int,)> {"));
+    assert!(code.contains("pub unsafe fn z(self, z: c_double) -> FBuilder<(c_int, c_double,)> {"));
+    assert!(code.contains("<FBuilder>::call_2a(self.x, self.optional_arguments.0)"));
+    assert!(code.contains("impl FBuilder<(c_int, c_double,)> {"));
+    assert!(code.contains(
+        "<FBuilder>::call_3a(self.x, self.optional_arguments.0, self.optional_arguments.1)"
+    ));
+    assert!(!code.contains("panic!"));
+}
+
+#[test]
+fn arguments_builder_with_overload() {
+    let mut config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    config.set_use_arguments_builders(true);
+    let cpp_items = vec![
+        CppItem::Function(function_with_default_arguments()),
+        CppItem::Function(free_function("f", vec![argument("x", double(), false)])),
+    ];
+    let items = run_generator(&config, cpp_items).unwrap();
+    assert_eq!(
+        function_names(&items),
+        vec!["::crate1::f_double", "::crate1::f_int"]
+    );
+    let constructor = public_functions(&items)
+        .into_iter()
+        .find(|function| function.path == RustPath::from_good_str("crate1::f_int"))
+        .unwrap();
+    if let RustFunctionKind::ArgumentsBuilderConstructor(data) = &constructor.kind {
+        assert_eq!(
+            data.builder_path,
+            RustPath::from_good_str("crate1::FIntBuilder")
+        );
+    } else {
+        panic!("invalid function kind: {:?}", constructor.kind);
+    }
+}
+
+#[test]
+fn no_arguments_builder_if_disabled() {
+    let config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    let cpp_items = vec![CppItem::Function(function_with_default_arguments())];
+    let items = run_generator(&config, cpp_items).unwrap();
+    assert_eq!(
+        function_names(&items),
+        vec!["::crate1::f_1a", "::crate1::f_2a", "::crate1::f_3a"]
+    );
+    assert!(!items.iter().any(|item| item.as_struct_ref().is_some()));
+}
+
+fn enum_items(name: &str, is_scoped: bool, values: &[(&str, i64)]) -> Vec<CppItem> {
+    let mut items = vec![CppItem::Type(CppTypeDeclaration {
+        path: CppPath::from_good_str(name),
+        kind: CppTypeDeclarationKind::Enum { is_scoped },
+    })];
+    for &(value_name, value) in values {
+        items.push(CppItem::EnumValue(CppEnumValue {
+            path: CppPath::from_good_str(&format!("{}::{}", name, value_name)),
+            value,
+        }));
+    }
+    items
+}
+
+#[test]
+fn native_enum() {
+    let config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    let cpp_items = enum_items("Color", true, &[("Red", 0), ("Green", 1), ("Verde", 1)]);
+    let code = generate_code(&config, cpp_items).unwrap();
+    assert!(code.contains("pub enum ColorEnum {\n    Red,\n    Green,\n}"));
+    assert!(code.contains("impl From<ColorEnum> for Color {"));
+    assert!(code.contains("ColorEnum::Red => Self::from(0 as ::std::os::raw::c_int),"));
+    assert!(code.contains("ColorEnum::Green => Self::from(1 as ::std::os::raw::c_int),"));
+    assert!(code.contains("impl ::std::convert::TryFrom<Color> for ColorEnum {"));
+    assert!(code.contains("0 => Ok(ColorEnum::Red),"));
+    assert!(code.contains("1 => Ok(ColorEnum::Green),"));
+    assert!(code.contains("impl ::std::fmt::Display for ColorEnum {"));
+    assert!(code.contains("ColorEnum::Green => \"Green\","));
+    assert!(!code.contains("\"Verde\","));
+    assert!(code.contains("impl ::std::str::FromStr for ColorEnum {"));
+    assert!(code.contains("\"Green\" => Ok(ColorEnum::Green),"));
+    assert!(code.contains("\"Verde\" => Ok(ColorEnum::Green),"));
+}
+
+#[test]
+fn native_enum_for_closed_enum() {
+    let cpp_items = enum_items("Color", false, &[("Red", 0), ("Green", 1)]);
+    let config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    let code = generate_code(&config, cpp_items.clone()).unwrap();
+    assert!(code.contains("pub struct Color("));
+    assert!(!code.contains("ColorEnum"));
+
+    let mut config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    config.add_closed_enum(CppPath::from_good_str("Color"));
+    let code = generate_code(&config, cpp_items).unwrap();
+    assert!(code.contains("pub enum ColorEnum {"));
+}
+
+#[test]
+fn no_native_enum_for_large_values() {
+    let config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    let cpp_items = enum_items("Color", true, &[("Red", 0), ("Large", 1 << 40)]);
+    let code = generate_code(&config, cpp_items).unwrap();
+    assert!(code.contains("pub struct Color("));
+    assert!(!code.contains("ColorEnum"));
+
+    let cpp_items = enum_items(
+        "Color",
+        true,
+        &[("Min", -(1 << 31)), ("Max", (1 << 31) - 1)],
+    );
+    let code = generate_code(&config, cpp_items).unwrap();
+    assert!(code.contains("pub enum ColorEnum {"));
+}
+
+fn constructor(
+    class_path: &CppPath,
+    arguments: Vec<CppFunctionArgument>,
+    visibility: CppVisibility,
+) -> CppFunction {
+    let mut function = empty_regular_method();
+    function.path = class_path.join(class_path.last().clone());
+    function.member = Some(CppFunctionMemberData {
+        kind: CppFunctionKind::Constructor,
+        visibility,
+        ..empty_membership()
+    });
+    function.arguments = arguments;
+    function
+}
+
+pub fn class_items(name: &str, visibility: CppVisibility) -> Vec<CppItem> {
+    let class_path = CppPath::from_good_str(name);
+    let mut destructor = empty_regular_method();
+    destructor.path = class_path.join(CppPathItem::from_good_str(&format!(
+        "~{}",
+        class_path.last().name
+    )));
+    destructor.member = Some(CppFunctionMemberData {
+        kind: CppFunctionKind::Destructor,
+        ..empty_membership()
+    });
+    let copy_argument = argument(
+        "other",
+        CppType::new_reference(true, CppType::Class(class_path.clone())),
+        false,
+    );
+    vec![
+        CppItem::Type(CppTypeDeclaration {
+            path: class_path.clone(),
+            kind: CppTypeDeclarationKind::Class,
+        }),
+        CppItem::Function(constructor(&class_path, vec![], visibility.clone())),
+        CppItem::Function(constructor(&class_path, vec![copy_argument], visibility)),
+        CppItem::Function(destructor),
+    ]
+}
+
+fn trait_impl_names(items: &[RustItem]) -> Vec<String> {
+    let mut names = items
+        .iter()
+        .filter_map(|item| item.as_trait_impl_ref())
+        .map(|item| {
+            format!(
+                "{} for {}",
+                item.trait_type.path.full_name(None),
+                rust_code_generator::rust_type_to_code(&item.target_type, None)
+            )
+        })
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+#[test]
+fn clone_and_default_from_constructors() {
+    let config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    let items = run_generator(&config, class_items("A", CppVisibility::Public)).unwrap();
+    assert_eq!(
+        trait_impl_names(&items),
+        vec![
+            "::cpp_core::CppClone for ::crate1::A",
+            "::cpp_core::CppDefault for ::crate1::A",
+            "::cpp_core::CppDeletable for ::crate1::A",
+        ]
+    );
+}
+
+#[test]
+fn no_clone_and_default_from_private_constructors() {
+    let config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    let items = run_generator(&config, class_items("A", CppVisibility::Private)).unwrap();
+    assert_eq!(
+        trait_impl_names(&items),
+        vec!["::cpp_core::CppDeletable for ::crate1::A"]
+    );
+}
+
+#[test]
+fn no_clone_and_default_from_deleted_constructors() {
+    let config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    // Deleted constructors are parsed as usual but fail to compile.
+    let is_success = |item: &CppItem| {
+        item.as_function_ref()
+            .map_or(true, |function| !function.is_constructor())
+    };
+    let items = run_generator_with_checks(
+        &config,
+        class_items("A", CppVisibility::Public),
+        &is_success,
+    )
+    .unwrap();
+    assert_eq!(
+        trait_impl_names(&items),
+        vec!["::cpp_core::CppDeletable for ::crate1::A"]
+    );
+}
+
+fn conversion_operator(class_name: &str, declaration_code: &str) -> CppFunction {
+    let mut function = empty_regular_method();
+    function.path = CppPath::from_good_str(&format!("{}::operator double", class_name));
+    function.member = Some(CppFunctionMemberData {
+        is_const: true,
+        ..empty_membership()
+    });
+    function.return_type = double();
+    function.operator = Some(CppOperator::Conversion(double()));
+    function.declaration_code = Some(declaration_code.to_string());
+    function
+}
+
+#[test]
+fn implicit_conversion_operator() {
+    let config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    let mut cpp_items = class_items("A", CppVisibility::Public);
+    cpp_items.push(CppItem::Function(conversion_operator(
+        "A",
+        "operator double() const",
+    )));
+    let items = run_generator(&config, cpp_items).unwrap();
+    assert!(trait_impl_names(&items)
+        .contains(&"::std::convert::From for ::std::os::raw::c_double".to_string()));
+    let from_impl = items
+        .iter()
+        .filter_map(|item| item.as_trait_impl_ref())
+        .find(|item| item.trait_type.path == RustPath::from_good_str("std::convert::From"))
+        .unwrap();
+    assert_eq!(
+        from_impl.trait_type.generic_arguments.as_ref().unwrap()[0],
+        RustType::new_reference(
+            true,
+            RustType::Common(RustCommonType {
+                path: RustPath::from_good_str("crate1::A"),
+                generic_arguments: None,
+            })
+        )
+    );
+    assert!(function_names(&items).contains(&"::crate1::A::to_double".to_string()));
+}
+
+#[test]
+fn explicit_conversion_operator() {
+    let config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    let mut cpp_items = class_items("A", CppVisibility::Public);
+    cpp_items.push(CppItem::Function(conversion_operator(
+        "A",
+        "explicit operator double() const",
+    )));
+    let items = run_generator(&config, cpp_items).unwrap();
+    assert!(!trait_impl_names(&items)
+        .iter()
+        .any(|name| name.starts_with("::std::convert::From")));
+    assert!(function_names(&items).contains(&"::crate1::A::to_double".to_string()));
+}
+
+#[test]
+fn imports() {
+    let config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    let mut cpp_items = Vec::new();
+    for name in &["ns1", "ns2"] {
+        cpp_items.push(CppItem::Namespace(CppNamespace {
+            path: CppPath::from_good_str(name),
+        }));
+    }
+    for name in &["ns1::A", "ns1::B", "ns1::String", "ns2::A"] {
+        cpp_items.push(CppItem::Type(CppTypeDeclaration {
+            path: CppPath::from_good_str(name),
+            kind: CppTypeDeclarationKind::Class,
+        }));
+    }
+    let class_ptr =
+        |name| CppType::new_pointer(false, CppType::Class(CppPath::from_good_str(name)));
+    cpp_items.push(CppItem::Function(free_function(
+        "ns2::f",
+        vec![
+            argument("a1", class_ptr("ns1::A"), false),
+            argument("a2", class_ptr("ns2::A"), false),
+            argument("b", class_ptr("ns1::B"), false),
+            argument("s", class_ptr("ns1::String"), false),
+        ],
+    )));
+    let files = generate_files(&config, cpp_items).unwrap();
+    let code = &files["ns2.rs"];
+    assert!(code.contains("use crate::ns1::B;"));
+    // `A` is declared in the module itself.
+    assert!(code.contains("use crate::ns1::A as Ns1A;"));
+    assert!(code.contains("a1: impl CastInto<Ptr<Ns1A>>, a2: impl CastInto<Ptr<A>>"));
+    // Importing `String` would shadow the prelude type.
+    assert!(!code.contains("use crate::ns1::String"));
+    assert!(code.contains("s: impl CastInto<Ptr<crate::ns1::String>>"));
+}
+
+#[test]
+fn no_imports_for_items_with_ffi_source() {
+    let mut config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    config.set_use_arguments_builders(true);
+    let mut cpp_items = vec![CppItem::Namespace(CppNamespace {
+        path: CppPath::from_good_str("ns1"),
+    })];
+    cpp_items.extend(class_items("ns1::C", CppVisibility::Public));
+    cpp_items.push(CppItem::Function(constructor(
+        &CppPath::from_good_str("ns1::C"),
+        vec![argument("x", int(), false), argument("y", int(), true)],
+        CppVisibility::Public,
+    )));
+    let files = generate_files(&config, cpp_items).unwrap();
+    let code = &files["ns1.rs"];
+    // The builder is generated from FFI items, so it may have
+    // a `cfg` condition and is always referred to by full path.
+    assert!(code.contains("use crate::ns1::C;"));
+    assert!(!code.contains("use crate::ns1::c::New1ABuilder"));
+    assert!(code.contains("fn new_1a(x: c_int) -> crate::ns1::c::New1ABuilder {"));
+}
+
+fn stream_operator(class_name: &str) -> CppFunction {
+    let stream_type = CppType::new_reference(
+        false,
+        CppType::Class(CppPath::from_good_str("std::ostream")),
+    );
+    let value_type =
+        CppType::new_reference(true, CppType::Class(CppPath::from_good_str(class_name)));
+    let mut function = empty_regular_method();
+    function.path = CppPath::from_item(CppPathItem {
+        name: "operator<<".to_string(),
+        template_arguments: None,
+    });
+    function.arguments = vec![
+        argument("stream", stream_type.clone(), false),
+        argument("value", value_type, false),
+    ];
+    function.operator = Some(CppOperator::BitwiseLeftShift);
+    function.return_type = stream_type;
+    function
+}
+
+fn count_debug_impls(code: &str) -> usize {
+    code.matches("impl ::std::fmt::Debug for").count()
+}
+
+#[test]
+fn fallback_debug_impl() {
+    let config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    let code = generate_code(&config, class_items("A", CppVisibility::Public)).unwrap();
+    assert_eq!(count_debug_impls(&code), 1);
+    assert!(code.contains("write!(f, \"A({:p})\", self)"));
+}
+
+#[test]
+fn debug_impl_from_stream_operator() {
+    let config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    let mut cpp_items = class_items("A", CppVisibility::Public);
+    cpp_items.push(CppItem::Function(stream_operator("A")));
+    let code = generate_code(&config, cpp_items).unwrap();
+    assert!(code.contains("impl ::std::fmt::Display for"));
+    assert_eq!(count_debug_impls(&code), 1);
+    assert!(!code.contains("({:p})"));
+}
+
+#[test]
+fn conditional_debug_impl_from_stream_operator() {
+    let config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    let mut cpp_items = class_items("A", CppVisibility::Public);
+    cpp_items.push(CppItem::Function(stream_operator("A")));
+    let current_env = LibraryTarget {
+        target: current_target(),
+        cpp_library_version: None,
+    };
+    let mut other_env = current_env.clone();
+    other_env.target.pointer_width = if current_env.target.pointer_width == PointerWidth::P64 {
+        PointerWidth::P32
+    } else {
+        PointerWidth::P64
+    };
+    let is_success = |item: &CppItem, env: &LibraryTarget| {
+        let is_stream_operator = item
+            .as_function_ref()
+            .map_or(false, |f| f.is_stream_output_operator());
+        !is_stream_operator || env == &current_env
+    };
+    let files = generate_files_for_envs(
+        &config,
+        cpp_items,
+        &[current_env.clone(), other_env],
+        &is_success,
+    )
+    .unwrap();
+    let code = &files["lib.rs"];
+    // The fallback implementation is only used if the `Display` implementation is disabled.
+    assert_eq!(count_debug_impls(code), 2);
+    assert!(code.contains("#[cfg(not(all(any("));
+    assert!(code.contains("write!(f, \"A({:p})\", self)"));
+}
+
+fn hash_impl_targets(items: &[RustItem]) -> Vec<String> {
+    items
+        .iter()
+        .filter_map(|item| item.as_extra_impl_ref())
+        .filter_map(|item| {
+            if let RustExtraImplKind::Hash(data) = &item.kind {
+                Some(data.target_path.full_name(None))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn std_hash_impl() {
+    let cpp_items = class_items("A", CppVisibility::Public);
+    let config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    let items = run_generator(&config, cpp_items.clone()).unwrap();
+    assert!(hash_impl_targets(&items).is_empty());
+
+    let mut config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    config.set_use_std_hash_specializations(true);
+    let items = run_generator(&config, cpp_items.clone()).unwrap();
+    assert_eq!(hash_impl_targets(&items), vec!["::crate1::A"]);
+
+    // The class doesn't have a `std::hash` specialization.
+    let items = run_generator_with_checks(&config, cpp_items, &|item| item.as_type_ref().is_none())
+        .unwrap();
+    assert!(hash_impl_targets(&items).is_empty());
+}
+
+fn template_path(namespace: Option<&str>, name: &str, argument: CppType) -> CppPath {
+    let mut items = Vec::new();
+    if let Some(namespace) = namespace {
+        items.push(CppPathItem::from_good_str(namespace));
+    }
+    items.push(CppPathItem {
+        name: name.to_string(),
+        template_arguments: Some(vec![argument]),
+    });
+    CppPath::from_items(items)
+}
+
+#[test]
+fn unique_ptr_argument_requires_deletable_type() {
+    let config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    let unique_ptr = CppType::Class(template_path(
+        Some("std"),
+        "unique_ptr",
+        CppType::Class(CppPath::from_good_str("A")),
+    ));
+    let mut cpp_items = class_items("A", CppVisibility::Public);
+    cpp_items.push(CppItem::Function(free_function(
+        "take",
+        vec![argument("value", unique_ptr, false)],
+    )));
+
+    let items = run_generator(&config, cpp_items.clone()).unwrap();
+    assert!(function_names(&items).contains(&"::crate1::take".to_string()));
+
+    // `CppBox<A>` can't be created if the destructor is not available.
+    let is_success = |item: &CppItem| {
+        item.as_function_ref()
+            .map_or(true, |function| !function.is_destructor())
+    };
+    let items = run_generator_with_checks(&config, cpp_items, &is_success).unwrap();
+    assert!(!function_names(&items).contains(&"::crate1::take".to_string()));
+}
+
+#[test]
+fn shared_ptr_of_qobject_get_returns_ptr() {
+    let config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    let q_object = CppType::Class(CppPath::from_good_str("QObject"));
+    let shared_ptr_path = template_path(None, "QSharedPointer", q_object.clone());
+    let mut data = empty_regular_method();
+    data.path = shared_ptr_path.join(CppPathItem::from_good_str("data"));
+    data.member = Some(CppFunctionMemberData {
+        is_const: true,
+        ..empty_membership()
+    });
+    data.return_type = CppType::new_pointer(false, q_object);
+
+    let mut cpp_items = class_items("QObject", CppVisibility::Public);
+    cpp_items.push(CppItem::Type(CppTypeDeclaration {
+        path: shared_ptr_path,
+        kind: CppTypeDeclarationKind::Class,
+    }));
+    cpp_items.push(CppItem::Function(data));
+
+    let items = run_generator(&config, cpp_items).unwrap();
+    let get_impl = items
+        .iter()
+        .filter_map(|item| item.as_trait_impl_ref())
+        .find(|item| item.trait_type.path == RustPath::from_good_str("cpp_core::ops::Get"))
+        .expect("Get impl not found");
+    // `SharedPtr` can only deref through `Get` with `Ptr` output.
+    let output = rust_code_generator::rust_type_to_code(&get_impl.associated_types[0].value, None);
+    assert!(output.starts_with("::cpp_core::Ptr<"));
+}
+
+fn const_method(
+    class_name: &str,
+    name: &str,
+    arguments: Vec<CppFunctionArgument>,
+    return_type: CppType,
+) -> CppFunction {
+    let mut function = empty_regular_method();
+    function.path = CppPath::from_good_str(class_name).join(CppPathItem::from_good_str(name));
+    function.member = Some(CppFunctionMemberData {
+        is_const: true,
+        ..empty_membership()
+    });
+    function.arguments = arguments;
+    function.return_type = return_type;
+    function
+}
+
+fn count_trait_impls(items: &[RustItem], name: &str) -> usize {
+    trait_impl_names(items)
+        .iter()
+        .filter(|item| item.as_str() == name)
+        .count()
+}
+
+#[test]
+fn at_is_used_for_indexed_access() {
+    let config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    let at = const_method("A", "at", vec![argument("i", int(), false)], int());
+    let mut subscript = const_method("A", "operator[]", vec![argument("i", int(), false)], int());
+    subscript.operator = Some(CppOperator::Subscript);
+    assert!(at.is_indexed_access());
+    assert!(!subscript.is_indexed_access());
+
+    // The result doesn't depend on the order of functions.
+    for functions in &[
+        vec![at.clone(), subscript.clone()],
+        vec![subscript.clone(), at.clone()],
+    ] {
+        let mut cpp_items = class_items("A", CppVisibility::Public);
+        cpp_items.extend(functions.iter().cloned().map(CppItem::Function));
+        let items = run_generator(&config, cpp_items).unwrap();
+        assert_eq!(
+            count_trait_impls(&items, "::cpp_core::vector_ops::At for ::crate1::A"),
+            1
+        );
+    }
+
+    let mut cpp_items = class_items("A", CppVisibility::Public);
+    cpp_items.push(CppItem::Function(subscript));
+    let items = run_generator(&config, cpp_items).unwrap();
+    assert_eq!(
+        count_trait_impls(&items, "::cpp_core::vector_ops::At for ::crate1::A"),
+        0
+    );
+}
+
+#[test]
+fn key_value_for_iterator_types() {
+    let config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    let cursor = CppType::Class(CppPath::from_good_str("Cursor"));
+    let mut cpp_items = class_items("Map", CppVisibility::Public);
+    cpp_items.extend(class_items("Cursor", CppVisibility::Public));
+    cpp_items.extend(class_items("FakeIterator", CppVisibility::Public));
+    cpp_items.push(CppItem::Function(const_method(
+        "Map",
+        "begin",
+        vec![],
+        cursor,
+    )));
+    for class_name in &["Cursor", "FakeIterator"] {
+        cpp_items.push(CppItem::Function(const_method(
+            class_name,
+            "key",
+            vec![],
+            int(),
+        )));
+        cpp_items.push(CppItem::Function(const_method(
+            class_name,
+            "value",
+            vec![],
+            double(),
+        )));
+    }
+    let items = run_generator(&config, cpp_items).unwrap();
+    // `Cursor` is returned by `Map::begin()`, so it's an iterator despite its name.
+    assert_eq!(
+        count_trait_impls(&items, "::cpp_core::ops::Key for ::crate1::Cursor"),
+        1
+    );
+    assert_eq!(
+        count_trait_impls(&items, "::cpp_core::ops::Value for ::crate1::Cursor"),
+        1
+    );
+    assert_eq!(
+        count_trait_impls(&items, "::cpp_core::ops::Key for ::crate1::FakeIterator"),
+        0
+    );
+}
+
+#[test]
+fn naming_policy_for_receivers_and_field_accessors() {
+    let mut config = Config::new(CrateProperties::new("crate1", "0.0.0"));
+    config.rust_naming_policy_mut().add_case_exception("XML");
+    let mut signal = const_method("A", "XMLLoaded", vec![], CppType::Void);
+    signal.member.as_mut().unwrap().is_signal = true;
+    let mut slot = const_method("A", "readXML", vec![], CppType::Void);
+    slot.member.as_mut().unwrap().is_slot = true;
+    let mut cpp_items = class_items("A", CppVisibility::Public);
+    cpp_items.push(CppItem::Function(signal));
+    cpp_items.push(CppItem::Function(slot));
+    cpp_items.push(CppItem::ClassField(CppClassField {
+        path: CppPath::from_good_str("A::XMLData"),
+        field_type: int(),
+        visibility: CppVisibility::Public,
+        is_static: false,
+    }));
+    let items = run_generator(&config, cpp_items).unwrap();
+    let names = function_names(&items);
+    for name in &[
+        "::crate1::A::xml_loaded",
+        "::crate1::A::read_xml",
+        "::crate1::A::slot_read_xml",
+        "::crate1::A::xml_data",
+        "::crate1::A::set_xml_data",
+    ] {
+        assert!(
+            names.contains(&name.to_string()),
+            "{} not in {:?}",
+            name,
+            names
+        );
+    }
+}
@@ -0,0 +1,91 @@
+use crate::cpp_data::*;
+use crate::cpp_function::*;
+use crate::cpp_type::*;
+use crate::rust_rename_map::RustRenameMap;
+use crate::tests::cpp_method::empty_regular_method;
+use ritual_common::file_utils::create_file;
+use std::io::Write;
+
+fn load_map(content: &str) -> RustRenameMap {
+    let dir = tempdir::TempDir::new("test_rust_rename_map").unwrap();
+    let path = dir.path().join("renames.toml");
+    {
+        let mut file = create_file(&path).unwrap();
+        write!(file, "{}", content).unwrap();
+    }
+    RustRenameMap::load(&path).unwrap()
+}
+
+fn class_type(name: &str) -> CppType {
+    CppType::Class(CppPath::from_good_str(name))
+}
+
+fn button_constructor() -> CppFunction {
+    let mut function = empty_regular_method();
+    function.path = CppPath::from_good_str("QPushButton::QPushButton");
+    function.arguments = vec![
+        CppFunctionArgument {
+            name: "text".to_string(),
+            argument_type: CppType::new_reference(true, class_type("QString")),
+            has_default_value: false,
+        },
+        CppFunctionArgument {
+            name: "parent".to_string(),
+            argument_type: CppType::new_pointer(false, class_type("QWidget")),
+            has_default_value: true,
+        },
+    ];
+    function
+}
+
+#[test]
+fn find() {
+    let map = load_map(
+        r#"
+"QPushButton::QPushButton(const QString&, QWidget*)" = "from_text_and_parent"
+"QAbstractSlider::setValue" = "set"
+"#,
+    );
+    assert_eq!(
+        map.find_by_signature(&button_constructor()),
+        Some("from_text_and_parent")
+    );
+    assert_eq!(
+        map.find_by_path(&CppPath::from_good_str("QAbstractSlider::setValue")),
+        Some("set")
+    );
+    assert_eq!(
+        map.find_by_path(&CppPath::from_good_str("QPushButton::QPushButton")),
+        None
+    );
+
+    let mut other_constructor = button_constructor();
+    other_constructor.arguments.pop();
+    assert_eq!(map.find_by_signature(&other_constructor), None);
+}
+
+#[test]
+fn stale_keys() {
+    let map = load_map(
+        r#"
+"QPushButton::QPushButton(const QString&, QWidget*)" = "from_text_and_parent"
+"QAbstractSlider::setValue" = "set"
+"#,
+    );
+    let items = vec![CppItem::Function(button_constructor())];
+    assert_eq!(
+        map.stale_keys(items.iter()),
+        vec!["QAbstractSlider::setValue"]
+    );
+}
+
+#[test]
+fn invalid_name() {
+    let dir = tempdir::TempDir::new("test_rust_rename_map").unwrap();
+    let path = dir.path().join("renames.toml");
+    {
+        let mut file = create_file(&path).unwrap();
+        write!(file, "\"QAbstractSlider::setValue\" = \"set value\"").unwrap();
+    }
+    assert!(RustRenameMap::load(&path).is_err());
+}
@@ -4,6 +4,13 @@
 // This header includes system headers and declares functions
 // required by all regular headers of the library.
 
+// Conditions in the generated code use `__GLIBC__` (defined by libc headers)
+// and `TARGET_OS_IPHONE`, so these headers must be included first.
+#include <cstdlib>
+#ifdef __APPLE__
+    #include <TargetConditionals.h>
+#endif
+
 // for fixed size integer types
 #include <stdint.h>
 
@@ -14,8 +21,12 @@
 // placement new statements require this
 #include <new>
 
-// for exit()
-#include <cstdlib>
+// for converting objects to strings using output stream operators
+#include <sstream>
+#include <string>
+
+// for hashing objects using std::hash specializations
+#include <functional>
 
 #ifdef _WIN32
     #define RITUAL_EXPORT __declspec(dllexport)
@@ -38,6 +49,24 @@ namespace ritual {
         x->~T();
     }
 
+    // Formats `value` using its output stream operator.
+    // The returned string remains valid until the next call
+    // for the same type in the same thread.
+    template<typename T>
+    const char* to_string(const T& value) {
+        static thread_local std::string result;
+        std::ostringstream stream;
+        stream << value;
+        result = stream.str();
+        return result.c_str();
+    }
+
+    // Hashes `value` using its `std::hash` specialization.
+    template<typename T>
+    size_t std_hash(const T& value) {
+        return std::hash<T>()(value);
+    }
+
     template<class T>
     class Callback {
     public:
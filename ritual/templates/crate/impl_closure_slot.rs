@@ -0,0 +1,9 @@
+{condition_attribute}
+impl<T: FnMut({closure_args}) + 'static> {qt_core}::ClosureSlot<T> for {type_path} {{
+    unsafe fn with_closure(parent: ::cpp_core::Ptr<{qt_core}::QObject>, f: T) -> {qt_core}::QBox<Self> {{
+        Self::new(parent, f)
+    }}
+}}
+
+{condition_attribute}
+impl {qt_core}::SlotArguments<{type_path}> for {args} {{}}
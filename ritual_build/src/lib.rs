@@ -26,12 +26,15 @@
 
 use log::info;
 pub use ritual_common as common;
-use ritual_common::cpp_build_config::{CppBuildConfig, CppBuildPaths, CppLibraryType};
-use ritual_common::cpp_lib_builder::{BuildType, CMakeConfigData, CppLibBuilder};
+use ritual_common::cpp_build_config::{
+    CppBuildConfig, CppBuildConfigData, CppBuildPaths, CppLibraryType,
+};
+use ritual_common::cpp_lib_builder::{BuildType, CMakeConfigData, CMakeVar, CppLibBuilder};
 use ritual_common::errors::{bail, err_msg, FancyUnwrap, Result, ResultExt};
 use ritual_common::file_utils::{create_file, file_to_string, load_json, path_to_str};
 use ritual_common::target::{current_target, LibraryTarget, OS};
 use ritual_common::{env_var_names, BuildScriptData};
+use std::collections::BTreeMap;
 use std::env;
 use std::io::Write;
 use std::path::PathBuf;
@@ -59,6 +62,25 @@ fn build_script_data() -> Result<BuildScriptData> {
     load_json(manifest_dir()?.join("build_script_data.json"))
 }
 
+/// Returns true if the Cargo feature `name` of the crate being built is enabled.
+fn is_feature_enabled(name: &str) -> bool {
+    let var_name = format!("CARGO_FEATURE_{}", name.to_uppercase().replace('-', "_"));
+    env::var_os(var_name).is_some()
+}
+
+/// Returns C++ wrapper sources of the features for which
+/// `is_enabled` returns false.
+fn excluded_sources<'a>(
+    feature_sources: &'a BTreeMap<String, Vec<String>>,
+    is_enabled: impl Fn(&str) -> bool,
+) -> Vec<&'a String> {
+    feature_sources
+        .iter()
+        .filter(|(feature, _)| !is_enabled(feature))
+        .flat_map(|(_, sources)| sources)
+        .collect()
+}
+
 impl Config {
     /// Constructs default configuration state based on
     /// information in the generated `build_script_data.json` file
@@ -143,9 +165,7 @@ impl Config {
         let profile = env::var("PROFILE").with_context(|_| "PROFILE env var is missing")?;
         info!("Building C++ wrapper library");
 
-        let library_type = cpp_build_config_data
-            .library_type()
-            .ok_or_else(|| err_msg("library type (shared or static) is not set"))?;
+        let library_type = library_type(&cpp_build_config_data)?;
 
         let cmake_config = CMakeConfigData {
             cpp_build_config_data: &cpp_build_config_data,
@@ -154,7 +174,11 @@ impl Config {
             cpp_library_version: self.current_cpp_library_version.clone(),
         };
 
-        let cmake_vars = cmake_config.cmake_vars()?.into_iter().collect();
+        let mut cmake_vars = cmake_config.cmake_vars()?;
+        cmake_vars.push(CMakeVar::new_list(
+            "RITUAL_EXCLUDED_SOURCES",
+            excluded_sources(&self.build_script_data.feature_sources, is_feature_enabled),
+        )?);
         CppLibBuilder {
             cmake_source_dir: manifest_dir.join("c_lib"),
             build_dir: out_dir.join("c_lib_build"),
@@ -249,6 +273,18 @@ impl Config {
     }
 }
 
+/// Returns type of the C++ wrapper library and checks that
+/// it's compatible with the other build options.
+fn library_type(cpp_build_config_data: &CppBuildConfigData) -> Result<CppLibraryType> {
+    let library_type = cpp_build_config_data
+        .library_type()
+        .ok_or_else(|| err_msg("library type (shared or static) is not set"))?;
+    if cpp_build_config_data.dead_code_elimination() && library_type == CppLibraryType::Shared {
+        bail!("dead code elimination is only supported for static C++ wrapper library");
+    }
+    Ok(library_type)
+}
+
 /// Same as `run()`, but result of the operation is returned to the caller.
 pub fn try_run() -> Result<()> {
     Config::new()?.try_run()
@@ -260,3 +296,42 @@ pub fn run() -> ! {
     let config = Config::new().fancy_unwrap();
     config.run()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{excluded_sources, library_type};
+    use ritual_common::cpp_build_config::{CppBuildConfigData, CppLibraryType};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn excluded_sources_of_disabled_features() {
+        let mut feature_sources = BTreeMap::new();
+        feature_sources.insert(
+            "f1".to_string(),
+            vec!["file_f1_1.cpp".to_string(), "file_f1_2.cpp".to_string()],
+        );
+        feature_sources.insert("f2".to_string(), vec!["file_f2_1.cpp".to_string()]);
+        feature_sources.insert("f3".to_string(), vec!["file_f3_1.cpp".to_string()]);
+
+        assert!(excluded_sources(&feature_sources, |_| true).is_empty());
+        assert_eq!(
+            excluded_sources(&feature_sources, |feature| feature == "f2"),
+            vec!["file_f1_1.cpp", "file_f1_2.cpp", "file_f3_1.cpp"]
+        );
+        assert_eq!(excluded_sources(&feature_sources, |_| false).len(), 4);
+    }
+
+    #[test]
+    fn dead_code_elimination_requires_static_library() {
+        let mut data = CppBuildConfigData::new();
+        assert!(library_type(&data).is_err());
+
+        data.set_library_type(CppLibraryType::Shared);
+        assert_eq!(library_type(&data).unwrap(), CppLibraryType::Shared);
+        data.set_dead_code_elimination(true);
+        assert!(library_type(&data).is_err());
+
+        data.set_library_type(CppLibraryType::Static);
+        assert_eq!(library_type(&data).unwrap(), CppLibraryType::Static);
+    }
+}
@@ -52,6 +52,7 @@ pub struct CppBuildConfigData {
     compiler_flags: Vec<String>,
     library_type: Option<CppLibraryType>,
     cmake_vars: Vec<CMakeVar>,
+    dead_code_elimination: bool,
 }
 
 impl CppBuildConfigData {
@@ -96,6 +97,35 @@ impl CppBuildConfigData {
         self.library_type = Some(t);
     }
 
+    /// Enables building the C++ wrapper library in a way that allows the linker
+    /// to remove unused wrapper functions from the final binary.
+    ///
+    /// The wrapper is compiled with `-ffunction-sections -fdata-sections`
+    /// (`/Gy /Gw` on MSVC), so the linker's section garbage collection
+    /// (`--gc-sections`, `-dead_strip` or `/OPT:REF`, which `rustc` enables
+    /// by default) can drop individual functions.
+    ///
+    /// In release builds (`PROFILE=release`), GCC also emits LTO information
+    /// (`-flto -ffat-lto-objects`). Fat LTO objects contain regular object code
+    /// as well, so the library is still usable when the final link performed by `rustc`
+    /// doesn't run GCC's LTO plugin. Clang and MSVC only produce LTO objects that
+    /// require an LTO-aware link, so no LTO flags are added for them.
+    /// Cross-language LTO with Clang can be set up manually by adding `-flto=thin`
+    /// to compiler flags (see `CppBuildConfigData::add_compiler_flag`), building
+    /// the wrapper with the Clang version matching `rustc`'s LLVM and passing
+    /// `-C linker-plugin-lto` to `rustc`.
+    ///
+    /// Only static wrapper libraries can benefit from this mode, so
+    /// the build script reports an error if the library type is shared.
+    pub fn set_dead_code_elimination(&mut self, value: bool) {
+        self.dead_code_elimination = value;
+    }
+
+    /// Returns value set by `CppBuildConfigData::set_dead_code_elimination`.
+    pub fn dead_code_elimination(&self) -> bool {
+        self.dead_code_elimination
+    }
+
     /// Returns names of linked libraries.
     pub fn linked_libs(&self) -> &[String] {
         &self.linked_libs
@@ -129,6 +159,7 @@ impl CppBuildConfigData {
             self.library_type = other.library_type;
         }
         self.cmake_vars.extend_from_slice(&other.cmake_vars);
+        self.dead_code_elimination |= other.dead_code_elimination;
         Ok(())
     }
 
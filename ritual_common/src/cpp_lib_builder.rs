@@ -311,6 +311,14 @@ impl<'a, 'b> CMakeConfigData<'a, 'b> {
             "RITUAL_COMPILER_FLAGS",
             self.cpp_build_config_data.compiler_flags().join(" "),
         ));
+        cmake_vars.push(CMakeVar::new(
+            "RITUAL_DEAD_CODE_ELIMINATION",
+            if self.cpp_build_config_data.dead_code_elimination() {
+                "ON"
+            } else {
+                "OFF"
+            },
+        ));
         cmake_vars.extend_from_slice(self.cpp_build_config_data.cmake_vars());
         Ok(cmake_vars)
     }
@@ -8,6 +8,7 @@
 
 use crate::target::LibraryTarget;
 use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 pub mod cpp_build_config;
 pub mod cpp_lib_builder;
@@ -31,6 +32,9 @@ pub struct BuildScriptData {
     pub cpp_wrapper_lib_name: String,
     /// Environments the generator was used in
     pub known_targets: Vec<LibraryTarget>,
+    /// C++ wrapper source files (relative to the `c_lib` directory)
+    /// that are only compiled if the corresponding Cargo feature is enabled
+    pub feature_sources: BTreeMap<String, Vec<String>>,
 }
 
 #[derive(Debug)]
@@ -0,0 +1,60 @@
+use crate::cpp_build_config::{CppBuildConfig, CppBuildConfigData, CppBuildPaths, CppLibraryType};
+use crate::cpp_lib_builder::{CMakeConfigData, CMakeVar};
+use crate::target::{current_target, Condition};
+
+fn dead_code_elimination_data(value: bool) -> CppBuildConfigData {
+    let mut data = CppBuildConfigData::new();
+    data.set_dead_code_elimination(value);
+    data
+}
+
+#[test]
+fn dead_code_elimination_merge() {
+    let mut config = CppBuildConfig::new();
+    config.add(Condition::True, dead_code_elimination_data(false));
+    assert!(!config
+        .eval(&current_target())
+        .unwrap()
+        .dead_code_elimination());
+
+    config.add(Condition::False, dead_code_elimination_data(true));
+    assert!(!config
+        .eval(&current_target())
+        .unwrap()
+        .dead_code_elimination());
+
+    // Disabled value of a later item doesn't override the enabled one.
+    config.add(Condition::True, dead_code_elimination_data(true));
+    config.add(Condition::True, dead_code_elimination_data(false));
+    assert!(config
+        .eval(&current_target())
+        .unwrap()
+        .dead_code_elimination());
+}
+
+fn cmake_var<'a>(vars: &'a [CMakeVar], name: &str) -> Option<&'a str> {
+    vars.iter()
+        .find(|var| var.name == name)
+        .map(|var| var.value.as_str())
+}
+
+#[test]
+fn dead_code_elimination_cmake_var() {
+    let paths = CppBuildPaths::new();
+    for &(value, expected) in &[(false, "OFF"), (true, "ON")] {
+        let data = dead_code_elimination_data(value);
+        let vars = CMakeConfigData {
+            cpp_build_config_data: &data,
+            cpp_build_paths: &paths,
+            library_type: Some(CppLibraryType::Static),
+            cpp_library_version: None,
+        }
+        .cmake_vars()
+        .unwrap();
+        assert_eq!(
+            cmake_var(&vars, "RITUAL_DEAD_CODE_ELIMINATION"),
+            Some(expected)
+        );
+        assert_eq!(cmake_var(&vars, "RITUAL_LIBRARY_TYPE"), Some("STATIC"));
+    }
+}
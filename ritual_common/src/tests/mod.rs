@@ -1 +1,2 @@
+mod cpp_build_config;
 mod utils;